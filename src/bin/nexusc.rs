@@ -0,0 +1,271 @@
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use nexus_compiler::nexus::compiler::{self, CompilationResult, CompileOptions, ProgramResult};
+use nexus_compiler::nexus::backend::{self, Backend, Artifact};
+use nexus_compiler::nexus::json_output;
+use nexus_compiler::nexus::memory_layout::MemoryLayout;
+use nexus_compiler::util::diagnostic_sink::TerminalSink;
+
+// The usage message, where the targets come from the backend registry
+fn usage() -> String {
+    let names: Vec<&str> = backend::get_backend_names();
+    return format!("Usage: nexusc [options] [input]
+
+Compiles the programs in the input file (or stdin if no input or - is given).
+
+Options:
+  -t, --target <name>  Target to generate code for: {} (default: {})
+  -o, --output <file>  File to write the generated code to (default: stdout)
+                       Multiple programs are written to <file>.1.<ext>, <file>.2.<ext>, ...
+  -e, --executable     Write an executable (such as an ELF file for RISC-V) instead of the code
+                       This needs --output
+  -l, --listing        Write the annotated disassembly (such as for the 6502) instead of the code
+  -m, --memory <layout>
+                       Memory layout for the 6502 as comma-separated size, load, heap, and static addresses
+                       such as size=0x1000,load=0x100 (default: size=0x100,load=0x00,heap=0xFE)
+      --ir             Write the three-address code that the target generates its code from instead of the code
+      --multi-digit    Let integer literals have more than 1 digit and integers use the full width of the target
+                       (8 bits for the 6502 and 64 bits for RISC-V) instead of a byte
+  -v, --verbose        Print the info and debug logs for every phase instead of only the warnings and errors
+      --json           Write the full result for every program as JSON instead of the code
+  -h, --help           Print this message", names.join(", "), names[0]);
+}
+
+// The options given on the command line
+struct CliOptions {
+    target: Box<dyn Backend>,
+    memory_layout: MemoryLayout,
+    multi_digit_integers: bool,
+    input: Option<String>,
+    output: Option<String>,
+    verbose: bool,
+    json: bool,
+    executable: bool,
+    listing: bool,
+    ir: bool
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let options: CliOptions = match parse_args(&args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            // Help was requested
+            println!("{}", usage());
+            return ExitCode::SUCCESS;
+        },
+        Err(msg) => {
+            eprintln!("nexusc: {}\n\n{}", msg, usage());
+            return ExitCode::from(2);
+        }
+    };
+
+    // Read the source code from the file or stdin
+    let source_res: io::Result<String> = match &options.input {
+        Some(path) => std::fs::read_to_string(path),
+        None => {
+            let mut source: String = String::new();
+            io::stdin().read_to_string(&mut source).map(|_| source)
+        }
+    };
+
+    let source_code: String = match source_res {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("nexusc: unable to read {}: {}", options.input.as_deref().unwrap_or("stdin"), e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let compile_options: CompileOptions = CompileOptions {
+        memory_layout: options.memory_layout,
+        multi_digit_integers: options.multi_digit_integers,
+        ..CompileOptions::new(options.target.name())
+    };
+    let result: CompilationResult = match compiler::compile(&source_code, &compile_options) {
+        Ok(result) => result,
+        Err(msg) => {
+            eprintln!("nexusc: {}", msg);
+            return ExitCode::from(2);
+        }
+    };
+
+    // Print the logs now that the compilation is done
+    // Errors and warnings are shown in the source code where they were logged, so they stay with their program
+    let mut sink: TerminalSink = TerminalSink::new(options.verbose).with_source_code(&source_code);
+    for entry in result.logs.iter() {
+        entry.replay(&mut sink);
+    }
+
+    // The JSON document already has the success of each program in it
+    if options.json {
+        let json_doc: String = json_output::compilation_to_json(&result, options.target.name()).to_string();
+        let write_res: io::Result<()> = match &options.output {
+            Some(path) => std::fs::write(path, format!("{}\n", json_doc)),
+            None => writeln!(io::stdout(), "{}", json_doc)
+        };
+        if let Err(e) = write_res {
+            eprintln!("nexusc: unable to write the output: {}", e);
+            return ExitCode::from(2);
+        }
+        if result.programs.is_empty() || !result.is_ok() {
+            return ExitCode::FAILURE;
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    // Not every backend has a listing because some already generate readable code
+    if options.listing && result.programs.iter().any(|program| program.is_ok() && program.listing.is_none()) {
+        eprintln!("nexusc: the {} target does not have a listing", options.target.name());
+        return ExitCode::from(2);
+    }
+
+    if let Err(e) = write_outputs(&result.programs, &options) {
+        eprintln!("nexusc: unable to write the output: {}", e);
+        return ExitCode::from(2);
+    }
+
+    // Fail if there were no programs or any of them did not compile
+    if result.programs.is_empty() || !result.is_ok() {
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
+
+// Parses the command line arguments, or returns None if help was requested
+fn parse_args(args: &[String]) -> Result<Option<CliOptions>, String> {
+    let mut options: CliOptions = CliOptions {
+        target: backend::get_backends().remove(0),
+        memory_layout: MemoryLayout::default(),
+        multi_digit_integers: false,
+        input: None,
+        output: None,
+        verbose: false,
+        json: false,
+        executable: false,
+        listing: false,
+        ir: false
+    };
+
+    let mut i: usize = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-v" | "--verbose" => options.verbose = true,
+            "--json" => options.json = true,
+            "-e" | "--executable" => options.executable = true,
+            "-l" | "--listing" => options.listing = true,
+            "--ir" => options.ir = true,
+            "--multi-digit" => options.multi_digit_integers = true,
+            "-t" | "--target" => {
+                i += 1;
+                let name: &String = args.get(i).ok_or(format!("missing value for {}", args[i - 1]))?;
+                options.target = backend::get_backend(name).ok_or(format!("unknown target '{}'", name))?;
+            },
+            "-m" | "--memory" => {
+                i += 1;
+                let layout: &String = args.get(i).ok_or(format!("missing value for {}", args[i - 1]))?;
+                options.memory_layout = MemoryLayout::parse(layout).map_err(|msg| format!("invalid memory layout '{}': {}", layout, msg))?;
+            },
+            "-o" | "--output" => {
+                i += 1;
+                let path: &String = args.get(i).ok_or(format!("missing value for {}", args[i - 1]))?;
+                options.output = Some(path.to_owned());
+            },
+            "-" => options.input = None,
+            arg => {
+                if arg.starts_with('-') {
+                    return Err(format!("unknown option '{}'", arg));
+                } else if options.input.is_some() {
+                    return Err(format!("unexpected argument '{}'", arg));
+                }
+                options.input = Some(String::from(arg));
+            }
+        }
+        i += 1;
+    }
+
+    // Executables are binary, so they are not written to the terminal
+    if options.executable && options.output.is_none() {
+        return Err(String::from("--executable needs an output file"));
+    }
+    // Only 1 kind of output can be written
+    let num_outputs: usize = [options.executable, options.listing, options.ir].iter().filter(|is_set| **is_set).count();
+    if num_outputs > 1 {
+        return Err(String::from("only one of --executable, --listing, and --ir can be used"));
+    }
+
+    return Ok(Some(options));
+}
+
+// Gets the text that is written for a program, which is the code, the listing, or the IR
+fn get_text(program: &ProgramResult, code: &Artifact, options: &CliOptions) -> String {
+    if options.listing {
+        return program.listing.to_owned().unwrap_or_default();
+    }
+    if options.ir {
+        return program.ir.as_ref().map(|ir| ir.create_text()).unwrap_or_default();
+    }
+    return code.to_string();
+}
+
+// Writes the code for every successful program
+fn write_outputs(programs: &[ProgramResult], options: &CliOptions) -> io::Result<()> {
+    match &options.output {
+        None => {
+            let mut stdout: io::StdoutLock = io::stdout().lock();
+            for program in programs.iter() {
+                if let Some(code) = &program.code {
+                    writeln!(stdout, "{}", get_text(program, code, options).trim_end())?;
+                    writeln!(stdout)?;
+                }
+            }
+        },
+        Some(path) => {
+            for (i, program) in programs.iter().enumerate() {
+                if let Some(code) = &program.code {
+                    // Only number the files if there is more than 1 program
+                    let default_ext: &str = if options.executable { options.target.executable_extension() } else { options.target.file_extension() };
+                    let out_path: PathBuf = if programs.len() == 1 {
+                        PathBuf::from(path)
+                    } else {
+                        numbered_path(Path::new(path), i + 1, default_ext)
+                    };
+
+                    if options.executable {
+                        let executable: Vec<u8> = options.target.create_executable(code)
+                            .map_err(|msg| io::Error::other(format!("unable to create the executable for program {}: {}", i + 1, msg)))?;
+                        std::fs::write(&out_path, executable)?;
+                        make_executable(&out_path)?;
+                    } else {
+                        std::fs::write(&out_path, format!("{}\n", get_text(program, code, options).trim_end()))?;
+                    }
+                }
+            }
+        }
+    }
+    return Ok(());
+}
+
+// Lets the file be run directly
+#[cfg(unix)]
+fn make_executable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    return std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755));
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> io::Result<()> {
+    return Ok(());
+}
+
+// Creates <stem>.<number>.<ext> for a program in a multi-program file
+fn numbered_path(path: &Path, program_number: usize, default_ext: &str) -> PathBuf {
+    let ext: String = path.extension()
+        .map(|ext| ext.to_string_lossy().into_owned())
+        .unwrap_or(String::from(default_ext));
+    return path.with_extension(format!("{}.{}", program_number, ext));
+}
@@ -1,8 +1,8 @@
 use wasm_bindgen::{prelude::Closure, JsCast};
 use web_sys::{Window, Document, HtmlElement, Event, Element, DomTokenList, HtmlInputElement};
 
-use crate::{nexus::{compiler, syntax_tree::SyntaxTree, code_generator_6502::CodeGenerator6502}, util::nexus_log};
-use crate::util::target::Target;
+use crate::{nexus::{syntax_tree::SyntaxTree, backend::{self, Backend}}, util::nexus_log};
+use crate::editor::display;
 
 use wasm_bindgen::prelude::*;
 
@@ -16,6 +16,9 @@ extern "C" {
 
 // Function used to set up all interactive elements in the webpage
 pub fn set_up_buttons(document: &Document) {    
+    // The target buttons come from the backends that are available
+    create_target_buttons(document);
+
     // Grab the compile button
     let compile_btn: Element = document
         .get_element_by_id("compile-btn")
@@ -23,7 +26,7 @@ pub fn set_up_buttons(document: &Document) {
 
     // Create a function that will be used as the event listener and add it to the compile button
     let compile_btn_fn: Closure<dyn FnMut()> = Closure::wrap(Box::new(move || {
-        compiler::compile(&get_code_input());
+        display::compile_and_display(&get_code_input(), &get_current_target(), is_multi_digit_checked());
     }) as Box<dyn FnMut()>);
 
     compile_btn.add_event_listener_with_callback("click", compile_btn_fn.as_ref().unchecked_ref()).expect("Should be able to add the event listener");
@@ -38,7 +41,9 @@ pub fn set_up_buttons(document: &Document) {
     let clear_btn_fn: Closure<dyn FnMut()> = Closure::wrap(Box::new(|| {
         nexus_log::clear_logs();
         SyntaxTree::clear_display();
-        CodeGenerator6502::clear_display();
+        for backend in backend::get_backends() {
+            backend.clear_display();
+        }
     }) as Box<dyn FnMut()>);
 
     clear_btn.add_event_listener_with_callback("click", clear_btn_fn.as_ref().unchecked_ref()).expect("Should be able to add the event listener");
@@ -107,21 +112,71 @@ pub fn set_up_buttons(document: &Document) {
     toggle_log_mode_fn.forget();
 }
 
-// Function to get the current target platform
-pub fn get_current_target() -> Target {
+// Creates a radio button for each backend, where the first one is selected
+fn create_target_buttons(document: &Document) {
+    let target_area: Element = document
+        .get_element_by_id("target-area")
+        .expect("There should be a target-area element");
+
+    for (i, backend) in backend::get_backends().iter().enumerate() {
+        let input_id: String = format!("target-{}", backend.name());
+
+        // <input type="radio" class="target-input btn-check col" name="options-target" id="target-6502" autocomplete="off" checked>
+        let new_input: Element = document.create_element("input").expect("Should be able to create the input element");
+        new_input.set_attribute("type", "radio").expect("Should be able to add the attribute");
+        new_input.set_class_name("target-input btn-check col");
+        new_input.set_attribute("name", "options-target").expect("Should be able to add the attribute");
+        new_input.set_id(input_id.as_str());
+        new_input.set_attribute("autocomplete", "off").expect("Should be able to add the attribute");
+        if i == 0 {
+            new_input.set_attribute("checked", "").expect("Should be able to add the attribute");
+        }
+
+        // <label class="btn target-btn" for="target-6502">6502</label>
+        let new_label: Element = document.create_element("label").expect("Should be able to create the label element");
+        new_label.set_class_name("btn target-btn");
+        new_label.set_attribute("for", input_id.as_str()).expect("Should be able to add the attribute");
+        new_label.set_inner_html(backend.display_name());
+
+        target_area.append_child(&new_input).expect("Should be able to add the child");
+        target_area.append_child(&new_label).expect("Should be able to add the child");
+    }
+}
+
+// Function to get the name of the current target platform
+pub fn get_current_target() -> String {
     let window: Window = web_sys::window().expect("Should be able to get the window");
     let document: Document = window.document().expect("Should be able to get the document");
 
-    let target_6502: HtmlInputElement = document
-        .get_element_by_id("target-6502")
-        .expect("Should be able to get the element")
+    let backends: Vec<Box<dyn Backend>> = backend::get_backends();
+
+    // Find the backend whose button is checked
+    for backend in backends.iter() {
+        let target_input: HtmlInputElement = document
+            .get_element_by_id(format!("target-{}", backend.name()).as_str())
+            .expect("Should be able to get the element")
+            .dyn_into::<HtmlInputElement>()
+            .expect("The element should be recognized as an input element");
+
+        if target_input.checked() {
+            return String::from(backend.name());
+        }
+    }
+
+    // Fall back to the default target
+    return String::from(backends[0].name());
+}
+
+// Function to check if the multi-digit integer extension is turned on
+pub fn is_multi_digit_checked() -> bool {
+    let window: Window = web_sys::window().expect("Should be able to get the window");
+    let document: Document = window.document().expect("Should be able to get the document");
+
+    let multi_digit_input: HtmlInputElement = document
+        .get_element_by_id("multi-digit-input")
+        .expect("There should be a multi-digit-input element")
         .dyn_into::<HtmlInputElement>()
         .expect("The element should be recognized as an input element");
 
-    // Return the appropriate enum
-    if target_6502.checked() {
-        return Target::Target6502;
-    } else {
-        return Target::TargetRiscV;
-    }
+    return multi_digit_input.checked();
 }
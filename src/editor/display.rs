@@ -0,0 +1,67 @@
+use crate::nexus::compiler::{self, CompilationResult, CompileOptions, ProgramResult};
+use crate::nexus::{syntax_tree::SyntaxTree, backend::{self, Backend}};
+use crate::util::{nexus_log, diagnostic_sink::DomSink};
+
+// Function to compile the code and render everything on the webpage
+pub fn compile_and_display(source_code: &str, target: &str, multi_digit_integers: bool) {
+    let options: CompileOptions = CompileOptions {
+        multi_digit_integers,
+        ..CompileOptions::new(target)
+    };
+    let compile_res: Result<CompilationResult, String> = compiler::compile(source_code, &options);
+
+    // Clean up the output area
+    SyntaxTree::clear_display();
+    for backend in backend::get_backends() {
+        backend.clear_display();
+    }
+    nexus_log::clear_logs();
+
+    let mut result: CompilationResult = match compile_res {
+        Ok(result) => result,
+        Err(msg) => {
+            // Nothing was compiled, so the reason is the only thing to show
+            nexus_log::log(
+                nexus_log::LogTypes::Error,
+                nexus_log::LogSources::Nexus,
+                msg
+            );
+            return;
+        }
+    };
+
+    // Show the logs from the compilation
+    let mut dom_sink: DomSink = DomSink;
+    for entry in result.logs.iter() {
+        entry.replay(&mut dom_sink);
+    }
+
+    // The target was already checked by the compiler
+    let backend: Box<dyn Backend> = backend::get_backend(target).expect("The target should have a backend");
+
+    // Show the trees, symbol table, and code for each program
+    for program in result.programs.iter_mut() {
+        display_program(program, backend.as_ref());
+    }
+}
+
+fn display_program(program: &mut ProgramResult, backend: &dyn Backend) {
+    if program.cst.is_some() {
+        program.cst.as_ref().unwrap().display(&program.program_number);
+    }
+
+    // The symbol table goes in the AST pane, so the AST has to be displayed first
+    if program.ast.is_some() {
+        program.ast.as_ref().unwrap().create_syntax_tree().display(&program.program_number);
+    }
+
+    // The symbol table is only present if semantic analysis succeeded
+    if program.symbol_table.is_some() {
+        program.symbol_table.as_mut().unwrap().display_symbol_table(&program.program_number);
+    }
+
+    // Each backend knows how its code should be shown
+    if program.code.is_some() {
+        backend.display_code(program.code.as_ref().unwrap(), program.listing.as_deref(), &program.program_number);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod buttons;
-pub mod tests;
\ No newline at end of file
+pub mod display;
+pub mod tests;
@@ -2,11 +2,12 @@ use wasm_bindgen::prelude::*;
 use log::*;
 use web_sys::{Window, Document};
 
-mod nexus;
-mod util;
+pub mod nexus;
+pub mod util;
 mod editor;
 
 use editor::*;
+use nexus::{compiler::{self, CompileOptions}, json_output, backend};
 
 // Function to initialize Nexus
 #[wasm_bindgen]
@@ -25,3 +26,28 @@ pub fn nexus_init() {
 
     info!("Nexus initialized");
 }
+
+// Function to compile without the webpage, such as from Node or an editor extension
+// Takes the source code, target name (such as 6502 or riscv), and whether to use the multi-digit integer extension
+// and returns a JSON document with the result for each program
+#[wasm_bindgen]
+pub fn nexus_compile_json(source_code: &str, target_name: &str, multi_digit_integers: bool) -> String {
+    let options: CompileOptions = CompileOptions {
+        multi_digit_integers,
+        ..CompileOptions::new(target_name)
+    };
+    match compiler::compile(source_code, &options) {
+        // The target is shown by its name even if an alias was given
+        Ok(result) => {
+            let target: &str = backend::get_backend(target_name).unwrap().name();
+            return json_output::compilation_to_json(&result, target).to_string();
+        },
+        Err(msg) => return json_output::error_to_json(&msg).to_string()
+    }
+}
+
+// Gets the names of the targets that can be given to nexus_compile_json as a JSON array
+#[wasm_bindgen]
+pub fn nexus_targets_json() -> String {
+    return serde_json::json!(backend::get_backend_names()).to_string();
+}
@@ -0,0 +1,197 @@
+use crate::nexus::diagnostic::Span;
+use crate::nexus::symbol_table::Type;
+use crate::nexus::token::{Token, TokenType, Keywords};
+use crate::nexus::syntax_tree::{SyntaxTree, SyntaxTreeTypes};
+use crate::nexus::syntax_tree_node::{SyntaxTreeNode, NonTerminalsAst, SyntaxTreeNodeTypes};
+
+// The typed AST that semantic analysis and code generation work with
+// The petgraph SyntaxTree is only created from it for displaying the tree
+
+// A block of statements, which is its own scope
+// The program itself is a block
+#[derive (Debug, Clone, PartialEq)]
+pub struct Block {
+    pub statements: Vec<Stmt>,
+    // The scope the block creates in the symbol table, which are numbered in the order the blocks are found
+    pub scope: usize,
+    // From the left brace to the right brace
+    pub span: Span
+}
+
+// The valid statements in a block
+#[derive (Debug, Clone, PartialEq)]
+pub enum Stmt {
+    Block(Block),
+    VarDecl(VarDecl),
+    Assign(Assign),
+    Print(Print),
+    While(While),
+    If(If)
+}
+
+// A declaration such as int a
+#[derive (Debug, Clone, PartialEq)]
+pub struct VarDecl {
+    pub var_type: Type,
+    pub id: Ident,
+    // From the type keyword to the id
+    pub span: Span
+}
+
+// An assignment such as a = 1 + 2
+#[derive (Debug, Clone, PartialEq)]
+pub struct Assign {
+    pub id: Ident,
+    pub value: Expr,
+    // From the id being assigned to through the value
+    pub span: Span
+}
+
+#[derive (Debug, Clone, PartialEq)]
+pub struct Print {
+    pub expr: Expr,
+    // From the print keyword to the right paren
+    pub span: Span
+}
+
+#[derive (Debug, Clone, PartialEq)]
+pub struct While {
+    pub condition: Expr,
+    pub body: Block,
+    // From the while keyword to the end of the body
+    pub span: Span
+}
+
+#[derive (Debug, Clone, PartialEq)]
+pub struct If {
+    pub condition: Expr,
+    pub body: Block,
+    // From the if keyword to the end of the body
+    pub span: Span
+}
+
+// A use of an identifier
+#[derive (Debug, Clone, PartialEq)]
+pub struct Ident {
+    pub name: String,
+    pub span: Span
+}
+
+// The valid expressions
+#[derive (Debug, Clone, PartialEq)]
+pub enum Expr {
+    IntLiteral { value: u64, span: Span },
+    StringLiteral { value: String, span: Span },
+    BoolLiteral { value: bool, span: Span },
+    Id(Ident),
+    // Comparisons include their parentheses, and additions go from the digit to the end of the right side
+    Add { left: Box<Expr>, right: Box<Expr>, span: Span },
+    IsEq { left: Box<Expr>, right: Box<Expr>, span: Span },
+    NotEq { left: Box<Expr>, right: Box<Expr>, span: Span }
+}
+
+impl Expr {
+    // Gets the span of the whole expression
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::IntLiteral { span, .. } => return *span,
+            Expr::StringLiteral { span, .. } => return *span,
+            Expr::BoolLiteral { span, .. } => return *span,
+            Expr::Id(id) => return id.span,
+            Expr::Add { span, .. } => return *span,
+            Expr::IsEq { span, .. } => return *span,
+            Expr::NotEq { span, .. } => return *span
+        }
+    }
+}
+
+impl Block {
+    // Creates the graph form of the AST for the tree display
+    pub fn create_syntax_tree(&self) -> SyntaxTree {
+        let mut ast: SyntaxTree = SyntaxTree::new(SyntaxTreeTypes::Ast);
+        add_block(&mut ast, self);
+        return ast;
+    }
+}
+
+fn add_block(ast: &mut SyntaxTree, block: &Block) {
+    ast.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalAst(NonTerminalsAst::Block));
+    for stmt in block.statements.iter() {
+        add_stmt(ast, stmt);
+    }
+    ast.move_up();
+}
+
+fn add_stmt(ast: &mut SyntaxTree, stmt: &Stmt) {
+    match stmt {
+        Stmt::Block(block) => add_block(ast, block),
+        Stmt::VarDecl(var_decl) => {
+            ast.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalAst(NonTerminalsAst::VarDecl));
+            add_leaf(ast, type_token(&var_decl.var_type, var_decl.span));
+            add_leaf(ast, id_token(&var_decl.id));
+            ast.move_up();
+        },
+        Stmt::Assign(assign) => {
+            ast.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalAst(NonTerminalsAst::Assign));
+            add_leaf(ast, id_token(&assign.id));
+            add_expr(ast, &assign.value);
+            ast.move_up();
+        },
+        Stmt::Print(print) => {
+            ast.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalAst(NonTerminalsAst::Print));
+            add_expr(ast, &print.expr);
+            ast.move_up();
+        },
+        Stmt::While(while_stmt) => {
+            ast.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalAst(NonTerminalsAst::While));
+            add_expr(ast, &while_stmt.condition);
+            add_block(ast, &while_stmt.body);
+            ast.move_up();
+        },
+        Stmt::If(if_stmt) => {
+            ast.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalAst(NonTerminalsAst::If));
+            add_expr(ast, &if_stmt.condition);
+            add_block(ast, &if_stmt.body);
+            ast.move_up();
+        }
+    }
+}
+
+fn add_expr(ast: &mut SyntaxTree, expr: &Expr) {
+    match expr {
+        Expr::IntLiteral { value, span } => add_leaf(ast, Token::new(TokenType::Digit(*value), value.to_string(), *span)),
+        Expr::StringLiteral { value, span } => add_leaf(ast, Token::new(TokenType::Char(value.to_owned()), value.to_owned(), *span)),
+        Expr::BoolLiteral { value, span } => {
+            let keyword: Keywords = if *value { Keywords::True } else { Keywords::False };
+            add_leaf(ast, Token::new(TokenType::Keyword(keyword), value.to_string(), *span));
+        },
+        Expr::Id(id) => add_leaf(ast, id_token(id)),
+        Expr::Add { left, right, .. } => add_binary(ast, NonTerminalsAst::Add, left, right),
+        Expr::IsEq { left, right, .. } => add_binary(ast, NonTerminalsAst::IsEq, left, right),
+        Expr::NotEq { left, right, .. } => add_binary(ast, NonTerminalsAst::NotEq, left, right)
+    }
+}
+
+fn add_binary(ast: &mut SyntaxTree, operator: NonTerminalsAst, left: &Expr, right: &Expr) {
+    ast.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalAst(operator));
+    add_expr(ast, left);
+    add_expr(ast, right);
+    ast.move_up();
+}
+
+fn add_leaf(ast: &mut SyntaxTree, token: Token) {
+    ast.add_node(SyntaxTreeNodeTypes::Leaf, SyntaxTreeNode::Terminal(token));
+}
+
+fn id_token(id: &Ident) -> Token {
+    return Token::new(TokenType::Identifier(id.name.to_owned()), id.name.to_owned(), id.span);
+}
+
+fn type_token(var_type: &Type, span: Span) -> Token {
+    let (keyword, text): (Keywords, &str) = match var_type {
+        Type::Int => (Keywords::Int, "int"),
+        Type::String => (Keywords::String, "string"),
+        Type::Boolean => (Keywords::Boolean, "boolean")
+    };
+    return Token::new(TokenType::Keyword(keyword), String::from(text), span);
+}
@@ -0,0 +1,139 @@
+use crate::nexus::ast::{Block, Stmt, VarDecl, Assign, Print, While, If, Ident, Expr};
+
+// Traversal that takes ownership of the typed AST and builds a new one, such as for optimizations
+// Every method defaults to rebuilding the node from its folded children, so a pass only has to
+// override the nodes it wants to change and can call the matching walk function to keep going
+// The context is passed along to every method, just like for the Visitor
+pub trait Folder<C = ()> {
+    // Called before the statements of a block with the scope the block creates
+    fn enter_scope(&mut self, _scope: usize, _context: &mut C) {}
+
+    // Called after the statements of a block with the scope that is ending
+    fn exit_scope(&mut self, _scope: usize, _context: &mut C) {}
+
+    fn fold_block(&mut self, block: Block, context: &mut C) -> Block {
+        return walk_block(self, block, context);
+    }
+
+    // Returning None removes the statement from its block
+    // Blocks keep their scope number, so removing one does not change the scopes of the others
+    fn fold_stmt(&mut self, stmt: Stmt, context: &mut C) -> Option<Stmt> {
+        return walk_stmt(self, stmt, context);
+    }
+
+    fn fold_var_decl(&mut self, var_decl: VarDecl, context: &mut C) -> VarDecl {
+        return walk_var_decl(self, var_decl, context);
+    }
+
+    fn fold_assign(&mut self, assign: Assign, context: &mut C) -> Assign {
+        return walk_assign(self, assign, context);
+    }
+
+    fn fold_print(&mut self, print: Print, context: &mut C) -> Print {
+        return walk_print(self, print, context);
+    }
+
+    fn fold_while(&mut self, while_stmt: While, context: &mut C) -> While {
+        return walk_while(self, while_stmt, context);
+    }
+
+    fn fold_if(&mut self, if_stmt: If, context: &mut C) -> If {
+        return walk_if(self, if_stmt, context);
+    }
+
+    fn fold_expr(&mut self, expr: Expr, context: &mut C) -> Expr {
+        return walk_expr(self, expr, context);
+    }
+
+    // Identifiers do not have any children
+    fn fold_ident(&mut self, id: Ident, _context: &mut C) -> Ident {
+        return id;
+    }
+}
+
+// Enters the scope of the block, folds each statement, and then exits the scope
+pub fn walk_block<C, F: Folder<C> + ?Sized>(folder: &mut F, block: Block, context: &mut C) -> Block {
+    folder.enter_scope(block.scope, context);
+    let statements: Vec<Stmt> = block.statements.into_iter()
+        .filter_map(|stmt| folder.fold_stmt(stmt, context))
+        .collect();
+    folder.exit_scope(block.scope, context);
+
+    return Block {
+        statements,
+        ..block
+    };
+}
+
+// Sends the statement to the method for its kind
+pub fn walk_stmt<C, F: Folder<C> + ?Sized>(folder: &mut F, stmt: Stmt, context: &mut C) -> Option<Stmt> {
+    match stmt {
+        Stmt::Block(block) => return Some(Stmt::Block(folder.fold_block(block, context))),
+        Stmt::VarDecl(var_decl) => return Some(Stmt::VarDecl(folder.fold_var_decl(var_decl, context))),
+        Stmt::Assign(assign) => return Some(Stmt::Assign(folder.fold_assign(assign, context))),
+        Stmt::Print(print) => return Some(Stmt::Print(folder.fold_print(print, context))),
+        Stmt::While(while_stmt) => return Some(Stmt::While(folder.fold_while(while_stmt, context))),
+        Stmt::If(if_stmt) => return Some(Stmt::If(folder.fold_if(if_stmt, context)))
+    }
+}
+
+pub fn walk_var_decl<C, F: Folder<C> + ?Sized>(folder: &mut F, var_decl: VarDecl, context: &mut C) -> VarDecl {
+    return VarDecl {
+        id: folder.fold_ident(var_decl.id, context),
+        ..var_decl
+    };
+}
+
+pub fn walk_assign<C, F: Folder<C> + ?Sized>(folder: &mut F, assign: Assign, context: &mut C) -> Assign {
+    return Assign {
+        id: folder.fold_ident(assign.id, context),
+        value: folder.fold_expr(assign.value, context),
+        span: assign.span
+    };
+}
+
+pub fn walk_print<C, F: Folder<C> + ?Sized>(folder: &mut F, print: Print, context: &mut C) -> Print {
+    return Print {
+        expr: folder.fold_expr(print.expr, context),
+        span: print.span
+    };
+}
+
+pub fn walk_while<C, F: Folder<C> + ?Sized>(folder: &mut F, while_stmt: While, context: &mut C) -> While {
+    return While {
+        condition: folder.fold_expr(while_stmt.condition, context),
+        body: folder.fold_block(while_stmt.body, context),
+        span: while_stmt.span
+    };
+}
+
+pub fn walk_if<C, F: Folder<C> + ?Sized>(folder: &mut F, if_stmt: If, context: &mut C) -> If {
+    return If {
+        condition: folder.fold_expr(if_stmt.condition, context),
+        body: folder.fold_block(if_stmt.body, context),
+        span: if_stmt.span
+    };
+}
+
+// Folds the ids and sides of binary expressions, where literals are kept as they are
+pub fn walk_expr<C, F: Folder<C> + ?Sized>(folder: &mut F, expr: Expr, context: &mut C) -> Expr {
+    match expr {
+        Expr::IntLiteral { .. } | Expr::StringLiteral { .. } | Expr::BoolLiteral { .. } => return expr,
+        Expr::Id(id) => return Expr::Id(folder.fold_ident(id, context)),
+        Expr::Add { left, right, span } => return Expr::Add {
+            left: Box::new(folder.fold_expr(*left, context)),
+            right: Box::new(folder.fold_expr(*right, context)),
+            span
+        },
+        Expr::IsEq { left, right, span } => return Expr::IsEq {
+            left: Box::new(folder.fold_expr(*left, context)),
+            right: Box::new(folder.fold_expr(*right, context)),
+            span
+        },
+        Expr::NotEq { left, right, span } => return Expr::NotEq {
+            left: Box::new(folder.fold_expr(*left, context)),
+            right: Box::new(folder.fold_expr(*right, context)),
+            span
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use crate::nexus::token::{Token, TokenType, Symbols, Keywords};
+use crate::nexus::syntax_tree::SyntaxTree;
+use crate::nexus::syntax_tree_node::{SyntaxTreeNode, NonTerminalsCst};
+use crate::nexus::symbol_table::Type;
+use crate::nexus::diagnostic::Span;
+use crate::nexus::ast::{Block, Stmt, VarDecl, Assign, Print, While, If, Ident, Expr};
+
+use petgraph::graph::NodeIndex;
+
+use string_builder::Builder;
+
+// Lowers the CST from the parser into the typed AST
+// The grammar only lives in the parser, so this only has to know the shape of each CST node
+pub struct AstGenerator {
+    // The scope to give the next block
+    next_scope: usize
+}
+
+impl AstGenerator {
+    // Constructor for the AST generator
+    pub fn new() -> Self {
+        return AstGenerator {
+            next_scope: 0
+        };
+    }
+
+    // Starting function to generate the AST from a successfully parsed CST
+    pub fn generate_ast(&mut self, cst: &SyntaxTree) -> Block {
+        self.next_scope = 0;
+
+        // Program is Block followed by $, so the AST starts at the block
+        let program_children: Vec<NodeIndex> = self.get_children(cst, NodeIndex::new(cst.root.unwrap()));
+        return self.lower_block(cst, program_children[0]);
+    }
+
+    // Gets the children of a node in the order they appear in the source code
+    fn get_children(&self, cst: &SyntaxTree, cur_index: NodeIndex) -> Vec<NodeIndex> {
+        // Neighbors are stored in reverse order
+        let mut children: Vec<NodeIndex> = cst.graph.neighbors(cur_index).collect();
+        children.reverse();
+        return children;
+    }
+
+    fn get_node<'a>(&self, cst: &'a SyntaxTree, cur_index: NodeIndex) -> &'a SyntaxTreeNode {
+        return cst.graph.node_weight(cur_index).unwrap();
+    }
+
+    // Gets the token of a terminal node
+    fn get_token(&self, cst: &SyntaxTree, cur_index: NodeIndex) -> Token {
+        match self.get_node(cst, cur_index) {
+            SyntaxTreeNode::Terminal(token) => return token.to_owned(),
+            node => panic!("Received {:?} in the CST when expecting a terminal", node)
+        }
+    }
+
+    // Gets the token under a nonterminal that only wraps a terminal, such as <Id> or <Digit>
+    fn get_wrapped_token(&self, cst: &SyntaxTree, cur_index: NodeIndex) -> Token {
+        let children: Vec<NodeIndex> = self.get_children(cst, cur_index);
+        return self.get_token(cst, children[0]);
+    }
+
+    fn lower_block(&mut self, cst: &SyntaxTree, cur_index: NodeIndex) -> Block {
+        // Block is { StatementList }, where the statement list is missing if the block is empty
+        let children: Vec<NodeIndex> = self.get_children(cst, cur_index);
+        let lbrace: Token = self.get_token(cst, children[0]);
+        let rbrace: Token = self.get_token(cst, children[children.len() - 1]);
+
+        // The block gets its scope before the inner blocks to match the order of the symbol table
+        let scope: usize = self.next_scope;
+        self.next_scope += 1;
+
+        let mut statements: Vec<Stmt> = Vec::new();
+        if children.len() == 3 {
+            self.lower_statement_list(cst, children[1], &mut statements);
+        }
+
+        return Block {
+            statements,
+            scope,
+            span: lbrace.span.to(rbrace.span)
+        };
+    }
+
+    fn lower_statement_list(&mut self, cst: &SyntaxTree, cur_index: NodeIndex, statements: &mut Vec<Stmt>) {
+        // StatementList is Statement StatementList, so the statements get flattened into the block
+        for child in self.get_children(cst, cur_index) {
+            match self.get_node(cst, child) {
+                SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::Statement) => {
+                    let statement: Stmt = self.lower_statement(cst, child);
+                    statements.push(statement);
+                },
+                SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::StatementList) => self.lower_statement_list(cst, child, statements),
+                node => panic!("Received {:?} as part of a StatementList; Expected Statement or StatementList", node)
+            }
+        }
+    }
+
+    fn lower_statement(&mut self, cst: &SyntaxTree, cur_index: NodeIndex) -> Stmt {
+        // A statement only has the one child for the type of statement
+        let statement_index: NodeIndex = self.get_children(cst, cur_index)[0];
+        let children: Vec<NodeIndex> = self.get_children(cst, statement_index);
+
+        match self.get_node(cst, statement_index) {
+            SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::PrintStatement) => {
+                // print ( Expr )
+                return Stmt::Print(Print {
+                    expr: self.lower_expression(cst, children[2]),
+                    span: self.get_token(cst, children[0]).span.to(self.get_token(cst, children[3]).span)
+                });
+            },
+            SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::AssignmentStatement) => {
+                // Id = Expr
+                let id: Ident = self.lower_identifier(cst, children[0]);
+                let value: Expr = self.lower_expression(cst, children[2]);
+                return Stmt::Assign(Assign {
+                    span: id.span.to(value.span()),
+                    id,
+                    value
+                });
+            },
+            SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::VarDecl) => {
+                // Type Id
+                let type_token: Token = self.get_wrapped_token(cst, children[0]);
+                let var_type: Type = match type_token.token_type {
+                    TokenType::Keyword(Keywords::Int) => Type::Int,
+                    TokenType::Keyword(Keywords::String) => Type::String,
+                    TokenType::Keyword(Keywords::Boolean) => Type::Boolean,
+                    _ => panic!("Received {:?} at {:?}; Expected int, string, or boolean", type_token.token_type, type_token.position())
+                };
+
+                let id: Ident = self.lower_identifier(cst, children[1]);
+                return Stmt::VarDecl(VarDecl {
+                    var_type,
+                    span: type_token.span.to(id.span),
+                    id
+                });
+            },
+            SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::WhileStatement) => {
+                // while BooleanExpr Block
+                let body: Block = self.lower_block(cst, children[2]);
+                return Stmt::While(While {
+                    condition: self.lower_bool_expression(cst, children[1]),
+                    span: self.get_token(cst, children[0]).span.to(body.span),
+                    body
+                });
+            },
+            SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::IfStatement) => {
+                // if BooleanExpr Block
+                let body: Block = self.lower_block(cst, children[2]);
+                return Stmt::If(If {
+                    condition: self.lower_bool_expression(cst, children[1]),
+                    span: self.get_token(cst, children[0]).span.to(body.span),
+                    body
+                });
+            },
+            SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::Block) => return Stmt::Block(self.lower_block(cst, statement_index)),
+            node => panic!("Received {:?} as a statement; Expected PrintStatement, AssignmentStatement, VarDecl, WhileStatement, IfStatement, or Block", node)
+        }
+    }
+
+    fn lower_identifier(&mut self, cst: &SyntaxTree, cur_index: NodeIndex) -> Ident {
+        let token: Token = self.get_wrapped_token(cst, cur_index);
+        return Ident {
+            name: token.text,
+            span: token.span
+        };
+    }
+
+    fn lower_expression(&mut self, cst: &SyntaxTree, cur_index: NodeIndex) -> Expr {
+        // An expression only has the one child for the type of expression
+        let expr_index: NodeIndex = self.get_children(cst, cur_index)[0];
+
+        match self.get_node(cst, expr_index) {
+            SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::IntExpr) => return self.lower_int_expression(cst, expr_index),
+            SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::StringExpr) => return self.lower_string_expression(cst, expr_index),
+            SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::BooleanExpr) => return self.lower_bool_expression(cst, expr_index),
+            SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::Id) => return Expr::Id(self.lower_identifier(cst, expr_index)),
+            node => panic!("Received {:?} as an expression; Expected IntExpr, StringExpr, BooleanExpr, or Id", node)
+        }
+    }
+
+    fn lower_int_expression(&mut self, cst: &SyntaxTree, cur_index: NodeIndex) -> Expr {
+        let children: Vec<NodeIndex> = self.get_children(cst, cur_index);
+
+        // Every integer expression starts with a digit
+        let digit_token: Token = self.get_wrapped_token(cst, children[0]);
+        let digit: Expr = match digit_token.token_type {
+            TokenType::Digit(value) => Expr::IntLiteral { value, span: digit_token.span },
+            _ => panic!("Received {:?} at {:?}; Expected a digit", digit_token.token_type, digit_token.position())
+        };
+
+        if children.len() == 3 {
+            // Digit + Expr becomes an addition of the digit and the rest of the expression
+            let right: Expr = self.lower_expression(cst, children[2]);
+            return Expr::Add {
+                span: digit.span().to(right.span()),
+                left: Box::new(digit),
+                right: Box::new(right)
+            };
+        } else {
+            // It is just the digit
+            return digit;
+        }
+    }
+
+    fn lower_string_expression(&mut self, cst: &SyntaxTree, cur_index: NodeIndex) -> Expr {
+        // " CharList ", where the char list is missing if the string is empty
+        let children: Vec<NodeIndex> = self.get_children(cst, cur_index);
+
+        // The string goes from the open quote to the close quote
+        let open_quote: Token = self.get_token(cst, children[0]);
+        let close_quote: Token = self.get_token(cst, children[children.len() - 1]);
+
+        // Build the string from all of the characters
+        let mut str_builder: Builder = Builder::default();
+        if children.len() == 3 {
+            self.collect_char_list(cst, children[1], &mut str_builder);
+        }
+
+        return Expr::StringLiteral {
+            value: str_builder.string().unwrap(),
+            span: open_quote.span.to(close_quote.span)
+        };
+    }
+
+    fn collect_char_list(&mut self, cst: &SyntaxTree, cur_index: NodeIndex, str_builder: &mut Builder) {
+        // CharList is Char CharList or Space CharList
+        for child in self.get_children(cst, cur_index) {
+            match self.get_node(cst, child) {
+                SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::CharList) => self.collect_char_list(cst, child, str_builder),
+                _ => str_builder.append(self.get_wrapped_token(cst, child).text)
+            }
+        }
+    }
+
+    fn lower_bool_expression(&mut self, cst: &SyntaxTree, cur_index: NodeIndex) -> Expr {
+        let children: Vec<NodeIndex> = self.get_children(cst, cur_index);
+
+        if children.len() == 5 {
+            // ( Expr BoolOp Expr )
+            let left: Expr = self.lower_expression(cst, children[1]);
+            let right: Expr = self.lower_expression(cst, children[3]);
+
+            // The span includes the parentheses
+            let span: Span = self.get_token(cst, children[0]).span.to(self.get_token(cst, children[4]).span);
+
+            let bool_op: Token = self.get_wrapped_token(cst, children[2]);
+            match bool_op.token_type {
+                TokenType::Symbol(Symbols::EqOp) => return Expr::IsEq { left: Box::new(left), right: Box::new(right), span },
+                TokenType::Symbol(Symbols::NeqOp) => return Expr::NotEq { left: Box::new(left), right: Box::new(right), span },
+                _ => panic!("Received {:?} at {:?}; Expected == or !=", bool_op.token_type, bool_op.position())
+            }
+        } else {
+            // It is just true or false
+            let bool_val: Token = self.get_wrapped_token(cst, children[0]);
+            return Expr::BoolLiteral {
+                value: bool_val.token_type == TokenType::Keyword(Keywords::True),
+                span: bool_val.span
+            };
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::nexus::ast::{Block, Stmt, VarDecl, Assign, Print, While, If, Ident, Expr};
+
+// Read-only traversal of the typed AST
+// Every method defaults to walking the children of the node, so a pass only has to
+// override the nodes it cares about and can call the matching walk function to keep going
+// The context is passed along to every method, such as the symbol table for the code generators
+pub trait Visitor<C = ()> {
+    // Called before the statements of a block with the scope the block creates
+    fn enter_scope(&mut self, _scope: usize, _context: &mut C) {}
+
+    // Called after the statements of a block with the scope that is ending
+    fn exit_scope(&mut self, _scope: usize, _context: &mut C) {}
+
+    fn visit_block(&mut self, block: &Block, context: &mut C) {
+        walk_block(self, block, context);
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt, context: &mut C) {
+        walk_stmt(self, stmt, context);
+    }
+
+    fn visit_var_decl(&mut self, var_decl: &VarDecl, context: &mut C) {
+        walk_var_decl(self, var_decl, context);
+    }
+
+    fn visit_assign(&mut self, assign: &Assign, context: &mut C) {
+        walk_assign(self, assign, context);
+    }
+
+    fn visit_print(&mut self, print: &Print, context: &mut C) {
+        walk_print(self, print, context);
+    }
+
+    fn visit_while(&mut self, while_stmt: &While, context: &mut C) {
+        walk_while(self, while_stmt, context);
+    }
+
+    fn visit_if(&mut self, if_stmt: &If, context: &mut C) {
+        walk_if(self, if_stmt, context);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, context: &mut C) {
+        walk_expr(self, expr, context);
+    }
+
+    // Identifiers do not have any children
+    fn visit_ident(&mut self, _id: &Ident, _context: &mut C) {}
+}
+
+// Enters the scope of the block, visits each statement, and then exits the scope
+pub fn walk_block<C, V: Visitor<C> + ?Sized>(visitor: &mut V, block: &Block, context: &mut C) {
+    visitor.enter_scope(block.scope, context);
+    for stmt in block.statements.iter() {
+        visitor.visit_stmt(stmt, context);
+    }
+    visitor.exit_scope(block.scope, context);
+}
+
+// Sends the statement to the method for its kind
+pub fn walk_stmt<C, V: Visitor<C> + ?Sized>(visitor: &mut V, stmt: &Stmt, context: &mut C) {
+    match stmt {
+        Stmt::Block(block) => visitor.visit_block(block, context),
+        Stmt::VarDecl(var_decl) => visitor.visit_var_decl(var_decl, context),
+        Stmt::Assign(assign) => visitor.visit_assign(assign, context),
+        Stmt::Print(print) => visitor.visit_print(print, context),
+        Stmt::While(while_stmt) => visitor.visit_while(while_stmt, context),
+        Stmt::If(if_stmt) => visitor.visit_if(if_stmt, context)
+    }
+}
+
+pub fn walk_var_decl<C, V: Visitor<C> + ?Sized>(visitor: &mut V, var_decl: &VarDecl, context: &mut C) {
+    visitor.visit_ident(&var_decl.id, context);
+}
+
+pub fn walk_assign<C, V: Visitor<C> + ?Sized>(visitor: &mut V, assign: &Assign, context: &mut C) {
+    visitor.visit_ident(&assign.id, context);
+    visitor.visit_expr(&assign.value, context);
+}
+
+pub fn walk_print<C, V: Visitor<C> + ?Sized>(visitor: &mut V, print: &Print, context: &mut C) {
+    visitor.visit_expr(&print.expr, context);
+}
+
+pub fn walk_while<C, V: Visitor<C> + ?Sized>(visitor: &mut V, while_stmt: &While, context: &mut C) {
+    visitor.visit_expr(&while_stmt.condition, context);
+    visitor.visit_block(&while_stmt.body, context);
+}
+
+pub fn walk_if<C, V: Visitor<C> + ?Sized>(visitor: &mut V, if_stmt: &If, context: &mut C) {
+    visitor.visit_expr(&if_stmt.condition, context);
+    visitor.visit_block(&if_stmt.body, context);
+}
+
+// Visits the ids and sides of binary expressions, where literals have nothing to visit
+pub fn walk_expr<C, V: Visitor<C> + ?Sized>(visitor: &mut V, expr: &Expr, context: &mut C) {
+    match expr {
+        Expr::IntLiteral { .. } | Expr::StringLiteral { .. } | Expr::BoolLiteral { .. } => {},
+        Expr::Id(id) => visitor.visit_ident(id, context),
+        Expr::Add { left, right, .. } | Expr::IsEq { left, right, .. } | Expr::NotEq { left, right, .. } => {
+            visitor.visit_expr(left, context);
+            visitor.visit_expr(right, context);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::fmt;
+
+use crate::nexus::ir::{self, IrProgram};
+use crate::nexus::diagnostic::PhaseResult;
+use crate::nexus::memory_layout::MemoryLayout;
+use crate::nexus::code_generator_6502::CodeGenerator6502;
+use crate::nexus::code_generator_riscv::CodeGeneratorRiscV;
+
+// The output of a backend for a single program
+#[derive (Debug, Clone, PartialEq)]
+pub enum Artifact {
+    // The bytes of a memory image, such as the 256 bytes for the 6502 by default
+    Image(Vec<u8>),
+    // Assembly code that still has to be assembled
+    Assembly(String)
+}
+
+// Images are shown as hex bytes separated by spaces
+impl fmt::Display for Artifact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Artifact::Image(bytes) => {
+                let hex: Vec<String> = bytes.iter().map(|byte| format!("{:02X}", byte)).collect();
+                write!(f, "{}", hex.join(" "))
+            },
+            Artifact::Assembly(code) => write!(f, "{}", code)
+        }
+    }
+}
+
+// A target that the compiler can generate code for
+pub trait Backend {
+    // The name used to select the backend, such as on the command line
+    fn name(&self) -> &'static str;
+
+    // Other names that also select the backend
+    fn aliases(&self) -> &'static [&'static str] {
+        return &[];
+    }
+
+    // The name shown on the webpage
+    fn display_name(&self) -> &'static str;
+
+    // The file extension for the generated code
+    fn file_extension(&self) -> &'static str;
+
+    // Generates the code from the IR for a program that passed semantic analysis
+    fn generate_code(&mut self, ir: &IrProgram) -> PhaseResult<Artifact>;
+
+    // Creates a file from the generated code that can be run without any other tools
+    fn create_executable(&self, code: &Artifact) -> Result<Vec<u8>, String>;
+
+    // The file extension for the executable
+    fn executable_extension(&self) -> &'static str;
+
+    // The number of bits in the widest integers the backend can work with,
+    // which are only used when the multi-digit integer extension is on
+    fn max_int_bits(&self) -> u32 {
+        return ir::DEFAULT_INT_BITS;
+    }
+
+    // Sets where everything goes in memory, which only matters for the backends that generate an image
+    fn set_memory_layout(&mut self, _layout: &MemoryLayout) {}
+
+    // Creates a human readable listing of the last program that was generated, if the code is not already readable
+    fn create_listing(&self, _source_code: &str) -> Option<String> {
+        return None;
+    }
+
+    // Shows the generated code for a program on the webpage, along with its listing if there is one
+    fn display_code(&self, code: &Artifact, listing: Option<&str>, program_number: &u32);
+
+    // Removes the generated code from the webpage
+    fn clear_display(&self);
+}
+
+// Every backend in the order they are listed, where the first one is the default
+// This is the only place a new backend has to be added
+const BACKENDS: [fn() -> Box<dyn Backend>; 2] = [
+    || Box::new(CodeGenerator6502::new()),
+    || Box::new(CodeGeneratorRiscV::new())
+];
+
+// Creates a new instance of every backend
+pub fn get_backends() -> Vec<Box<dyn Backend>> {
+    return BACKENDS.iter().map(|create| create()).collect();
+}
+
+// Creates the backend with the given name or alias, ignoring case
+pub fn get_backend(name: &str) -> Option<Box<dyn Backend>> {
+    let name: String = name.to_lowercase();
+    return get_backends().into_iter()
+        .find(|backend| backend.name() == name || backend.aliases().contains(&name.as_str()));
+}
+
+// Gets the names of every backend, such as for error messages
+pub fn get_backend_names() -> Vec<&'static str> {
+    return get_backends().iter().map(|backend| backend.name()).collect();
+}
@@ -1,11 +1,15 @@
 use log::*;
 
-use crate::nexus::{syntax_tree::SyntaxTree, syntax_tree_node::*, symbol_table::*};
-use crate::nexus::token::{TokenType, Keywords};
+use crate::nexus::symbol_table::Type;
+use crate::nexus::ir::{IrProgram, Instruction, Variable, Temp, Label, Value};
+use crate::nexus::backend::{Backend, Artifact};
+use crate::nexus::diagnostic::{self, Diagnostic, DiagnosticCode, PhaseResult, Span};
+use crate::nexus::listing_6502::{self, ImageSymbols};
+use crate::nexus::memory_layout::MemoryLayout;
+use crate::nexus::peephole_6502::{PeepholeOptimizer, OptimizedCode};
 use crate::util::nexus_log;
-use petgraph::graph::{NodeIndex};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use web_sys::{Document, Window, Element, DomTokenList};
 use wasm_bindgen::{prelude::Closure, JsCast};
@@ -19,7 +23,8 @@ extern "C" {
     fn set_clipboard(newText: &str);
 }
 
-enum CodeGenBytes {
+#[derive (Clone, Copy, PartialEq)]
+pub(crate) enum CodeGenBytes {
     // Representation for final code/data in memory
     Code(u8),
     // Temporary variable address  until AST is traversed with identifier for later use
@@ -51,198 +56,252 @@ impl fmt::Debug for CodeGenBytes {
     }
 }
 
+// A value that instructions like ADC and CPX can read from memory
+#[derive (Debug, Clone, Copy)]
+enum MemoryOperand {
+    // The static offset of a variable
+    Var(usize),
+    // The index of the temp memory
+    Temp(usize)
+}
+
+// The parts of memory that grow as the code is generated
+#[derive (Debug, Clone, Copy, PartialEq)]
+enum Region {
+    Code,
+    Static,
+    Temp,
+    Heap
+}
+
+impl Region {
+    // The name of the region for the errors
+    fn get_name(&self) -> &'static str {
+        match self {
+            Region::Code => return "code",
+            Region::Static => return "static variables",
+            Region::Temp => return "temps",
+            Region::Heap => return "heap"
+        }
+    }
+}
+
+// Where the code for a statement starts, which is shown in the listing
+#[derive (Debug)]
+struct StatementStart {
+    address: usize,
+    span: Span,
+    // If and while statements only show a keyword and their condition instead of the whole body
+    condition: Option<(&'static str, Span)>
+}
+
 // The struct for the code generator
 #[derive (Debug)]
 pub struct CodeGenerator6502 {
-    // The current max scope we have seen so far, which are encountered in
-    // sequential order
-    max_scope: usize,
-    
     // The array for code gen
     code_arr: Vec<CodeGenBytes>,
 
+    // Where everything goes in memory
+    layout: MemoryLayout,
+
     // The current location of the code in the memory array
-    // The stack pointer is always code_pointer + 1
-    code_pointer: u8,
+    code_pointer: usize,
 
-    // The current location of the heap from the back of the array
-    heap_pointer: u8,
+    // The current location of the heap, which moves towards the front of the array
+    heap_pointer: u16,
 
     // The static table hashmap for <(id, scope), offset>
     static_table: HashMap<(String, usize), usize>,
 
-    // Index for the temoprary data
-    temp_index: usize,
+    // The number of bytes of temp memory that have been used
+    num_temps: usize,
+
+    // Temp memory that can be used again
+    free_temps: Vec<usize>,
+
+    // Temp memory that the current instruction read, which is freed once it is done
+    released_temps: Vec<usize>,
+
+    // The IR temps that are stored in temp memory
+    temp_memory: HashMap<Temp, usize>,
+
+    // The IR temp whose value is in the accumulator, if it has not been used yet
+    accumulator_temp: Option<Temp>,
+
+    // The IR temp whose value is in the Z flag, where Z = 1 is true
+    z_flag_temp: Option<Temp>,
 
     // Hashmap to keep track of the strings being stored on the heap
     string_history: HashMap<String, u8>,
 
     // Vector to keep track of each jump in the code
     jumps: Vec<u8>,
+
+    // The address of each IR label
+    labels: HashMap<Label, usize>,
+
+    // The jump index, label, and address after the branch for each branch to a label
+    branches: Vec<(usize, Label, usize)>,
+
+    // The jump index of the low order byte and the label for each JMP to a label
+    absolute_jumps: Vec<(usize, Label)>,
+
+    // Branches are too short to reach across the program, so every branch and jump goes through JMP
+    long_branches: bool,
+
+    // The statements in the order their code was generated
+    statement_starts: Vec<StatementStart>,
+
+    // The final image of the last program that was generated, which is used for the listing
+    image: Vec<u8>,
+
+    // The errors for the current program
+    diagnostics: Vec<Diagnostic>
 }
 
 impl CodeGenerator6502 {
     pub fn new() -> Self {
+        let layout: MemoryLayout = MemoryLayout::default();
         let mut code_gen: CodeGenerator6502 = CodeGenerator6502 {
-            // This is a flag for a new program
-            max_scope: usize::MAX,
+            code_arr: Vec::with_capacity(layout.image_size),
 
-            // We are only able to store 256 bytes in memory
-            code_arr: Vec::with_capacity(0x100),
+            layout,
 
-            // Code starts at 0x00
-            code_pointer: 0x00,
+            // Code starts at the load address
+            code_pointer: layout.load_address as usize,
 
-            // Heap starts at 0xFE (0xFF reserved for 0x00)
-            heap_pointer: 0xFE,
+            // Heap starts right before the 0x00
+            heap_pointer: layout.heap_start as u16,
 
             static_table: HashMap::new(),
 
-            // Always start with a temp index of 0
-            temp_index: 0,
+            num_temps: 0,
+            free_temps: Vec::new(),
+            released_temps: Vec::new(),
+            temp_memory: HashMap::new(),
+            accumulator_temp: None,
+            z_flag_temp: None,
 
             string_history: HashMap::new(),
 
-            jumps: Vec::new()
-        };
-
-        // Initialize the entire array to be unused spot in memory
-        for _ in 0..0x100 {
-            code_gen.code_arr.push(CodeGenBytes::Empty);
-        }
-
-        return code_gen;
-    }
+            jumps: Vec::new(),
 
-    pub fn generate_code(&mut self, ast: &SyntaxTree, symbol_table: &mut SymbolTable, program_number: &u32) {
-        // Make sure the current scope is set to be a flag for none
-        self.max_scope = usize::MAX;
-        
-        // Reset the array and empty it out
-        for i in 0..0x100 {
-            self.code_arr[i] = CodeGenBytes::Empty;
-        }
+            labels: HashMap::new(),
 
-        self.code_pointer = 0x00;
-        self.heap_pointer = 0xFE;
+            branches: Vec::new(),
 
-        self.static_table.clear();
-        self.temp_index = 0;
-        self.string_history.clear();
-        self.jumps.clear();
+            absolute_jumps: Vec::new(),
 
-        // We are going to store the strings false and true to print them
-        // out instead of 0 and 1
-        self.store_string("false");
-        self.store_string("true");
-
-        // Generate the code for the program
-        let program_res: bool = self.code_gen_block(ast, NodeIndex::new((*ast).root.unwrap()), symbol_table);
+            long_branches: false,
 
-        if program_res {
-            // All programs end with 0x00, which is HALT
-            let final_res: bool = self.add_code(0x00);
+            statement_starts: Vec::new(),
 
-            if final_res {
-                self.backpatch_addresses();
+            image: Vec::new(),
 
-                nexus_log::log(
-                    nexus_log::LogTypes::Info,
-                    nexus_log::LogSources::CodeGenerator,
-                    format!("Code generation completed successfully")
-                );
+            diagnostics: Vec::new()
+        };
 
-                nexus_log::log(
-                    nexus_log::LogTypes::Info,
-                    nexus_log::LogSources::Nexus,
-                    format!("Executable image for program {} is below", *program_number)
-                );
+        // Initialize the entire array to be unused spot in memory
+        code_gen.code_arr.resize(layout.image_size, CodeGenBytes::Empty);
 
-                self.display_code(program_number);
-                return;
-            }
-        }
+        return code_gen;
+    }
 
-        nexus_log::log(
-            nexus_log::LogTypes::Error,
-            nexus_log::LogSources::CodeGenerator,
-            format!("Code generation failed")
-        );
-        
-        nexus_log::insert_empty_line();
+    // Gets the start address and size of each region of memory, with 1 more byte in the region that is growing
+    // By default, the code and variables grow towards the temps and the heap
+    //  |  Code  |  Vars  ->  <-  Temp  |  Heap  | 0x00 |
+    fn get_regions(&self, growing: Option<Region>) -> [(Region, isize, usize); 4] {
+        let get_growth = |region: Region| -> usize { if growing == Some(region) { 1 } else { 0 } };
+
+        let code_size: usize = self.code_pointer - self.layout.load_address as usize + get_growth(Region::Code);
+        let static_size: usize = self.static_table.len() + get_growth(Region::Static);
+        let static_start: isize = match self.layout.static_address {
+            Some(static_address) => static_address as isize,
+            None => (self.layout.load_address as usize + code_size) as isize
+        };
 
-        nexus_log::log(
-            nexus_log::LogTypes::Warning,
-            nexus_log::LogSources::Nexus,
-            format!("Executable image display skipped due to code generation failure")
-        );
+        // The heap pointer wraps around once the first byte of memory is used
+        let heap_size: usize = (self.layout.heap_start as u16).wrapping_sub(self.heap_pointer) as usize + get_growth(Region::Heap);
+        let heap_bottom: isize = self.layout.zero_address() as isize - heap_size as isize;
+        let temp_size: usize = self.num_temps + get_growth(Region::Temp);
+
+        return [
+            (Region::Code, self.layout.load_address as isize, code_size),
+            (Region::Static, static_start, static_size),
+            (Region::Temp, heap_bottom - temp_size as isize, temp_size),
+            (Region::Heap, heap_bottom, heap_size)
+        ];
     }
 
-    fn code_gen_block(&mut self, ast: &SyntaxTree, cur_index: NodeIndex, symbol_table: &mut SymbolTable) -> bool {
-        // If this is the first block, then the first scope is 0
-        if self.max_scope == usize::MAX {
-            self.max_scope = 0;
-        } else {
-            // Otherwise just add 1
-            self.max_scope += 1;
-        }
-
-        nexus_log::log(
-            nexus_log::LogTypes::Debug,
-            nexus_log::LogSources::CodeGenerator,
-            format!("Starting code generation for the block for scope {}", self.max_scope)
-        );
+    // Checks if the region can grow by 1 byte without going outside of the image or into another region
+    fn has_available_memory(&self, growing: Region) -> bool {
+        let mut ranges: Vec<(isize, isize)> = self.get_regions(Some(growing)).iter()
+            .filter(|(_, _, size)| *size > 0)
+            .map(|(_, start, size)| (*start, *start + *size as isize))
+            .collect();
+        // The 0x00 can never be overwritten
+        let zero_address: isize = self.layout.zero_address() as isize;
+        ranges.push((zero_address, zero_address + 1));
+        ranges.sort();
+
+        return ranges.iter().all(|(start, end)| *start >= 0 && *end <= self.layout.image_size as isize)
+            && ranges.windows(2).all(|pair| pair[0].1 <= pair[1].0);
+    }
 
-        // Manually set the current scope because we are not able to look down
-        // in the symbol table
-        symbol_table.set_cur_scope(self.max_scope);
-
-        // The current node is the block, so we need to loop through each of its children
-        let neighbors: Vec<NodeIndex> = (*ast).graph.neighbors(cur_index).collect();
-
-        // Assume a success
-        let mut block_res: bool = true;
-
-        for neighbor_index in neighbors.into_iter().rev() {
-            let child: &SyntaxTreeNode = (*ast).graph.node_weight(neighbor_index).unwrap();
-            
-            match child {
-                SyntaxTreeNode::NonTerminalAst(non_terminal) => {
-                    block_res = match non_terminal {
-                        NonTerminalsAst::Block => self.code_gen_block(ast, neighbor_index, symbol_table),
-                        NonTerminalsAst::VarDecl => self.code_gen_var_decl(ast, neighbor_index, symbol_table),
-                        NonTerminalsAst::Assign => self.code_gen_assignment(ast, neighbor_index, symbol_table),
-                        NonTerminalsAst::Print => self.code_gen_print(ast, neighbor_index, symbol_table),
-                        NonTerminalsAst::If => self.code_gen_if(ast, neighbor_index, symbol_table),
-                        NonTerminalsAst::While => self.code_gen_while(ast, neighbor_index, symbol_table),
-                        _ => { 
-                            error!("Received {:?} when expecting an AST nonterminal statement in a block", non_terminal);
-                            false
-                        }
-                    };
-                    if !block_res {
-                        return false;
-                    }
+    // Finds the two things that overlap once the region grows by 1 byte
+    // Growing the code can push the static variables into something else, so this is not always the growing region
+    fn get_collision(&self, growing: Region) -> (String, String) {
+        let regions: [(Region, isize, usize); 4] = self.get_regions(Some(growing));
+        let mut ranges: Vec<(String, isize, isize)> = regions.iter()
+            .filter(|(_, _, size)| *size > 0)
+            .map(|(region, start, size)| (String::from(region.get_name()), *start, *start + *size as isize))
+            .collect();
+        let zero_address: isize = self.layout.zero_address() as isize;
+        ranges.push((format!("0x00 at 0x{:04X}", zero_address), zero_address, zero_address + 1));
+
+        // Check the growing region first so it is the one that is named when it is part of the collision
+        ranges.sort_by_key(|(name, _, _)| *name != growing.get_name());
+        for (i, (name, start, end)) in ranges.iter().enumerate() {
+            if *start < 0 {
+                return (name.to_owned(), String::from("start of memory"));
+            }
+            if *end > self.layout.image_size as isize {
+                return (name.to_owned(), String::from("end of memory"));
+            }
+            for (other_name, other_start, other_end) in ranges.iter().skip(i + 1) {
+                if *start < *other_end && *other_start < *end {
+                    return (name.to_owned(), other_name.to_owned());
                 }
-                _ => error!("Received {:?} when expecting an AST nonterminal for code gen in a block", child)
             }
         }
-
-        // Exit the current scope
-        symbol_table.end_cur_scope();
-        return block_res;
+        return (String::from(growing.get_name()), String::from("end of memory"));
     }
 
-    fn has_available_memory(&mut self) -> bool {
-        let num_vars: usize = self.static_table.len();
-        // Check for collision at the double bar (where stack meets heap)
-        //  |  Code  |  Vars  ||  Temp  |  Heap  |
-        return self.code_pointer + (num_vars as u8) <= self.heap_pointer - (self.temp_index as u8);
+    // Reports that a region ran out of room along with the number of bytes each region had used by then
+    fn report_out_of_memory(&mut self, growing: Region) {
+        let (code, overflow): (DiagnosticCode, &str) = match growing {
+            Region::Code | Region::Static => (DiagnosticCode::StackOverflow, "stack"),
+            Region::Temp | Region::Heap => (DiagnosticCode::HeapOverflow, "heap")
+        };
+        let (first, second): (String, String) = self.get_collision(growing);
+        let message: String = format!("The {} ran into the {} causing a {} overflow error", first, second, overflow);
+
+        // Code generation stops here, so these are not the totals the whole program would need
+        let regions: [(Region, isize, usize); 4] = self.get_regions(None);
+        diagnostic::report(
+            &mut self.diagnostics,
+            Diagnostic::new(code, message, None)
+                .with_note(&format!("When memory ran out, the code had used {} bytes so far, the static variables had used {}, the temps had used {}, and the heap had used {}", regions[0].2, regions[1].2, regions[2].2, regions[3].2))
+                .with_note(&format!("Everything has to fit in the {} bytes of memory, where the code starts at 0x{:04X}, the static variables start at 0x{:04X}{}, and the heap starts at 0x{:02X}",
+                    self.layout.image_size, self.layout.load_address, regions[1].1,
+                    if self.layout.static_address.is_some() { "" } else { " right after the code" },
+                    self.layout.heap_start))
+        );
     }
 
     // Function to add byte of code to the memory array
     fn add_code(&mut self, code: u8) -> bool {
-        if self.has_available_memory() {
+        if self.has_available_memory(Region::Code) {
             nexus_log::log(
                 nexus_log::LogTypes::Debug,
                 nexus_log::LogSources::CodeGenerator,
@@ -250,23 +309,19 @@ impl CodeGenerator6502 {
             );
 
             // Add the code to the next available spot in memory
-            self.code_arr[self.code_pointer as usize] = CodeGenBytes::Code(code);
+            self.code_arr[self.code_pointer] = CodeGenBytes::Code(code);
             self.code_pointer += 1;
             // No error, so successful addition to the code
             return true;
         } else {
-            nexus_log::log(
-                nexus_log::LogTypes::Error,
-                nexus_log::LogSources::CodeGenerator,
-                String::from("The stack has collided with the heap causing a stack overflow error")
-            );
+            self.report_out_of_memory(Region::Code);
             return false;
         }
     }
 
     // Function to add byte of code to the memory array for variable addressing
     fn add_var(&mut self, var: usize) -> bool {
-        if self.has_available_memory() {
+        if self.has_available_memory(Region::Code) {
             nexus_log::log(
                 nexus_log::LogTypes::Debug,
                 nexus_log::LogSources::CodeGenerator,
@@ -274,23 +329,19 @@ impl CodeGenerator6502 {
             );
 
             // Add the code to the next available spot in memory
-            self.code_arr[self.code_pointer as usize] = CodeGenBytes::Var(var);
+            self.code_arr[self.code_pointer] = CodeGenBytes::Var(var);
             self.code_pointer += 1;
             // All vars are followed by the high order byte
             return self.add_high_order_byte();
         } else {
-            nexus_log::log(
-                nexus_log::LogTypes::Error,
-                nexus_log::LogSources::CodeGenerator,
-                String::from("The stack has collided with the heap causing a stack overflow error")
-            );
+            self.report_out_of_memory(Region::Code);
             return false;
         }
     }
 
     // Function to add the high order byte for unknown addresses that will be backpatched
     fn add_high_order_byte(&mut self) -> bool {
-        if self.has_available_memory() {
+        if self.has_available_memory(Region::Code) {
             nexus_log::log(
                 nexus_log::LogTypes::Debug,
                 nexus_log::LogSources::CodeGenerator,
@@ -298,39 +349,35 @@ impl CodeGenerator6502 {
             );
 
             // Add the code to the next available spot in memory
-            self.code_arr[self.code_pointer as usize] = CodeGenBytes::HighOrderByte;
+            self.code_arr[self.code_pointer] = CodeGenBytes::HighOrderByte;
             self.code_pointer += 1;
             return true;
         } else {
-            nexus_log::log(
-                nexus_log::LogTypes::Error,
-                nexus_log::LogSources::CodeGenerator,
-                String::from("The stack has collided with the heap causing a stack overflow error")
-            );
+            self.report_out_of_memory(Region::Code);
             return false;
         }
     }
 
     // Function to create space for new temp data and return its index
     fn new_temp(&mut self) -> Option<usize> {
-        if self.has_available_memory() {
-            // Make the room for the single byte
-            let temp_addr: usize = self.temp_index.to_owned();
-            self.temp_index += 1;
+        // Reuse temp memory that is no longer needed before making more
+        if let Some(temp_addr) = self.free_temps.pop() {
             return Some(temp_addr);
+        }
+
+        // Make the room for the single byte
+        if self.has_available_memory(Region::Temp) {
+            self.num_temps += 1;
+            return Some(self.num_temps - 1);
         } else {
-            nexus_log::log(
-                nexus_log::LogTypes::Error,
-                nexus_log::LogSources::CodeGenerator,
-                String::from("The heap has collided with the stack causing a heap overflow error")
-            );
+            self.report_out_of_memory(Region::Temp);
             return None;
         }
     }
 
     // Function to add byte of code to memory array for temporary data
     fn add_temp(&mut self, temp: usize) -> bool {
-        if self.has_available_memory() {
+        if self.has_available_memory(Region::Code) {
             nexus_log::log(
                 nexus_log::LogTypes::Debug,
                 nexus_log::LogSources::CodeGenerator,
@@ -338,39 +385,31 @@ impl CodeGenerator6502 {
             );
 
             // Add the addressing for the temporary value
-            self.code_arr[self.code_pointer as usize] = CodeGenBytes::Temp(temp);
+            self.code_arr[self.code_pointer] = CodeGenBytes::Temp(temp);
             self.code_pointer += 1;
             // All temps are followed by the high order byte
             return self.add_high_order_byte();
         } else {
-            nexus_log::log(
-                nexus_log::LogTypes::Error,
-                nexus_log::LogSources::CodeGenerator,
-                String::from("The heap has collided with the stack causing a heap overflow error")
-            );
+            self.report_out_of_memory(Region::Code);
             return false;
         }
     }
 
     // Function to add a byte of data to the heap
     fn add_data(&mut self, data: u8) -> bool {
-        if self.has_available_memory() {
+        if self.has_available_memory(Region::Heap) {
             nexus_log::log(
                 nexus_log::LogTypes::Debug,
                 nexus_log::LogSources::CodeGenerator,
                 format!("Adding data 0x{:02X} at memory location 0x{:02X}", data, self.heap_pointer)
             );
 
-            // Heap starts from the end of the 256 bytes and moves towards the front
+            // Heap starts right before the 0x00 and moves towards the front
             self.code_arr[self.heap_pointer as usize] = CodeGenBytes::Data(data);
-            self.heap_pointer -= 1;
+            self.heap_pointer = self.heap_pointer.wrapping_sub(1);
             return true;
         } else {
-            nexus_log::log(
-                nexus_log::LogTypes::Error,
-                nexus_log::LogSources::CodeGenerator,
-                String::from("The heap has collided with the stack causing a heap overflow error")
-            );
+            self.report_out_of_memory(Region::Heap);
             return false;
         }
     }
@@ -382,7 +421,9 @@ impl CodeGenerator6502 {
             let mut is_stored: bool = true;
 
             // All strings are null terminated, so start with a 0x00 at the end
-            self.add_data(0x00);
+            if !self.add_data(0x00) {
+                return None;
+            }
 
             // Loop through the string in reverse order
             for c in string.chars().rev() {
@@ -395,15 +436,17 @@ impl CodeGenerator6502 {
             }
            
             if is_stored {
+                // The heap is always in the first 256 bytes, so the address fits in a byte
+                let string_addr: u8 = self.heap_pointer.wrapping_add(1) as u8;
                 nexus_log::log(
                     nexus_log::LogTypes::Debug,
                     nexus_log::LogSources::CodeGenerator,
-                    format!("Stored string \"{}\" at memory location 0x{:02X}", string, self.heap_pointer + 1)
+                    format!("Stored string \"{}\" at memory location 0x{:02X}", string, string_addr)
                 );
 
                 // Store it for future use
-                self.string_history.insert(String::from(string), self.heap_pointer + 1);
-                return Some(self.heap_pointer + 1);
+                self.string_history.insert(String::from(string), string_addr);
+                return Some(string_addr);
             } else {
                 // There is no address to return
                 return None;
@@ -415,7 +458,7 @@ impl CodeGenerator6502 {
     }
 
     fn add_jump(&mut self) -> bool {
-        if self.has_available_memory() {
+        if self.has_available_memory(Region::Code) {
             nexus_log::log(
                 nexus_log::LogTypes::Debug,
                 nexus_log::LogSources::CodeGenerator,
@@ -423,16 +466,12 @@ impl CodeGenerator6502 {
             );
 
             // Add the jump to the code and set it to 0 in the vector of jumps
-            self.code_arr[self.code_pointer as usize] = CodeGenBytes::Jump(self.jumps.len());
+            self.code_arr[self.code_pointer] = CodeGenBytes::Jump(self.jumps.len());
             self.code_pointer += 1;
             self.jumps.push(0x00);
             return true;
         } else {
-            nexus_log::log(
-                nexus_log::LogTypes::Error,
-                nexus_log::LogSources::CodeGenerator,
-                String::from("The stack has collided with the heap causing a stack overflow error")
-            );
+            self.report_out_of_memory(Region::Code);
             return false;
         }
     }
@@ -445,18 +484,18 @@ impl CodeGenerator6502 {
             match &self.code_arr[i] {
                 CodeGenBytes::Var(offset) => {
                     // Compute the new address
-                    let new_addr: u8 = self.code_pointer + *offset as u8;
+                    let new_addr: u16 = (self.get_static_start() + *offset) as u16;
                     nexus_log::log(
                         nexus_log::LogTypes::Debug,
                         nexus_log::LogSources::CodeGenerator,
-                        format!("Backpatching 0x{:02X} for variable placeholder {} at memory location 0x{:02X}", new_addr, offset, i)
+                        format!("Backpatching 0x{:02X} for variable placeholder {} at memory location 0x{:02X}", new_addr as u8, offset, i)
                     );
 
-                    self.code_arr[i] = CodeGenBytes::Code(new_addr);
+                    self.code_arr[i] = CodeGenBytes::Code(new_addr as u8);
 
                     // The integer division result is the high order byte
-                    // Always 0 in this case
-                    let new_high: u8 = (new_addr as u16 / 0x100) as u8;
+                    // It is only 0 when the address is in the first 256 bytes
+                    let new_high: u8 = (new_addr / 0x100) as u8;
 
                     nexus_log::log(
                         nexus_log::LogTypes::Debug,
@@ -468,19 +507,19 @@ impl CodeGenerator6502 {
                 },
                 CodeGenBytes::Temp(offset) => {
                     // Compute the address of the temp data
-                    let new_addr: u8 = self.heap_pointer - *offset as u8;
+                    let new_addr: u16 = self.heap_pointer.wrapping_sub(*offset as u16);
                     
                     nexus_log::log(
                         nexus_log::LogTypes::Debug,
                         nexus_log::LogSources::CodeGenerator,
-                        format!("Backpatching 0x{:02X} for temp data placeholder {} at memory location 0x{:02X}", new_addr, offset, i)
+                        format!("Backpatching 0x{:02X} for temp data placeholder {} at memory location 0x{:02X}", new_addr as u8, offset, i)
                     );
 
-                    self.code_arr[i] = CodeGenBytes::Code(new_addr);
+                    self.code_arr[i] = CodeGenBytes::Code(new_addr as u8);
                    
                     // The integer division result is the high order byte
-                    // Always 0 in this case
-                    let new_high: u8 = (new_addr as u16 / 0x100) as u8;
+                    // Always 0 because the temps are right below the heap
+                    let new_high: u8 = (new_addr / 0x100) as u8;
 
                     nexus_log::log(
                         nexus_log::LogTypes::Debug,
@@ -505,578 +544,356 @@ impl CodeGenerator6502 {
         }
     }
 
-    // Function for creating the code for a variable declaration
-    fn code_gen_var_decl(&mut self, ast: &SyntaxTree, cur_index: NodeIndex, symbol_table: &mut SymbolTable) -> bool {
-        nexus_log::log(
-            nexus_log::LogTypes::Debug,
-            nexus_log::LogSources::CodeGenerator,
-            format!("Starting code generation for variable declaration statement in scope {}", symbol_table.cur_scope.unwrap())
-        );
+    // Gets the address of the first static variable, which is right after the code unless the layout says otherwise
+    fn get_static_start(&self) -> usize {
+        match self.layout.static_address {
+            Some(static_address) => return static_address as usize,
+            None => return self.code_pointer
+        }
+    }
 
-        let children: Vec<NodeIndex> = (*ast).graph.neighbors(cur_index).collect();
-        let id_node: &SyntaxTreeNode = (*ast).graph.node_weight(children[0]).unwrap();
-
-        match id_node {
-            SyntaxTreeNode::Terminal(token) => {
-                // Get the offset this variable will be on the stack
-                let static_offset: usize = self.static_table.len();
-                self.static_table.insert((token.text.to_owned(), symbol_table.cur_scope.unwrap()), static_offset);
-
-                // Get the symbol table entry to get the type of the variable
-                let symbol_table_entry: &SymbolTableEntry = symbol_table.get_symbol_with_context(&token.text, token.position).unwrap();
-                match symbol_table_entry.symbol_type {
-                    // Only integers and booleans are initialized
-                    Type::Int | Type::Boolean => {
-                        // Generate the code for the variable declaration
-                        if !self.add_code(0xA9) { return false; }
-                        if !self.add_code(0x00) { return false; }
-                        if !self.add_code(0x8D) { return false; }
-                        if !self.add_var(static_offset) { return false; }
-                    },
-                    // Strings do not get initialized
-                    Type::String => {
-                        // Nothing to do here, so may end up initially with dirty data
-                        // from temp values
-                    }
-                }
-            },
-            _ => error!("Received {:?} when expecting terminal for var decl child in code gen", id_node)
+    // Records that the code for a statement starts at the current position
+    fn add_statement_start(&mut self, span: Span, condition: Option<(&'static str, Span)>) {
+        self.statement_starts.push(StatementStart {
+            address: self.code_pointer,
+            span,
+            condition
+        });
+    }
+
+    // Gets the static offset of a variable
+    fn get_static_offset(&self, var: &Variable) -> usize {
+        return self.static_table.get(&(var.name.to_owned(), var.scope)).unwrap().to_owned();
+    }
+
+    // Adds the 2 byte address for a value in memory
+    fn add_memory_operand(&mut self, operand: MemoryOperand) -> bool {
+        match operand {
+            MemoryOperand::Var(offset) => return self.add_var(offset),
+            MemoryOperand::Temp(temp_addr) => return self.add_temp(temp_addr)
         }
+    }
+
+    // Gets the temp memory for an IR temp that has been stored
+    // The memory is free to use again once the current instruction is done
+    fn take_temp_memory(&mut self, temp: Temp) -> usize {
+        let temp_addr: usize = self.temp_memory.remove(&temp).unwrap();
+        self.released_temps.push(temp_addr);
+        return temp_addr;
+    }
+
+    // Stores the value in the accumulator if it is still needed, so the accumulator can be used for something else
+    fn spill_accumulator(&mut self) -> bool {
+        if let Some(temp) = self.accumulator_temp.take() {
+            let temp_addr_option: Option<usize> = self.new_temp();
+            if temp_addr_option.is_none() {
+                return false;
+            }
+            let temp_addr: usize = temp_addr_option.unwrap();
 
+            if !self.add_code(0x8D) { return false; }
+            if !self.add_temp(temp_addr) { return false; }
+            self.temp_memory.insert(temp, temp_addr);
+        }
         return true;
     }
 
-    // Function for creating the code for an assignment
-    fn code_gen_assignment(&mut self, ast: &SyntaxTree, cur_index: NodeIndex, symbol_table: &mut SymbolTable) -> bool {
-        nexus_log::log(
-            nexus_log::LogTypes::Debug,
-            nexus_log::LogSources::CodeGenerator,
-            format!("Starting code generation for assignment statement in scope {}", symbol_table.cur_scope.unwrap())
-        );
+    // Stores the value of the Z flag in memory if it is still needed, so another comparison can be made
+    fn spill_z_flag(&mut self) -> bool {
+        if let Some(temp) = self.z_flag_temp.take() {
+            if !self.spill_accumulator() { return false; }
+            if !self.get_z_flag_value() { return false; }
+            self.accumulator_temp = Some(temp);
+            return self.spill_accumulator();
+        }
+        return true;
+    }
 
-        let children: Vec<NodeIndex> = (*ast).graph.neighbors(cur_index).collect();
-        let value_node: &SyntaxTreeNode = (*ast).graph.node_weight(children[0]).unwrap();
-        let id_node: &SyntaxTreeNode = (*ast).graph.node_weight(children[1]).unwrap();
-
-        match value_node {
-            SyntaxTreeNode::Terminal(token) => {
-                match &token.token_type {
-                    TokenType::Identifier(_) => {
-                        let value_id_entry: &SymbolTableEntry = symbol_table.get_symbol_with_context(&token.text, token.position).unwrap(); 
-                        let value_static_offset: usize = self.static_table.get(&(token.text.to_owned(), value_id_entry.scope)).unwrap().to_owned();
-                        
-                        if !self.add_code(0xAD) { return false; }
-                        if !self.add_var(value_static_offset) { return false; }
-                    },
-                    TokenType::Digit(val) => {
-                        // Digits just load a constant to the accumulator
-                        if !self.add_code(0xA9) { return false; }
-                        if !self.add_code(*val as u8) { return false; }
-                    },
-                    TokenType::Char(string) => {
-                        // Start by storing the string
-                        let addr: Option<u8> = self.store_string(&string);
-
-                        // Store the starting address of the string in memory
-                        if addr.is_some() {
-                            if !self.add_code (0xA9) { return false; }
-                            if !self.add_code(addr.unwrap()) { return false; }
-                        } else {
-                            return false;
-                        }
-                    },
-                    TokenType::Keyword(keyword) => {
-                        match &keyword {
-                            Keywords::True => {
-                                // True is 0x01
-                                if !self.add_code(0xA9) { return false; }
-                                if !self.add_code(0x01) { return false; }
-                            },
-                            Keywords::False => {
-                                // False is 0x00
-                                if !self.add_code(0xA9) { return false; }
-                                if !self.add_code(0x00) { return false; }
-                            },
-                            _ => error!("Received {:?} when expecting true or false for keyword terminals in assignment", keyword)
-                        }
-                    },
-                    _ => error!("Received {:?} for terminal in assignment when expecting id, digit, char, or keyword", token)
-                }
+    // Loads a value into the accumulator
+    fn load_accumulator(&mut self, value: &Value) -> bool {
+        if let Value::Temp(temp) = value {
+            if self.accumulator_temp == Some(*temp) {
+                // Already there, so nothing to do
+                self.accumulator_temp = None;
+                return true;
+            }
+        }
+
+        if !self.spill_accumulator() { return false; }
+
+        match value {
+            Value::Var(var) => {
+                let static_offset: usize = self.get_static_offset(var);
+                if !self.add_code(0xAD) { return false; }
+                if !self.add_var(static_offset) { return false; }
             },
-            SyntaxTreeNode::NonTerminalAst(non_terminal) => {
-                match non_terminal {
-                    NonTerminalsAst::Add => {
-                        // Call add, so the result will be in both the accumulator and in memory
-                        if !self.code_gen_add(ast, children[0], symbol_table, true) { return false; }
-                    },
-                    NonTerminalsAst::IsEq => {
-                        if !self.code_gen_compare(ast, children[0], symbol_table, true) { return false; }
-                        if !self.get_z_flag_value() { return false; }
-                    },
-                    NonTerminalsAst::NotEq => {
-                        if !self.code_gen_compare(ast, children[0], symbol_table, false) { return false; }
-                        if !self.get_z_flag_value() { return false; }
-                    },
-                    _ => error!("Received {:?} for nonterminal on right side of assignment for code gen", non_terminal)
+            Value::Temp(temp) => {
+                if self.z_flag_temp == Some(*temp) {
+                    // Booleans in the Z flag have to be turned into a 0 or 1
+                    self.z_flag_temp = None;
+                    if !self.get_z_flag_value() { return false; }
+                } else {
+                    let temp_addr: usize = self.take_temp_memory(*temp);
+                    if !self.add_code(0xAD) { return false; }
+                    if !self.add_temp(temp_addr) { return false; }
                 }
             },
-            _ => error!("Received {:?} when expecting terminal or AST nonterminal for assignment in code gen", value_node)
+            _ => {
+                // Constants are loaded directly
+                let constant_option: Option<u8> = self.get_constant(value);
+                if constant_option.is_none() {
+                    return false;
+                }
+                if !self.add_code(0xA9) { return false; }
+                if !self.add_code(constant_option.unwrap()) { return false; }
+            }
         }
+        return true;
+    }
 
-        match id_node {
-            SyntaxTreeNode::Terminal(token) => {
-                // Get the static offset for the variable being assigned to
-                let id_entry: &SymbolTableEntry = symbol_table.get_symbol_with_context(&token.text, token.position).unwrap(); 
-                let static_offset = self.static_table.get(&(token.text.to_owned(), id_entry.scope)).unwrap().to_owned();
-                
-                // The data that we are storing is already in the accumulator
-                // so just run the code to store the data
-                if !self.add_code(0x8D) { return false; }
+    // Loads a value into the X register
+    fn load_x_register(&mut self, value: &Value) -> bool {
+        match value {
+            Value::Var(var) => {
+                let static_offset: usize = self.get_static_offset(var);
+                if !self.add_code(0xAE) { return false; }
                 if !self.add_var(static_offset) { return false; }
             },
-            _ => error!("Received {:?} when expecting terminal for assignmentchild in code gen", id_node)
+            Value::Temp(_) => {
+                // Temps can only be moved to X through memory
+                let operand_option: Option<MemoryOperand> = self.get_memory_operand(value);
+                if operand_option.is_none() {
+                    return false;
+                }
+                if !self.add_code(0xAE) { return false; }
+                if !self.add_memory_operand(operand_option.unwrap()) { return false; }
+            },
+            _ => {
+                let constant_option: Option<u8> = self.get_constant(value);
+                if constant_option.is_none() {
+                    return false;
+                }
+                if !self.add_code(0xA2) { return false; }
+                if !self.add_code(constant_option.unwrap()) { return false; }
+            }
         }
-
         return true;
     }
 
-    // Function for generating code for a print statement
-    fn code_gen_print(&mut self, ast: &SyntaxTree, cur_index: NodeIndex, symbol_table: &mut SymbolTable) -> bool {
-        nexus_log::log(
-            nexus_log::LogTypes::Debug,
-            nexus_log::LogSources::CodeGenerator,
-            format!("Starting code generation for print statement in scope {}", symbol_table.cur_scope.unwrap())
-        );
-
-        // Get the child on the print statement to evaluate
-        let children: Vec<NodeIndex> = (*ast).graph.neighbors(cur_index).collect();
-        let child: &SyntaxTreeNode = (*ast).graph.node_weight(children[0]).unwrap();
-
-        match child {
-            SyntaxTreeNode::Terminal(token) => {
-                match &token.token_type {
-                    TokenType::Identifier(id_name) => {
-                        let print_id: &SymbolTableEntry = symbol_table.get_symbol_with_context(&id_name, token.position).unwrap();
-                        let static_offset: usize = self.static_table.get(&(id_name.to_owned(), print_id.scope)).unwrap().to_owned();
-                        match &print_id.symbol_type {
-                            Type::Int  => {
-                                // Load the integer value into the Y register
-                                if !self.add_code(0xAC) { return false; }
-                                if !self.add_var(static_offset) { return false; }
-
-                                // Set X to 1 for the system call
-                                if !self.add_code(0xA2) { return false; }
-                                if !self.add_code(0x01) { return false; }
-                            },
-                            Type::String => {
-                                // Store the string address in Y
-                                if !self.add_code(0xAC) { return false; }
-                                if !self.add_var(static_offset) { return false; }
-
-                                // X = 2 for this sys call
-                                if !self.add_code(0xA2) { return false; }
-                                if !self.add_code(0x02) { return false; }
-                            },
-                            Type::Boolean => {
-                                // Compare the value of the variable with true
-                                if !self.add_code(0xA2) { return false; }
-                                if !self.add_code(0x01) { return false; }
-                                if !self.add_code(0xEC) { return false; }
-                                if !self.add_var(static_offset) { return false; }
-                                // Skip to the false string if it is false
-                                if !self.add_code(0xD0) { return false; }
-                                if !self.add_code(0x07) { return false; }
-                                
-                                // Load the true string and skip over the false string
-                                if !self.add_code(0xA0) { return false; }
-                                if !self.add_code(*self.string_history.get("true").unwrap()) { return false; }
-                                if !self.add_code(0xEC) { return false; }
-                                if !self.add_code(0xFF) { return false; }
-                                if !self.add_code(0x00) { return false; }
-                                if !self.add_code(0xD0) { return false; }
-                                if !self.add_code(0x02) { return false; }
-                                // Load the false string
-                                if !self.add_code(0xA0) { return false; }
-                                if !self.add_code(*self.string_history.get("false").unwrap()) { return false; }
-
-                                // We are printing a string, so X = 2
-                                if !self.add_code(0xA2) { return false; }
-                                if !self.add_code(0x02) { return false; }
-                            }
-                        }
-                    },
-                    TokenType::Digit(digit) => {
-                        // Sys call 1 for integers needs the number in Y
-                        if !self.add_code(0xA0) { return false; }
-                        if !self.add_code(*digit as u8) { return false; }
-
-                        // And X = 1
-                        if !self.add_code(0xA2) { return false; }
-                        if !self.add_code(0x01) { return false; }
-                    },
-                    TokenType::Char(string) => {
-                        // Store the string in memory and load its address to Y
-                        let addr: Option<u8> = self.store_string(&string);
-                        if addr.is_some() {
-                            if !self.add_code(0xA0) { return false; }
-                            if !self.add_code(addr.unwrap()) { return false; }
-                        } else {
-                            return false;
-                        }
+    // Gets the byte for a constant value, where strings are their address on the heap
+    fn get_constant(&mut self, value: &Value) -> Option<u8> {
+        match value {
+            // The 6502 only has byte integers, so the IR already wrapped them to fit
+            Value::Int(int_value) => return Some(*int_value as u8),
+            Value::Bool(bool_value) => return Some(*bool_value as u8),
+            Value::String(string) => return self.store_string(string),
+            _ => {
+                error!("Received {:?} when expecting a constant", value);
+                return None;
+            }
+        }
+    }
 
-                        // X = 2 for a string sys call
-                        if !self.add_code(0xA2) { return false; }
-                        if !self.add_code(0x02) { return false; }
-                    },
-                    TokenType::Keyword(keyword) => {
-                        if !self.add_code(0xA0) { return false; }
-                        match keyword {
-                            Keywords::True => {
-                                // Y = true addr for true
-                                if !self.add_code(*self.string_history.get("true").unwrap()) { return false; }
-                            },
-                            Keywords::False => {
-                                // Y = false addr for false
-                                if !self.add_code(*self.string_history.get("false").unwrap()) { return false; }
-                            },
-                            _ => error!("Received {:?} when expecting true or false for print keyword", keyword)
-                        }
-                        // X = 2 for the sys call
-                        if !self.add_code(0xA2) { return false; }
-                        if !self.add_code(0x02) { return false; }
-                    },
-                    _ => error!("Received {:?} when expecting id, digit, string, or keyword for print terminal", token)
+    // Makes sure a value is in memory for the instructions that only work with memory, such as ADC and CPX
+    fn get_memory_operand(&mut self, value: &Value) -> Option<MemoryOperand> {
+        match value {
+            Value::Var(var) => return Some(MemoryOperand::Var(self.get_static_offset(var))),
+            Value::Temp(temp) => {
+                // Temps in the accumulator or Z flag have to be stored first
+                if self.accumulator_temp == Some(*temp) && !self.spill_accumulator() {
+                    return None;
+                }
+                if self.z_flag_temp == Some(*temp) && !self.spill_z_flag() {
+                    return None;
                 }
+                return Some(MemoryOperand::Temp(self.take_temp_memory(*temp)));
             },
-            SyntaxTreeNode::NonTerminalAst(non_terminal) => {
-                match non_terminal {
-                    NonTerminalsAst::Add => {
-                        // Generate the result of the addition expression
-                        if !self.code_gen_add(ast, children[0], symbol_table, true) { return false; }
-
-                        let temp_addr_option: Option<usize> = self.new_temp();
-                        if temp_addr_option.is_none() {
-                            return false;
-                        }
-                        let temp_addr: usize = temp_addr_option.unwrap();
-
-                        if !self.add_code(0x8D) { return false; }
-                        if !self.add_temp(temp_addr) { return false; }
-                        
-                        // Load the result to Y (wish there was TAY)
-                        if !self.add_code(0xAC) { return false; }
-                        if !self.add_temp(temp_addr) { return false; }
-                        
-                        // We are done with the temp data
-                        self.temp_index -= 1;
+            _ => {
+                // Constants have to be stored in temp memory first
+                if !self.load_accumulator(value) { return None; }
 
-                        // X = 1 for the sys call for integers
-                        if !self.add_code(0xA2) { return false; }
-                        if !self.add_code(0x01) { return false; }
-                    },
-                    NonTerminalsAst::IsEq => {
-                        // If it is true or false is in the Z flag
-                        if !self.code_gen_compare(ast, children[0], symbol_table, true) { return false; }
-
-                        // We are printing a string, so X = 2
-                        if !self.add_code(0xA2) { return false; }
-                        if !self.add_code(0x02) { return false; }
+                let temp_addr_option: Option<usize> = self.new_temp();
+                if temp_addr_option.is_none() {
+                    return None;
+                }
+                let temp_addr: usize = temp_addr_option.unwrap();
 
-                        // Skip to the false string if it is false
-                        if !self.add_code(0xD0) { return false; }
-                        if !self.add_code(0x07) { return false; }
-                        
-                        // Load the true string and skip over the false string
-                        if !self.add_code(0xA0) { return false; }
-                        if !self.add_code(*self.string_history.get("true").unwrap()) { return false; }
-                        if !self.add_code(0xEC) { return false; }
-                        if !self.add_code(0xFF) { return false; }
-                        if !self.add_code(0x00) { return false; }
-                        if !self.add_code(0xD0) { return false; }
-                        if !self.add_code(0x02) { return false; }
+                if !self.add_code(0x8D) { return None; }
+                if !self.add_temp(temp_addr) { return None; }
+                self.released_temps.push(temp_addr);
+                return Some(MemoryOperand::Temp(temp_addr));
+            }
+        }
+    }
 
-                        // Load the false string
-                        if !self.add_code(0xA0) { return false; }
-                        if !self.add_code(*self.string_history.get("false").unwrap()) { return false; }
-                    },
-                    NonTerminalsAst::NotEq => {
-                        if !self.code_gen_compare(ast, children[0], symbol_table, false) { return false; }
-                         // We are printing a string, so X = 2
-                        if !self.add_code(0xA2) { return false; }
-                        if !self.add_code(0x02) { return false; }
+    // Adds the address of the 0x00 that is always right after the heap
+    fn add_zero_address(&mut self) -> bool {
+        let [low_order, high_order]: [u8; 2] = self.layout.zero_address().to_le_bytes();
+        if !self.add_code(low_order) { return false; }
+        return self.add_code(high_order);
+    }
 
-                        // Skip to the false string if it is false
-                        if !self.add_code(0xD0) { return false; }
-                        if !self.add_code(0x07) { return false; }
-                        
-                        // Load the true string and skip over the false string
-                        if !self.add_code(0xA0) { return false; }
-                        if !self.add_code(*self.string_history.get("true").unwrap()) { return false; }
-                        if !self.add_code(0xEC) { return false; }
-                        if !self.add_code(0xFF) { return false; }
-                        if !self.add_code(0x00) { return false; }
-                        if !self.add_code(0xD0) { return false; }
-                        if !self.add_code(0x02) { return false; }
+    // Adds the 2 byte address of the label for a JMP, which is backpatched once all of the labels are known
+    fn add_absolute_jump(&mut self, label: Label) -> bool {
+        self.absolute_jumps.push((self.jumps.len(), label));
+        if !self.add_jump() { return false; }
+        return self.add_jump();
+    }
 
-                        // Load the false string
-                        if !self.add_code(0xA0) { return false; }
-                        if !self.add_code(*self.string_history.get("false").unwrap()) { return false; }
-                   },
-                    _ => error!("Received {:?} when expecting addition or boolean expression for nonterminal print", non_terminal)
-                }
-            },
-            _ => error!("Received {:?} when expecting terminal or AST nonterminal for print in code gen", child)
+    // Adds a branch to the label, which is backpatched once all of the labels are known
+    fn add_branch(&mut self, label: Label) -> bool {
+        if self.long_branches {
+            // The label might be too far away for BNE, so take the branch to a JMP instead
+            if !self.add_code(0xD0) { return false; }
+            if !self.add_code(0x07) { return false; }
+            // Skip over the JMP when the branch is not taken
+            if !self.add_code(0xA2) { return false; }
+            if !self.add_code(0x01) { return false; }
+            if !self.add_code(0xEC) { return false; }
+            if !self.add_zero_address() { return false; }
+            if !self.add_code(0xD0) { return false; }
+            if !self.add_code(0x03) { return false; }
+            if !self.add_code(0x4C) { return false; }
+            return self.add_absolute_jump(label);
         }
 
-        // The x and y registers are all set up, so just add the sys call
-        if !self.add_code(0xFF) { return false; }
+        let jump_index: usize = self.jumps.len();
+        if !self.add_code(0xD0) { return false; }
+        if !self.add_jump() { return false; }
+        // The offset is from the address after the branch
+        self.branches.push((jump_index, label, self.code_pointer));
         return true;
     }
 
-    // Function to generate code for an addition statement
-    // Result is left in the accumulator
-    fn code_gen_add(&mut self, ast: &SyntaxTree, cur_index: NodeIndex, symbol_table: &mut SymbolTable, is_first: bool) -> bool {
-        nexus_log::log(
-            nexus_log::LogTypes::Debug,
-            nexus_log::LogSources::CodeGenerator,
-            format!("Starting code generation for addition expression in scope {}", symbol_table.cur_scope.unwrap())
-        );
+    // Runs the peephole optimizations on the code and moves everything that points into the code to match
+    fn optimize_code(&mut self) {
+        // The optimizer only sees the code, so its addresses start at 0
+        let code_start: usize = self.layout.load_address as usize;
+        let code_end: usize = self.code_pointer;
+        let label_addresses: HashSet<usize> = self.labels.values().map(|addr| *addr - code_start).collect();
+        let optimized: OptimizedCode = PeepholeOptimizer::new().optimize(&self.code_arr[code_start..code_end], &label_addresses);
+
+        // The code only gets shorter, so clear out the spots that are not used anymore
+        for i in code_start..code_end {
+            self.code_arr[i] = match optimized.code.get(i - code_start) {
+                Some(byte) => *byte,
+                None => CodeGenBytes::Empty
+            };
+        }
+        self.code_pointer = code_start + optimized.code.len();
 
-        // Get the child for addition
-        let children: Vec<NodeIndex> = (*ast).graph.neighbors(cur_index).collect();
-        let right_child: &SyntaxTreeNode = (*ast).graph.node_weight(children[0]).unwrap();
-        let left_child: &SyntaxTreeNode = (*ast).graph.node_weight(children[1]).unwrap();
+        let new_addresses: &Vec<usize> = &optimized.new_addresses;
+        for addr in self.labels.values_mut() {
+            *addr = code_start + new_addresses[*addr - code_start];
+        }
+        for (_, _, branch_end) in self.branches.iter_mut() {
+            *branch_end = code_start + new_addresses[*branch_end - code_start];
+        }
+        for statement in self.statement_starts.iter_mut() {
+            statement.address = code_start + new_addresses[statement.address - code_start];
+        }
+    }
 
-        // Make some space for the temporary data only if first addition
-        // Otherwise, use the current max temp index, which is the working temp location
-        let mut temp_addr: usize = self.temp_index - 1;
-        if is_first {
-            let temp_addr_option: Option<usize> = self.new_temp();
-            if temp_addr_option.is_none() {
-                return false;
+    // Computes the offset for every branch now that the address of each label is known
+    // Returns false if a branch is too far away from its label
+    fn resolve_branches(&mut self) -> bool {
+        let mut is_in_range: bool = true;
+        for (jump_index, label, branch_end) in self.branches.iter() {
+            let label_addr: usize = *self.labels.get(label).unwrap();
+            let offset: isize = label_addr as isize - *branch_end as isize;
+            // Branches wrap around the first 256 bytes, so they can reach anything when that is all of memory
+            if self.layout.image_size > 0x100 && !(-128..=127).contains(&offset) {
+                is_in_range = false;
             }
-            temp_addr = temp_addr_option.unwrap();
+            // Backwards branches wrap around to be negative
+            self.jumps[*jump_index] = offset as u8;
         }
+        for (jump_index, label) in self.absolute_jumps.iter() {
+            let label_addr: u16 = *self.labels.get(label).unwrap() as u16;
+            let [low_order, high_order]: [u8; 2] = label_addr.to_le_bytes();
+            self.jumps[*jump_index] = low_order;
+            self.jumps[*jump_index + 1] = high_order;
+        }
+        return is_in_range;
+    }
 
-        match right_child {
-            SyntaxTreeNode::Terminal(token) => {
-                match &token.token_type {
-                    TokenType::Digit(num) => {
-                        // Store right side digit in the accumulator
-                        if !self.add_code(0xA9) { return false; }
-                        if !self.add_code(*num) { return false; }
-                    },
-                    TokenType::Identifier(_) => {
-                        // Get the address needed from memory for the identifier
-                        let value_id_entry: &SymbolTableEntry = symbol_table.get_symbol_with_context(&token.text, token.position).unwrap(); 
-                        let value_static_offset: usize = self.static_table.get(&(token.text.to_owned(), value_id_entry.scope)).unwrap().to_owned();
-                        
-                        // Load the value into the accumulator
-                        if !self.add_code(0xAD) { return false; }
-                        if !self.add_var(value_static_offset) { return false; }
-                    },
-                    _ => error!("Received {:?} when expecting digit or id for right side of addition", token)
-                }
-
-                // Both digits and ids are in the accumulator, so move them to
-                // the res address for usage in the math operation
-                if !self.add_code(0x8D) { return false; }
-                if !self.add_temp(temp_addr) { return false; }
-                // We are using a new temporary value for temps, so increment the index
-            },
-            // Nonterminals are always add, so just call it
-            SyntaxTreeNode::NonTerminalAst(_) => if !self.code_gen_add(ast, children[0], symbol_table, false) { return false; },
-            _ => error!("Received {:?} when expecting terminal or AST nonterminal for right addition value", right_child)
+    // Function for creating the code for a variable declaration
+    fn code_gen_declare(&mut self, var: &Variable) -> bool {
+        // The variable needs its own byte in the static area
+        if !self.has_available_memory(Region::Static) {
+            self.report_out_of_memory(Region::Static);
+            return false;
         }
 
-        match left_child {
-            SyntaxTreeNode::Terminal(token) => {
-                match &token.token_type {
-                    TokenType::Digit(num) => {
-                        // Put left digit in acc
-                        if !self.add_code(0xA9) { return false; }
-                        if !self.add_code(*num) { return false; }
-
-                        // Perform the addition
-                        if !self.add_code(0x6D) { return false; }
-                        if !self.add_temp(temp_addr) { return false; }
-
-                        // Only store the result back in memory if we have more addition to do
-                        if !is_first {
-                            // Store it back in the resulting address
-                            if !self.add_code(0x8D) { return false; }
-                            if !self.add_temp(temp_addr) { return false; }
-                        } else {
-                            // We are done with the memory location, so can move
-                            // the pointer back over 1
-                            self.temp_index -= 1;
-                        }
-                    },
-                    _ => error!("Received {:?} when expecting a digit for left side of addition for code gen", token)
-                }
+        // Get the offset this variable will be on the stack
+        let static_offset: usize = self.static_table.len();
+        self.static_table.insert((var.name.to_owned(), var.scope), static_offset);
+
+        match var.var_type {
+            // Only integers and booleans are initialized
+            Type::Int | Type::Boolean => {
+                // Generate the code for the variable declaration
+                if !self.spill_accumulator() { return false; }
+                if !self.add_code(0xA9) { return false; }
+                if !self.add_code(0x00) { return false; }
+                if !self.add_code(0x8D) { return false; }
+                if !self.add_var(static_offset) { return false; }
             },
-            _ => error!("Received {:?} when expecting a terminal for the left side of addition for code gen", left_child)
+            // Strings do not get initialized
+            Type::String => {
+                // Nothing to do here, so may end up initially with dirty data
+                // from temp values
+            }
         }
 
         return true;
     }
 
-    // Function to generate code for comparisons
-    // Result is left in the Z flag and get_z_flag_vale function can be used
-    // afterwards to place z flag value into the accumulator
-    fn code_gen_compare(&mut self, ast: &SyntaxTree, cur_index: NodeIndex, symbol_table: &mut SymbolTable, is_eq: bool) -> bool {
-        nexus_log::log(
-            nexus_log::LogTypes::Debug,
-            nexus_log::LogSources::CodeGenerator,
-            format!("Starting code generation for comparison expression (is_eq = {}) in scope {}", is_eq, symbol_table.cur_scope.unwrap())
-        );
+    // Function for creating the code for an assignment
+    fn code_gen_assignment(&mut self, dest: &Variable, value: &Value) -> bool {
+        if !self.load_accumulator(value) { return false; }
 
-        // Get the child for comparison
-        let children: Vec<NodeIndex> = (*ast).graph.neighbors(cur_index).collect();
-        let right_child: &SyntaxTreeNode = (*ast).graph.node_weight(children[0]).unwrap();
-        let left_child: &SyntaxTreeNode = (*ast).graph.node_weight(children[1]).unwrap();
-
-        match left_child {
-            SyntaxTreeNode::Terminal(token) => {
-                match &token.token_type {
-                    TokenType::Identifier(_) => {
-                        // Get the address needed from memory for the identifier
-                        let value_id_entry: &SymbolTableEntry = symbol_table.get_symbol_with_context(&token.text, token.position).unwrap(); 
-                        let value_static_offset: usize = self.static_table.get(&(token.text.to_owned(), value_id_entry.scope)).unwrap().to_owned();
-                        
-                        // Load the value into the accumulator
-                        if !self.add_code(0xAD) { return false; }
-                        if !self.add_var(value_static_offset) { return false; }
-                    },
-                    TokenType::Digit(num) => {
-                        // Store the digit in memory
-                        if !self.add_code(0xA9) { return false; }
-                        if !self.add_code(*num) { return false; }
-                    },
-                    TokenType::Char(string) => {
-                        let string_addr: Option<u8> = self.store_string(string);
-                        if string_addr.is_some() {
-                            if !self.add_code(0xA9) { return false; }
-                            if !self.add_code(string_addr.unwrap()) { return false; }
-                        } else {
-                            return false;
-                        }
-                    },
-                    TokenType::Keyword(keyword) => {
-                        if !self.add_code(0xA9) { return false; }
-                        match &keyword {
-                            Keywords::True => if !self.add_code(0x01) { return false; },
-                            Keywords::False => if !self.add_code(0x00) { return false; },
-                            _ => error!("Received {:?} when expecting true or false for keywords in boolean expression", keyword)
-                        }
-                    },
-                    _ => error!("Received {:?} when expecting an Id, digit, char, or keyword for left side of boolean expression", token)
-                }
-            },
-            SyntaxTreeNode::NonTerminalAst(non_terminal) => {
-                match &non_terminal {
-                    NonTerminalsAst::Add => {
-                        if !self.code_gen_add(ast, children[1], symbol_table, true) { return false; }
-                    },
-                    NonTerminalsAst::IsEq => {
-                        if !self.code_gen_compare(ast, children[1], symbol_table, true) { return false; }
-                        if !self.get_z_flag_value() { return false; }
-                    },
-                    NonTerminalsAst::NotEq => {
-                        if !self.code_gen_compare(ast, children[1], symbol_table, false) { return false; }
-                        if !self.get_z_flag_value() { return false; }
-                    },
-                    _ => error!("Received {:?} for left side of nonterminal boolean expression, when expected Add, IsEq, or NotEq", non_terminal)
-                }
-            },
-            _ => error!("Received {:?} when expected terminal or AST nonterminal for left side of comparison in code gen", left_child)
-        }
+        // The data that we are storing is already in the accumulator
+        // so just run the code to store the data
+        let static_offset: usize = self.get_static_offset(dest);
+        if !self.add_code(0x8D) { return false; }
+        if !self.add_var(static_offset) { return false; }
+
+        return true;
+    }
 
-        // The left hand side is already in the ACC, so can store in temp memory
-        let left_temp_option: Option<usize> = self.new_temp();
-        if left_temp_option.is_none() {
+    // Function to generate code for an addition, where the result is left in the accumulator
+    fn code_gen_add(&mut self, dest: Temp, left: &Value, right: &Value) -> bool {
+        // ADC only adds from memory, so the right side goes there first
+        let right_operand: Option<MemoryOperand> = self.get_memory_operand(right);
+        if right_operand.is_none() {
             return false;
         }
-        let left_temp: usize = left_temp_option.unwrap();
 
-        if !self.add_code(0x8D) { return false; }
-        if !self.add_temp(left_temp) { return false; }
-
-        match right_child {
-            SyntaxTreeNode::Terminal(token) => {
-                match &token.token_type {
-                    TokenType::Identifier(_) => {
-                        // Get the address needed from memory for the identifier
-                        let value_id_entry: &SymbolTableEntry = symbol_table.get_symbol_with_context(&token.text, token.position).unwrap(); 
-                        let value_static_offset: usize = self.static_table.get(&(token.text.to_owned(), value_id_entry.scope)).unwrap().to_owned();
-                        
-                        // Load the value into the X register
-                        if !self.add_code(0xAE) { return false; }
-                        if !self.add_var(value_static_offset) { return false; }
-                    },
-                    TokenType::Digit(num) => {
-                        // Store the digit in X
-                        if !self.add_code(0xA2) { return false; }
-                        if !self.add_code(*num) { return false; }
-                    },
-                    TokenType::Char(string) => {
-                        let string_addr: Option<u8> = self.store_string(string);
-                        if string_addr.is_some() {
-                            if !self.add_code(0xA2) { return false; }
-                            if !self.add_code(string_addr.unwrap()) { return false; }
-                        } else {
-                            return false;
-                        }
-                    },
-                    TokenType::Keyword(keyword) => {
-                        if !self.add_code(0xA2) { return false; }
-                        match &keyword {
-                            Keywords::True => if !self.add_code(0x01) { return false; },
-                            Keywords::False => if !self.add_code(0x00) { return false; },
-                            _ => error!("Received {:?} when expecting true or false for keywords in boolean expression", keyword)
-                        }
-                    },
-                    _ => error!("Received {:?} when expecting an Id, digit, char, or keyword for left side of boolean expression", token)
-                }
-            },
-            SyntaxTreeNode::NonTerminalAst(non_terminal) => {
-                match &non_terminal {
-                    NonTerminalsAst::Add => {
-                        if !self.code_gen_add(ast, children[0], symbol_table, true) { return false; }
-                    },
-                    NonTerminalsAst::IsEq => {
-                        if !self.code_gen_compare(ast, children[0], symbol_table, true) { return false; }
-                        if !self.get_z_flag_value() { return false; }
-                    },
-                    NonTerminalsAst::NotEq => {
-                        if !self.code_gen_compare(ast, children[0], symbol_table, false) { return false; }
-                        if !self.get_z_flag_value() { return false; }
-                    },
-                    _ => error!("Received {:?} for right side of nonterminal boolean expression, when expected Add, IsEq, or NotEq", non_terminal)
-                }
+        if !self.load_accumulator(left) { return false; }
 
-                // The nonterminal result is in the ACC, so have to move to X
-                let temp_addr_option: Option<usize> = self.new_temp();
-                if temp_addr_option.is_none() {
-                    return false;
-                }
-                let temp_addr: usize = temp_addr_option.unwrap();
+        // Perform the addition
+        if !self.add_code(0x6D) { return false; }
+        if !self.add_memory_operand(right_operand.unwrap()) { return false; }
 
-                if !self.add_code(0x8D) { return false; }
-                if !self.add_temp(temp_addr) { return false; }
+        self.accumulator_temp = Some(dest);
+        return true;
+    }
 
-                if !self.add_code(0xAE) { return false; }
-                if !self.add_temp(temp_addr) { return false; }
-                self.temp_index -= 1;
-            },
-            _ => error!("Received {:?} when expected terminal or AST nonterminal for left side of comparison in code gen", left_child)
+    // Function to generate code for comparisons, where the result is left in the Z flag
+    fn code_gen_compare(&mut self, dest: Temp, left: &Value, right: &Value, is_eq: bool) -> bool {
+        // The comparison is going to overwrite the Z flag
+        if !self.spill_z_flag() { return false; }
+
+        // CPX compares X to memory, so the left side goes in memory and the right side goes in X
+        let left_operand: Option<MemoryOperand> = self.get_memory_operand(left);
+        if left_operand.is_none() {
+            return false;
         }
+        if !self.load_x_register(right) { return false; }
 
         if !self.add_code(0xEC) { return false; }
-        if !self.add_temp(left_temp) { return false; }
-
-        // We are done with this data
-        self.temp_index -= 1;
+        if !self.add_memory_operand(left_operand.unwrap()) { return false; }
 
         // Add code if the operation is for not equals
         // This effectively flips the Z flag
@@ -1092,10 +909,10 @@ impl CodeGenerator6502 {
             if !self.add_code(0x01) { return false; }
             // Compare with 0 to flip the Z flag
             if !self.add_code(0xEC) { return false; }
-            if !self.add_code(0xFF) { return false; }
-            if !self.add_code(0x00) { return false; }
+            if !self.add_zero_address() { return false; }
         }
 
+        self.z_flag_temp = Some(dest);
         return true;
     }
 
@@ -1114,149 +931,241 @@ impl CodeGenerator6502 {
         return true;
     }
 
-    fn code_gen_if(&mut self, ast: &SyntaxTree, cur_index: NodeIndex, symbol_table: &mut SymbolTable) -> bool {
-        nexus_log::log(
-            nexus_log::LogTypes::Debug,
-            nexus_log::LogSources::CodeGenerator,
-            format!("Starting code generation for if statement in scope {}", symbol_table.cur_scope.unwrap())
-        );
+    // Loads the address of the true or false string into Y based on the Z flag
+    fn load_bool_string(&mut self) -> bool {
+        // Skip to the false string if it is false
+        if !self.add_code(0xD0) { return false; }
+        if !self.add_code(0x07) { return false; }
+
+        // Load the true string and skip over the false string
+        if !self.add_code(0xA0) { return false; }
+        if !self.add_code(*self.string_history.get("true").unwrap()) { return false; }
+        if !self.add_code(0xEC) { return false; }
+        if !self.add_zero_address() { return false; }
+        if !self.add_code(0xD0) { return false; }
+        if !self.add_code(0x02) { return false; }
 
-        // Get the child for comparison
-        let children: Vec<NodeIndex> = (*ast).graph.neighbors(cur_index).collect();
-        let left_child: &SyntaxTreeNode = (*ast).graph.node_weight(children[1]).unwrap();
+        // Load the false string
+        if !self.add_code(0xA0) { return false; }
+        if !self.add_code(*self.string_history.get("false").unwrap()) { return false; }
 
-        // Starting address for the branch, but 0 will never be valid, so can have
-        // default value set to 0
-        let mut start_addr: u8 = 0x00;
-        // This is the index of the jump that will ultimately be backpatched
-        let jump_index: usize = self.jumps.len();
+        return true;
+    }
 
-        match left_child {
-            SyntaxTreeNode::NonTerminalAst(non_terminal) => {
-                match &non_terminal {
-                    // Evaluate the boolean expression for the if statement
-                    // The Z flag is set by these function calls
-                    NonTerminalsAst::IsEq => if !self.code_gen_compare(ast, children[1], symbol_table, true) { return false; },
-                    NonTerminalsAst::NotEq => if !self.code_gen_compare(ast, children[1], symbol_table, false) { return false; },
-                    _ => error!("Received {:?} when expecting IsEq or NotEq for nonterminal if expression", non_terminal)
+    // Function for generating code for a print statement
+    fn code_gen_print(&mut self, value: &Value, value_type: &Type) -> bool {
+        match value_type {
+            Type::Int | Type::String => {
+                // Sys call 1 prints the integer in Y and sys call 2 prints the string at the address in Y
+                match value {
+                    Value::Int(_) | Value::String(_) => {
+                        let constant_option: Option<u8> = self.get_constant(value);
+                        if constant_option.is_none() {
+                            return false;
+                        }
+                        if !self.add_code(0xA0) { return false; }
+                        if !self.add_code(constant_option.unwrap()) { return false; }
+                    },
+                    _ => {
+                        // Load the value to Y from memory (wish there was TAY)
+                        let operand_option: Option<MemoryOperand> = self.get_memory_operand(value);
+                        if operand_option.is_none() {
+                            return false;
+                        }
+                        if !self.add_code(0xAC) { return false; }
+                        if !self.add_memory_operand(operand_option.unwrap()) { return false; }
+                    }
                 }
-                // Add the branch code
-                if !self.add_code(0xD0) { return false; }
-                if !self.add_jump() { return false; }
-                start_addr = self.code_pointer.to_owned();
+
+                let sys_call: u8 = if *value_type == Type::Int { 0x01 } else { 0x02 };
+                if !self.add_code(0xA2) { return false; }
+                if !self.add_code(sys_call) { return false; }
             },
-            SyntaxTreeNode::Terminal(token) => {
-                match &token.token_type {
-                    TokenType::Keyword(Keywords::True) => { /* Small optimization because no comparison is needed */ }
-                    TokenType::Keyword(Keywords::False) => {
-                        // No code should be generated here because the if-statement is just dead
-                        // code and will never be reached, so no point in trying to store the code
-                        // with the limited space that we already have (256 bytes)
-                        return true;
+            Type::Boolean => {
+                match value {
+                    Value::Bool(bool_value) => {
+                        // Y = the address of the string for the value
+                        let bool_str: &str = if *bool_value { "true" } else { "false" };
+                        if !self.add_code(0xA0) { return false; }
+                        if !self.add_code(*self.string_history.get(bool_str).unwrap()) { return false; }
+
+                        // X = 2 for the sys call
+                        if !self.add_code(0xA2) { return false; }
+                        if !self.add_code(0x02) { return false; }
+                    },
+                    Value::Temp(temp) if self.z_flag_temp == Some(*temp) => {
+                        // If it is true or false is already in the Z flag
+                        self.z_flag_temp = None;
+
+                        // We are printing a string, so X = 2
+                        if !self.add_code(0xA2) { return false; }
+                        if !self.add_code(0x02) { return false; }
+                        if !self.load_bool_string() { return false; }
+                    },
+                    _ => {
+                        let operand_option: Option<MemoryOperand> = self.get_memory_operand(value);
+                        if operand_option.is_none() {
+                            return false;
+                        }
+                        if !self.spill_z_flag() { return false; }
+
+                        // Compare the value with true
+                        if !self.add_code(0xA2) { return false; }
+                        if !self.add_code(0x01) { return false; }
+                        if !self.add_code(0xEC) { return false; }
+                        if !self.add_memory_operand(operand_option.unwrap()) { return false; }
+                        if !self.load_bool_string() { return false; }
+
+                        // We are printing a string, so X = 2
+                        if !self.add_code(0xA2) { return false; }
+                        if !self.add_code(0x02) { return false; }
                     }
-                    _ => error!("Received {:?} when expecting true or false for if expression terminals", token)
                 }
-            },
-            _ => error!("Received {:?} when expecting AST nonterminal or a terminal", left_child)
+            }
         }
 
-        // Generate the code for the body
-        if !self.code_gen_block(ast, children[0], symbol_table) { return false; }
+        // The x and y registers are all set up, so just add the sys call
+        if !self.add_code(0xFF) { return false; }
+        return true;
+    }
+
+    // Function for generating code for an unconditional jump
+    fn code_gen_jump(&mut self, label: Label) -> bool {
+        if !self.spill_z_flag() { return false; }
 
-        // If there was a comparison to make, there is a start addr
-        if start_addr != 0x00 {
-            // Compute the difference and set it in the vector for use in backpatching
-            let branch_offset: u8 = self.code_pointer - start_addr;
-            self.jumps[jump_index] = branch_offset;
+        if self.long_branches {
+            // JMP can go anywhere in memory
+            if !self.add_code(0x4C) { return false; }
+            return self.add_absolute_jump(label);
         }
 
-        return true;
+        // Set X to 1
+        if !self.add_code(0xA2) { return false; }
+        if !self.add_code(0x01) { return false; }
+        // The byte after the heap is always 0, so comparing it to 1 will result in Z = 0,
+        // so the branch will always be taken
+        if !self.add_code(0xEC) { return false; }
+        if !self.add_zero_address() { return false; }
+        return self.add_branch(label);
     }
 
-    fn code_gen_while(&mut self, ast: &SyntaxTree, cur_index: NodeIndex, symbol_table: &mut SymbolTable) -> bool {
-         nexus_log::log(
+    // Function for generating code to jump to the label if the condition is false
+    fn code_gen_branch_if_false(&mut self, condition: &Value, label: Label) -> bool {
+        match condition {
+            // The Z flag is clear when the condition is false, which is when BNE branches
+            Value::Temp(temp) if self.z_flag_temp == Some(*temp) => {
+                self.z_flag_temp = None;
+                return self.add_branch(label);
+            },
+            Value::Bool(true) => return true,
+            Value::Bool(false) => return self.code_gen_jump(label),
+            _ => {
+                let operand_option: Option<MemoryOperand> = self.get_memory_operand(condition);
+                if operand_option.is_none() {
+                    return false;
+                }
+                if !self.spill_z_flag() { return false; }
+
+                // Compare the condition with true
+                if !self.add_code(0xA2) { return false; }
+                if !self.add_code(0x01) { return false; }
+                if !self.add_code(0xEC) { return false; }
+                if !self.add_memory_operand(operand_option.unwrap()) { return false; }
+                return self.add_branch(label);
+            }
+        }
+    }
+
+    // Generates the code for a single IR instruction
+    fn code_gen_instruction(&mut self, instruction: &Instruction) -> bool {
+        nexus_log::log(
             nexus_log::LogTypes::Debug,
             nexus_log::LogSources::CodeGenerator,
-            format!("Starting code generation for while statement in scope {}", symbol_table.cur_scope.unwrap())
+            format!("Starting code generation for {}", instruction)
         );
 
-        // Get the child for comparison
-        let children: Vec<NodeIndex> = (*ast).graph.neighbors(cur_index).collect();
-        let left_child: &SyntaxTreeNode = (*ast).graph.node_weight(children[1]).unwrap();
-
-        // Save the current address for the loop
-        let loop_start_addr: u8 = self.code_pointer.to_owned();
-
-        // Starting address for the body of the while structure,
-        // but 0 will never be valid, so can have default value set to 0
-        let mut body_start_addr: u8 = 0x00;
-        // This is the index of the body jump if a condition eveluates to false
-        // that will ultimately be backpatched
-        let body_jump_index: usize = self.jumps.len();
-
-        match left_child {
-            SyntaxTreeNode::NonTerminalAst(non_terminal) => {
-                match &non_terminal {
-                    // Evaluate the boolean expression for the while statement
-                    // The Z flag is set by these function calls
-                    NonTerminalsAst::IsEq => if !self.code_gen_compare(ast, children[1], symbol_table, true) { return false; },
-                    NonTerminalsAst::NotEq => if !self.code_gen_compare(ast, children[1], symbol_table, false) { return false; },
-                    _ => error!("Received {:?} when expecting IsEq or NotEq for nonterminal if expression", non_terminal)
-                }
-                // Add the branch code
-                if !self.add_code(0xD0) { return false; }
-                if !self.add_jump() { return false; }
-                body_start_addr = self.code_pointer.to_owned();
+        let res: bool = match instruction {
+            Instruction::StatementStart { span, condition } => {
+                self.add_statement_start(*span, *condition);
+                true
             },
-            SyntaxTreeNode::Terminal(token) => {
-                match &token.token_type {
-                    TokenType::Keyword(Keywords::True) => { /* Small optimization because no comparison is needed */ }
-                    TokenType::Keyword(Keywords::False) => {
-                        // No code should be generated here because the while-statement is just dead
-                        // code and will never be reached, so no point in trying to store the code
-                        // with the limited space that we already have (256 bytes)
-                        return true;
-                    }
-                    _ => error!("Received {:?} when expecting true or false for while expression terminals", token)
-                }
+            Instruction::Declare(var) => self.code_gen_declare(var),
+            Instruction::Assign { dest, value } => self.code_gen_assignment(dest, value),
+            Instruction::Add { dest, left, right } => self.code_gen_add(*dest, left, right),
+            Instruction::Compare { dest, left, right, is_eq } => self.code_gen_compare(*dest, left, right, *is_eq),
+            Instruction::Print { value, value_type } => self.code_gen_print(value, value_type),
+            Instruction::Label(label) => {
+                self.labels.insert(*label, self.code_pointer);
+                true
             },
-            _ => error!("Received {:?} when expecting AST nonterminal or a terminal", left_child)
-        }
+            Instruction::Jump(label) => self.code_gen_jump(*label),
+            Instruction::BranchIfFalse { condition, target } => self.code_gen_branch_if_false(condition, *target)
+        };
 
-        // Generate the code for the body
-        if !self.code_gen_block(ast, children[0], symbol_table) { return false; }
+        // Temp memory that was read by the instruction can be used again
+        self.free_temps.append(&mut self.released_temps);
+        return res;
+    }
 
-        // Get the position in the vector for the unconditional branch
-        let unconditional_jump_index: usize = self.jumps.len();
-        // Set X to 1
-        if !self.add_code(0xA2) { return false; }
-        if !self.add_code(0x01) { return false; }
-        // 0xFF is always 0, so comparing it to 1 will result in Z = 0,
-        // so the branch will always be taken
-        if !self.add_code(0xEC) { return false; }
-        if !self.add_code(0xFF) { return false; }
-        if !self.add_code(0x00) { return false; }
-        if !self.add_code(0xD0) { return false; }
-        if !self.add_jump() { return false; }
+    // Generates the code for the whole program and optimizes it, where the branches still have to be resolved
+    fn generate_program(&mut self, ir: &IrProgram, long_branches: bool) -> bool {
+        // Reset the array and empty it out
+        self.code_arr.clear();
+        self.code_arr.resize(self.layout.image_size, CodeGenBytes::Empty);
+
+        self.code_pointer = self.layout.load_address as usize;
+        self.heap_pointer = self.layout.heap_start as u16;
+
+        self.static_table.clear();
+        self.num_temps = 0;
+        self.free_temps.clear();
+        self.released_temps.clear();
+        self.temp_memory.clear();
+        self.accumulator_temp = None;
+        self.z_flag_temp = None;
+        self.string_history.clear();
+        self.jumps.clear();
+        self.labels.clear();
+        self.branches.clear();
+        self.absolute_jumps.clear();
+        self.long_branches = long_branches;
+        self.statement_starts.clear();
+        self.image.clear();
+        self.diagnostics.clear();
 
-        // If there was a comparison to make, there is a start addr for the body
-        // to skip over in case evaluate to false
-        if body_start_addr != 0x00 {
-            // Compute the difference and set it in the vector for use in backpatching
-            let conditional_branch_offset: u8 = self.code_pointer - body_start_addr;
-            self.jumps[body_jump_index] = conditional_branch_offset;
+        // We are going to store the strings false and true to print them
+        // out instead of 0 and 1
+        if self.store_string("false").is_none() || self.store_string("true").is_none() {
+            return false;
+        }
+
+        // Generate the code for the program
+        // Nothing else can be generated once the memory runs out
+        let is_generated: bool = ir.instructions.iter().all(|instruction| self.code_gen_instruction(instruction));
+
+        // All programs end with 0x00, which is HALT
+        if !is_generated || !self.add_code(0x00) {
+            return false;
         }
-        
-        // The branch offset is the 2s complement difference between the current position
-        // and the start of the loop, so take the difference and negate and add 1
-        let unconditional_branch_offset: u8 = !(self.code_pointer - loop_start_addr) + 1;
-        // Set the unconditional branch offset in the jump
-        self.jumps[unconditional_jump_index] = unconditional_branch_offset;
 
+        self.optimize_code();
         return true;
     }
 
-    fn display_code(&mut self, program_number: &u32) {
+    // Creates the space-separated hex string of the executable image
+    fn create_image(&self) -> Vec<u8> {
+        // Everything has been backpatched, so only code, data, and empty spots are left
+        return self.code_arr.iter().map(|byte| {
+            match byte {
+                CodeGenBytes::Code(code) => *code,
+                CodeGenBytes::Data(data) => *data,
+                CodeGenBytes::Empty => 0x00,
+                _ => panic!("Received {:?} in the executable image after backpatching", byte)
+            }
+        }).collect();
+    }
+
+    fn display_code_tab(code_str: &str, listing: Option<&str>, program_number: &u32) {
         let window: Window = web_sys::window().expect("Should be able to get the window");
         let document: Document = window.document().expect("Should be able to get the document");
 
@@ -1324,17 +1233,21 @@ impl CodeGenerator6502 {
         // The div is a container for the content of the ast info
         display_area_class_list.add_3("container", "text-center", "code-gen-pane").expect("Should be able to add the classes");
 
-        // Get the array of values but only keep the hex digits and spaces
-        let mut code_str: String = format!("{:?}", self.code_arr);
-        code_str.retain(|c| c != ',' && c != '[' && c != ']');
-
         // This is the element that the code is in
         let code_elem: Element = document.create_element("p").expect("Should be able to create the element");
         code_elem.set_class_name("code-text");
-        code_elem.set_inner_html(&code_str);
+        code_elem.set_inner_html(code_str);
 
         display_area_div.append_child(&code_elem).expect("Should be able to add the child node");
 
+        // The listing goes below the hex so students can see what each byte does
+        if let Some(listing) = listing {
+            let listing_elem: Element = document.create_element("pre").expect("Should be able to create the element");
+            listing_elem.set_class_name("code-listing");
+            listing_elem.set_text_content(Some(listing));
+            display_area_div.append_child(&listing_elem).expect("Should be able to add the child node");
+        }
+
         // This is the button to copy to the clipboard
         let copy_btn: Element = document.create_element("button").expect("Should be able to create the element");
         copy_btn.set_inner_html("Copy to Clipboard");
@@ -1342,9 +1255,10 @@ impl CodeGenerator6502 {
         display_area_div.append_child(&copy_btn).expect("Should be able to add the child node");
 
         // Create a function that will be used as the event listener and add it to the copy button
+        let code_str_clipboard: String = String::from(code_str);
         let copy_btn_fn: Closure<dyn FnMut()> = Closure::wrap(Box::new(move || {
             // Call the JS function that handles the clipboard
-            set_clipboard(&code_str);
+            set_clipboard(&code_str_clipboard);
         }) as Box<dyn FnMut()>);
         copy_btn.add_event_listener_with_callback("click", copy_btn_fn.as_ref().unchecked_ref()).expect("Should be able to add the event listener");
         copy_btn_fn.forget();
@@ -1352,8 +1266,40 @@ impl CodeGenerator6502 {
         // Add the div to the pane
         content_area.append_child(&display_area_div).expect("Should be able to add the child node");
     }
+}
 
-    pub fn clear_display() {
+impl Backend for CodeGenerator6502 {
+    fn name(&self) -> &'static str {
+        return "6502";
+    }
+
+    fn display_name(&self) -> &'static str {
+        return "6502";
+    }
+
+    fn file_extension(&self) -> &'static str {
+        return "hex";
+    }
+
+    fn executable_extension(&self) -> &'static str {
+        return "bin";
+    }
+
+    // The image is loaded straight into memory, so it is already the executable
+    fn create_executable(&self, code: &Artifact) -> Result<Vec<u8>, String> {
+        match code {
+            Artifact::Image(image) => return Ok(image.to_owned()),
+            _ => return Err(format!("Expected a 6502 image"))
+        }
+    }
+
+    // Shows the hex of the image with the listing below it
+    fn display_code(&self, code: &Artifact, listing: Option<&str>, program_number: &u32) {
+        CodeGenerator6502::display_code_tab(&code.to_string(), listing, program_number);
+    }
+
+    // Removes the code of every program from the webpage
+    fn clear_display(&self) {
         // Get the preliminary objects
         let window: Window = web_sys::window().expect("Should be able to get the window");
         let document: Document = window.document().expect("Should be able to get the document");
@@ -1364,4 +1310,82 @@ impl CodeGenerator6502 {
         let content_area: Element = document.get_element_by_id("code-gen-tab-content").expect("Should be able to find the element");
         content_area.set_inner_html("");
     }
+
+    // Disassembles the image and labels it with the variables, strings, and statements from the code generation
+    fn create_listing(&self, source_code: &str) -> Option<String> {
+        if self.image.is_empty() {
+            return None;
+        }
+
+        let static_start: usize = self.get_static_start();
+        let mut variables: Vec<(u16, String)> = self.static_table.iter()
+            .map(|((id, scope), offset)| ((static_start + *offset) as u16, format!("{}_{}", id, scope)))
+            .collect();
+        variables.sort();
+
+        let statements: Vec<(u16, String)> = self.statement_starts.iter().map(|statement| {
+            let (keyword, text_span): (Option<&str>, Span) = match statement.condition {
+                Some((keyword, condition_span)) => (Some(keyword), condition_span),
+                None => (None, statement.span)
+            };
+            // Expressions can go over multiple lines, but each statement is shown on 1 line
+            let text: String = source_code[text_span.start..text_span.end].replace(['\n', '\r', '\t'], " ");
+            let description: String = match keyword {
+                Some(keyword) => format!("{} {}", keyword, text),
+                None => text
+            };
+            (statement.address as u16, format!("{}:{} {}", statement.span.line, statement.span.col, description))
+        }).collect();
+
+        let symbols: ImageSymbols = ImageSymbols {
+            code_start: self.layout.load_address,
+            code_end: self.code_pointer as u16,
+            variables,
+            temp_start: self.heap_pointer,
+            num_temps: self.num_temps,
+            zero_address: self.layout.zero_address(),
+            strings: self.string_history.iter().map(|(string, addr)| (*addr, string.to_owned())).collect(),
+            statements
+        };
+        return Some(listing_6502::create_listing(&self.image, &symbols));
+    }
+
+
+    // Generates the executable image for the program
+    fn generate_code(&mut self, ir: &IrProgram) -> PhaseResult<Artifact> {
+        let mut is_generated: bool = self.generate_program(ir, false);
+        if is_generated && !self.resolve_branches() {
+            nexus_log::log(
+                nexus_log::LogTypes::Info,
+                nexus_log::LogSources::CodeGenerator,
+                String::from("A branch is too far away from its label, so generating the code again with JMP")
+            );
+            is_generated = self.generate_program(ir, true) && self.resolve_branches();
+        }
+
+        if is_generated {
+            self.backpatch_addresses();
+
+            nexus_log::log(
+                nexus_log::LogTypes::Info,
+                nexus_log::LogSources::CodeGenerator,
+                format!("Code generation completed successfully")
+            );
+
+            self.image = self.create_image();
+            return PhaseResult::new(Some(Artifact::Image(self.image.to_owned())), std::mem::take(&mut self.diagnostics));
+        }
+
+        nexus_log::log(
+            nexus_log::LogTypes::Error,
+            nexus_log::LogSources::CodeGenerator,
+            format!("Code generation failed")
+        );
+
+        return PhaseResult::new(None, std::mem::take(&mut self.diagnostics));
+    }
+
+    fn set_memory_layout(&mut self, layout: &MemoryLayout) {
+        self.layout = *layout;
+    }
 }
@@ -1,9 +1,11 @@
-use log::*;
 
-use crate::nexus::{syntax_tree::SyntaxTree, syntax_tree_node::*, symbol_table::*};
-use crate::nexus::token::{TokenType, Keywords};
+use crate::nexus::symbol_table::Type;
+use crate::nexus::ir::{self, IrProgram, Instruction, Variable, Temp, Label, Value};
+use crate::nexus::register_allocator::{RegisterAllocator, Allocation, Name};
+use crate::nexus::backend::{Backend, Artifact};
+use crate::nexus::diagnostic::PhaseResult;
+use crate::nexus::riscv_assembler;
 use crate::util::nexus_log;
-use petgraph::graph::{NodeIndex};
 
 use std::collections::HashMap;
 use web_sys::{Document, Window, Element, DomTokenList};
@@ -23,141 +25,58 @@ extern "C" {
 // The struct for the code generator
 #[derive (Debug)]
 pub struct CodeGeneratorRiscV {
-    // The current max scope we have seen so far, which are encountered in
-    // sequential order
-    max_scope: usize,
-    
     // The array for the code
     code_arr: Vec<String>,
 
-    // The array for the variables
-    static_arr: Vec<String>,
+    // The array for the variables along with their size in bytes
+    static_arr: Vec<(usize, String)>,
     
     // The array for strings / heap data
     heap_arr: Vec<String>,
 
-    // Index for the temoprary data
-    temp_index: usize,
-
     // Hashmap to keep track of the strings being stored on the heap
     string_history: HashMap<String, usize>,
 
-    // The number of if statements
-    if_count: usize,
+    // The registers for the variables and temps of the current program
+    allocation: Option<Allocation>,
+
+    // The stack offsets of the temps that were spilled
+    temp_offsets: HashMap<Temp, usize>,
 
-    // The number of while statements
-    while_count: usize
+    // The number of bits in an integer for the current program, which is either a byte or a full register
+    int_bits: u32
 }
 
+// Variables are kept in the saved registers and temps are in the temporary registers
+// t0 and t1 are left for loading spilled values and constants, and the print subroutines only
+// change temporary registers, which is safe because a temp is never live across a print
+const VAR_REGISTERS: [&str; 12] = ["s0", "s1", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11"];
+const TEMP_REGISTERS: [&str; 5] = ["t2", "t3", "t4", "t5", "t6"];
+
 impl CodeGeneratorRiscV {
     pub fn new() -> Self {
         return CodeGeneratorRiscV {
-            max_scope: usize::MAX,
             code_arr: Vec::new(),
             static_arr: Vec::new(),
             heap_arr: Vec::new(),
-            temp_index: 0,
             string_history: HashMap::new(),
-            if_count: 0,
-            while_count: 0
+            allocation: None,
+            temp_offsets: HashMap::new(),
+            int_bits: ir::DEFAULT_INT_BITS
         };
     }
 
-    pub fn generate_code(&mut self, ast: &SyntaxTree, symbol_table: &mut SymbolTable, program_number: &u32) {
-        // Make sure the current scope is set to be a flag for none
-        self.max_scope = usize::MAX;
-        
-        self.code_arr.clear();
-        self.static_arr.clear();
-        self.heap_arr.clear();
-
-        // Initialize the basic data for printing functionality
-        self.heap_arr.push(format!("new_line: .ascii \"\\n\""));
-        self.heap_arr.push(format!("print_int_char: .byte 0"));
-        
-        self.temp_index = 0;
-        self.string_history.clear();
-        self.if_count = 0;
-        self.while_count = 0;
-
-        // Store the actual strings "true" and "false"
-        self.store_string("false");
-        self.store_string("true");
-
-        // Generate the code for the program
-        self.code_gen_block(ast, NodeIndex::new((*ast).root.unwrap()), symbol_table);
-        
-        // Add the code to exit the program
-        self.code_arr.push(format!("li  a7, 93"));
-        self.code_arr.push(format!("li  a0, 0"));
-        self.code_arr.push(format!("ecall"));
-
-        // Add a function for printing an integer
-        self.add_print_int_code();
-        self.add_print_string_code();
-        self.add_print_boolean_code();
-        self.add_print_new_line_code();
-        self.add_compare_eq_code();
-        self.add_compare_neq_code();
-       
-        nexus_log::log(
-            nexus_log::LogTypes::Info,
-            nexus_log::LogSources::CodeGenerator,
-            format!("Code generation completed successfully")
-        );
-
-        nexus_log::log(
-            nexus_log::LogTypes::Info,
-            nexus_log::LogSources::Nexus,
-            format!("Executable image for program {} is below", *program_number)
-        );
-
-        self.display_code(program_number);
+    // Integers that are a full register take up a double word in memory instead of a byte
+    fn is_wide_int(&self) -> bool {
+        return self.int_bits > ir::DEFAULT_INT_BITS;
     }
 
-    fn code_gen_block(&mut self, ast: &SyntaxTree, cur_index: NodeIndex, symbol_table: &mut SymbolTable) {
-        // If this is the first block, then the first scope is 0
-        if self.max_scope == usize::MAX {
-            self.max_scope = 0;
-        } else {
-            // Otherwise just add 1
-            self.max_scope += 1;
-        }
-
-        nexus_log::log(
-            nexus_log::LogTypes::Debug,
-            nexus_log::LogSources::CodeGenerator,
-            format!("Starting code generation for the block for scope {}", self.max_scope)
-        );
-
-        // Manually set the current scope because we are not able to look down
-        // in the symbol table
-        symbol_table.set_cur_scope(self.max_scope);
-
-        // The current node is the block, so we need to loop through each of its children
-        let neighbors: Vec<NodeIndex> = (*ast).graph.neighbors(cur_index).collect();
-
-        for neighbor_index in neighbors.into_iter().rev() {
-            let child: &SyntaxTreeNode = (*ast).graph.node_weight(neighbor_index).unwrap();
-            
-            match child {
-                SyntaxTreeNode::NonTerminalAst(non_terminal) => {
-                    match non_terminal {
-                        NonTerminalsAst::Block => self.code_gen_block(ast, neighbor_index, symbol_table),
-                        NonTerminalsAst::VarDecl => self.code_gen_var_decl(ast, neighbor_index, symbol_table),
-                        NonTerminalsAst::Assign => self.code_gen_assignment(ast, neighbor_index, symbol_table),
-                        NonTerminalsAst::Print => self.code_gen_print(ast, neighbor_index, symbol_table),
-                        NonTerminalsAst::If => self.code_gen_if(ast, neighbor_index, symbol_table),
-                        NonTerminalsAst::While => self.code_gen_while(ast, neighbor_index, symbol_table),
-                        _ => error!("Received {:?} when expecting an AST nonterminal statement in a block", non_terminal)
-                    }
-                }
-                _ => error!("Received {:?} when expecting an AST nonterminal for code gen in a block", child)
-            }
+    // Temps can hold an integer, so they take up a double word on the stack when integers are wide
+    fn get_temp_size(&self) -> usize {
+        if self.is_wide_int() {
+            return 8;
         }
-
-        // Exit the current scope
-        symbol_table.end_cur_scope();
+        return 4;
     }
 
     fn add_print_int_code(&mut self) {
@@ -181,15 +100,28 @@ impl CodeGeneratorRiscV {
         self.code_arr.push(format!("li  t1, 0"));
 
         // t2 is what we are dividing by to get the digit
-        // Starts with 100 because a byte is no longer than 3 digits long in base 10
-        self.code_arr.push(format!("li  t2, 100"));
+        // Starts with the place of the first digit of the largest integer, such as 100 for a byte
+        let num_digits: u32 = ir::max_int(self.int_bits).to_string().len() as u32;
+        self.code_arr.push(format!("li  t2, {}", 10u64.pow(num_digits - 1)));
 
-        // No more than 3 iterations of the loop
-        self.code_arr.push(format!("li  t3, 3"));
+        // No more than 1 iteration of the loop for each digit
+        self.code_arr.push(format!("li  t3, {}", num_digits));
 
         // 10 has to be stored for later use
         self.code_arr.push(format!("li  t4, 10"));
 
+        if self.is_wide_int() {
+            // A full register has 20 digits, so the leading zeros are skipped instead of printed
+            // The last digit is always printed, which is when the divisor gets to 1
+            self.code_arr.push(format!("li  t6, 1"));
+            self.code_arr.push(format!("print_int_skip:"));
+            self.code_arr.push(format!("bgeu  t0, t2, print_int_loop"));
+            self.code_arr.push(format!("bgeu  t6, t2, print_int_loop"));
+            self.code_arr.push(format!("divu  t2, t2, t4"));
+            self.code_arr.push(format!("addi  t1, t1, 1"));
+            self.code_arr.push(format!("j  print_int_skip"));
+        }
+
         // Create the label for the loop
         self.code_arr.push(format!("print_int_loop:"));
         
@@ -283,67 +215,36 @@ impl CodeGeneratorRiscV {
         self.code_arr.push(format!("ret"));
     }
 
-    fn add_compare_eq_code(&mut self) {
-        // Create the label for comparing equality between 2 values
-        self.code_arr.push(format!("compare_eq:"));
-
-        // Assume both values are in a0 and a1
-        self.code_arr.push(format!("beq  a0, a1, compare_eq_true"));
-
-        // Result stored in a0
-        self.code_arr.push(format!("li  a0, 0"));
-        self.code_arr.push(format!("j  compare_eq_ret"));
-
-        // Create the label for storing the true value
-        self.code_arr.push(format!("compare_eq_true:"));
-        self.code_arr.push(format!("li  a0, 1"));
-
-        // Return form the subroutine
-        self.code_arr.push(format!("compare_eq_ret:"));
-        self.code_arr.push(format!("ret"));
-    }
-
-    fn add_compare_neq_code(&mut self) {
-        // Create the label for comparing equality between 2 values
-        self.code_arr.push(format!("compare_neq:"));
-
-        // Assume both values are in a0 and a1
-        self.code_arr.push(format!("bne  a0, a1, compare_neq_true"));
-
-        // Result stored in a0
-        self.code_arr.push(format!("li  a0, 0"));
-        self.code_arr.push(format!("j  compare_neq_ret"));
-
-        // Create the label for storing the true value
-        self.code_arr.push(format!("compare_neq_true:"));
-        self.code_arr.push(format!("li  a0, 1"));
-
-        // Return form the subroutine
-        self.code_arr.push(format!("compare_neq_ret:"));
-        self.code_arr.push(format!("ret"));
-    }
-
     fn create_output_string(&mut self) -> String {
         let mut output_builder: Builder = Builder::default();
         
-        output_builder.append(".section .text<br>");
-        output_builder.append(".global _start<br>");
-        output_builder.append("_start:<br>");
-        output_builder.append("nop<br>");
+        output_builder.append(".section .text\n");
+        output_builder.append(".global _start\n");
+        output_builder.append("_start:\n");
+        output_builder.append("nop\n");
         for code in self.code_arr.iter() {
             output_builder.append(code.as_str());
-            output_builder.append("<br>");
+            output_builder.append("\n");
         }
 
-        //output_builder.append(".section .data\n");
-        for static_data in self.static_arr.iter() {
+        // Variables are changed by the program, so they go in the writable data section
+        output_builder.append(".section .data\n");
+        // The largest variables go first after aligning to their size, so every variable ends up aligned
+        let mut static_data_arr: Vec<&(usize, String)> = self.static_arr.iter().collect();
+        static_data_arr.sort_by_key(|(size, _)| std::cmp::Reverse(*size));
+        if let Some((max_size, _)) = static_data_arr.first() {
+            if *max_size > 1 {
+                output_builder.append(format!(".align {}\n", max_size.trailing_zeros()).as_str());
+            }
+        }
+        for (_, static_data) in static_data_arr.iter() {
             output_builder.append(static_data.as_str());
-            output_builder.append("<br>");
+            output_builder.append("\n");
         }
 
         for heap_data in self.heap_arr.iter() {
             output_builder.append(heap_data.as_str());
-            output_builder.append("<br>");
+            output_builder.append("\n");
         }
 
         return output_builder.string().unwrap();
@@ -374,559 +275,283 @@ impl CodeGeneratorRiscV {
         }
     }
 
-    // Function for creating the code for a variable declaration
-    fn code_gen_var_decl(&mut self, ast: &SyntaxTree, cur_index: NodeIndex, symbol_table: &mut SymbolTable) {
-        nexus_log::log(
-            nexus_log::LogTypes::Debug,
-            nexus_log::LogSources::CodeGenerator,
-            format!("Starting code generation for variable declaration statement in scope {}", symbol_table.cur_scope.unwrap())
-        );
-
-        let children: Vec<NodeIndex> = (*ast).graph.neighbors(cur_index).collect();
-        let id_node: &SyntaxTreeNode = (*ast).graph.node_weight(children[0]).unwrap();
-
-        match id_node {
-            SyntaxTreeNode::Terminal(token) => {
-                // Get the symbol table entry to get the type of the variable
-                let symbol_table_entry: &SymbolTableEntry = symbol_table.get_symbol_with_context(&token.text, token.position).unwrap();
-                match symbol_table_entry.symbol_type {
-                    // Only integers and booleans are initialized
-                    Type::Int | Type::Boolean => {
-                        self.static_arr.push(format!("{}_{}: .byte 0", token.text, symbol_table_entry.scope));
-                        // Generate the code for the variable initialization to 1
-                        self.code_arr.push(format!("la  t1, {}_{}", token.text, symbol_table_entry.scope));
-                        self.code_arr.push(format!("li  t0, 0"));
-                        self.code_arr.push(format!("sb  t0, 0(t1)"));
-                    },
-                    // Strings do not get initialized
-                    Type::String => {
-                        // Only have to create the static entry here
-                        // Since it is a string on the heap, we have to store the address
-                        // which is a full word
-                        self.static_arr.push(format!("{}_{}: .word 0", token.text, symbol_table_entry.scope));
-                    }
-                }
-            },
-            _ => error!("Received {:?} when expecting terminal for var decl child in code gen", id_node)
-        }
+    // Gets the register a name was given, or None if it was spilled
+    fn get_register(&self, name: &Name) -> Option<&'static str> {
+        return self.allocation.as_ref().and_then(|allocation| allocation.get_register(name));
     }
 
-    // Function for creating the code for an assignment
-    fn code_gen_assignment(&mut self, ast: &SyntaxTree, cur_index: NodeIndex, symbol_table: &mut SymbolTable) {
-        nexus_log::log(
-            nexus_log::LogTypes::Debug,
-            nexus_log::LogSources::CodeGenerator,
-            format!("Starting code generation for assignment statement in scope {}", symbol_table.cur_scope.unwrap())
-        );
+    // Gets the offset of a spilled temp from the stack pointer, where each temp has its own word or double word
+    fn get_temp_offset(&self, temp: Temp) -> usize {
+        return self.temp_offsets[&temp];
+    }
 
-        let children: Vec<NodeIndex> = (*ast).graph.neighbors(cur_index).collect();
-        let value_node: &SyntaxTreeNode = (*ast).graph.node_weight(children[0]).unwrap();
-        let id_node: &SyntaxTreeNode = (*ast).graph.node_weight(children[1]).unwrap();
-
-        match value_node {
-            SyntaxTreeNode::Terminal(token) => {
-                match &token.token_type {
-                    TokenType::Identifier(id_name) => {
-                        let value_id_entry: &SymbolTableEntry = symbol_table.get_symbol_with_context(&token.text, token.position).unwrap(); 
-                        
-                        // Load the address of the value variable then load the data
-                        self.code_arr.push(format!("la  t2, {}_{}", id_name, value_id_entry.scope));
-
-                        match value_id_entry.symbol_type {
-                            Type::Int | Type::Boolean => {
-                                // Load only a byte for integers and booleans
-                                self.code_arr.push(format!("lbu t0, 0(t2)"));
-                            },
-                            Type::String => {
-                                // Strings are an entire word
-                                self.code_arr.push(format!("lwu t0, 0(t2)"));
-                            }
-                        }
-                    },
-                    TokenType::Digit(val) => {
-                        // Digits just load a constant to the accumulator
-                        self.code_arr.push(format!("li  t0, {}", val)); 
-                    },
-                    TokenType::Char(string) => {
-                        // Start by storing the string
-                        let string_index: usize = self.store_string(&string);
+    // Gets a register with the value, which is loaded into the given register if it is not already in one
+    fn load_value(&mut self, value: &Value, register: &'static str) -> &'static str {
+        match value {
+            Value::Var(var) => {
+                if let Some(var_register) = self.get_register(&Name::from_var(var)) {
+                    return var_register;
+                }
 
-                        // Store the starting address of the string in memory
-                        self.code_arr.push(format!("la  t0, string_{}", string_index));
-                    },
-                    TokenType::Keyword(keyword) => {
-                        match &keyword {
-                            Keywords::True => {
-                                // True is 1
-                                self.code_arr.push(format!("li  t0, 1"));
-                            },
-                            Keywords::False => {
-                                // False is 0
-                                self.code_arr.push(format!("li  t0, 0")); 
-                            },
-                            _ => error!("Received {:?} when expecting true or false for keyword terminals in assignment", keyword)
-                        }
-                    },
-                    _ => error!("Received {:?} for terminal in assignment when expecting id, digit, char, or keyword", token)
+                // Spilled variables are loaded from their static address
+                self.code_arr.push(format!("la  {}, {}", register, var));
+                match var.var_type {
+                    // Wide integers are a double word
+                    Type::Int if self.is_wide_int() => self.code_arr.push(format!("ld  {}, 0({})", register, register)),
+                    // Load only a byte for integers and booleans
+                    Type::Int | Type::Boolean => self.code_arr.push(format!("lbu  {}, 0({})", register, register)),
+                    // Strings are an entire word
+                    Type::String => self.code_arr.push(format!("lwu  {}, 0({})", register, register))
                 }
             },
-            SyntaxTreeNode::NonTerminalAst(non_terminal) => {
-                match non_terminal {
-                    NonTerminalsAst::Add => {
-                        // Call add, so the result will be in both the accumulator and in memory
-                        self.code_gen_add(ast, children[0], symbol_table, true);
-                    },
-                    NonTerminalsAst::IsEq => {
-                        self.code_gen_compare(ast, children[0], symbol_table, true);
-                        self.code_arr.push(format!("mv  t0, a0"));
-                    },
-                    NonTerminalsAst::NotEq => {
-                        self.code_gen_compare(ast, children[0], symbol_table, false);
-                        self.code_arr.push(format!("mv  t0, a0"));
-                    },
-                    _ => error!("Received {:?} for nonterminal on right side of assignment for code gen", non_terminal)
+            Value::Temp(temp) => {
+                if let Some(temp_register) = self.get_register(&Name::Temp(*temp)) {
+                    return temp_register;
                 }
-            },
-            _ => error!("Received {:?} when expecting terminal or AST nonterminal for assignment in code gen", value_node)
-        }
-
-        match id_node {
-            SyntaxTreeNode::Terminal(token) => {
-                // Get the static offset for the variable being assigned to
-                let id_entry: &SymbolTableEntry = symbol_table.get_symbol_with_context(&token.text, token.position).unwrap(); 
-                
-                // The data that we are storing is already in t0, so load the appropriate
-                // address and store the data
-
-                self.code_arr.push(format!("la  t1, {}_{}", token.text, id_entry.scope));
-                match &id_entry.symbol_type {
-                    Type::Int | Type::Boolean => {
-                        // Int and boolean take up only 1 byte
-                        self.code_arr.push(format!("sb  t0, 0(t1)")); 
-                    },
-                    Type::String => {
-                        // Strings take up a full word
-                        self.code_arr.push(format!("sw  t0, 0(t1)"));
-                    }
+                let offset: usize = self.get_temp_offset(*temp);
+                if self.is_wide_int() {
+                    self.code_arr.push(format!("ld  {}, {}(sp)", register, offset));
+                } else {
+                    self.code_arr.push(format!("lwu  {}, {}(sp)", register, offset));
                 }
             },
-            _ => error!("Received {:?} when expecting terminal for assignmentchild in code gen", id_node)
-        }
-    }
-
-    // Function for generating code for a print statement
-    fn code_gen_print(&mut self, ast: &SyntaxTree, cur_index: NodeIndex, symbol_table: &mut SymbolTable) {
-        nexus_log::log(
-            nexus_log::LogTypes::Debug,
-            nexus_log::LogSources::CodeGenerator,
-            format!("Starting code generation for print statement in scope {}", symbol_table.cur_scope.unwrap())
-        );
-
-        // Get the child on the print statement to evaluate
-        let children: Vec<NodeIndex> = (*ast).graph.neighbors(cur_index).collect();
-        let child: &SyntaxTreeNode = (*ast).graph.node_weight(children[0]).unwrap();
-
-        match child {
-            SyntaxTreeNode::Terminal(token) => {
-                match &token.token_type {
-                    TokenType::Identifier(id_name) => {
-                        let print_id: &SymbolTableEntry = symbol_table.get_symbol_with_context(&id_name, token.position).unwrap();
-                        match &print_id.symbol_type {
-                            Type::Int => {
-                                self.code_arr.push(format!("la  t0, {}_{}", id_name, print_id.scope));
-                                self.code_arr.push(format!("lbu  a0, 0(t0)"));
-                                self.code_arr.push(format!("call print_int"));
-                            },
-                            Type::String => {
-                                // Store the string address in Y
-                                self.code_arr.push(format!("lwu  a0, {}_{}", id_name, print_id.scope));
-                                self.code_arr.push(format!("call print_string"));
-                            },
-                            Type::Boolean => {
-                                // Compare the value of the variable with false
-                                self.code_arr.push(format!("lbu  a0, {}_{}", id_name, print_id.scope));
-                                self.code_arr.push(format!("call print_boolean"));
-                            }
-                        }
-                    },
-                    TokenType::Digit(digit) => {
-                        // Place the number in a0 and call the function that
-                        // handles numbers
-                        self.code_arr.push(format!("li  a0, {}", digit));
-                        self.code_arr.push(format!("call print_int"));
-                    },
-                    TokenType::Char(string) => {
-                        // Store the string in memory and get its index
-                        let string_index: usize = self.store_string(&string);
-
-                        // Get the address of the string we want to print
-                        self.code_arr.push(format!("la  a0, string_{}", string_index));
-                        self.code_arr.push(format!("call print_string"));
-                    },
-                    TokenType::Keyword(keyword) => {
-                        match keyword {
-                            Keywords::True => {
-                                // Load the address for true
-                                self.code_arr.push(format!("la  a0, string_1"));
-                            },
-                            Keywords::False => {
-                                // Load the address for false
-                                self.code_arr.push(format!("la  a0, string_0"));
-                            },
-                            _ => error!("Received {:?} when expecting true or false for print keyword", keyword)
-                        }
-                        // Make the system call
-                        self.code_arr.push(format!("call print_string"));
-                    },
-                    _ => error!("Received {:?} when expecting id, digit, string, or keyword for print terminal", token)
-                }
+            Value::Int(int_value) => {
+                self.code_arr.push(format!("li  {}, {}", register, int_value));
             },
-            SyntaxTreeNode::NonTerminalAst(non_terminal) => {
-                match non_terminal {
-                    NonTerminalsAst::Add => {
-                        // Generate the result of the addition expression
-                        self.code_gen_add(ast, children[0], symbol_table, true);
-                        
-                        // Move the contents in t0 to a0
-                        self.code_arr.push(format!("mv  a0, t0"));
-                        self.code_arr.push(format!("call print_int")); 
-                    },
-                    NonTerminalsAst::IsEq => {
-                        // The result of the equality comparison is in a0
-                        self.code_gen_compare(ast, children[0], symbol_table, true);
-                        self.code_arr.push(format!("call print_boolean"));
-                    },
-                    NonTerminalsAst::NotEq => {
-                        self.code_gen_compare(ast, children[0], symbol_table, false);
-                        self.code_arr.push(format!("call print_boolean"));
-                    },
-                    _ => error!("Received {:?} when expecting addition or boolean expression for nonterminal print", non_terminal)
-                }
+            Value::Bool(bool_value) => {
+                // True is 1 and false is 0
+                self.code_arr.push(format!("li  {}, {}", register, *bool_value as u8));
             },
-            _ => error!("Received {:?} when expecting terminal or AST nonterminal for print in code gen", child)
+            Value::String(string) => {
+                // Store the string and load its address
+                let string_index: usize = self.store_string(string);
+                self.code_arr.push(format!("la  {}, string_{}", register, string_index));
+            }
         }
-
-        // Add a new line for cleanliness
-        self.code_arr.push(format!("call print_new_line"));
+        return register;
     }
 
-    // Function to generate code for an addition statement
-    // Result is left in t0
-    fn code_gen_add(&mut self, ast: &SyntaxTree, cur_index: NodeIndex, symbol_table: &mut SymbolTable, is_first: bool) {
-        nexus_log::log(
-            nexus_log::LogTypes::Debug,
-            nexus_log::LogSources::CodeGenerator,
-            format!("Starting code generation for addition expression in scope {}", symbol_table.cur_scope.unwrap())
-        );
-
-        // Get the child for addition
-        let children: Vec<NodeIndex> = (*ast).graph.neighbors(cur_index).collect();
-        let right_child: &SyntaxTreeNode = (*ast).graph.node_weight(children[0]).unwrap();
-        let left_child: &SyntaxTreeNode = (*ast).graph.node_weight(children[1]).unwrap();
-
-        match right_child {
-            SyntaxTreeNode::Terminal(token) => {
-                match &token.token_type {
-                    TokenType::Digit(num) => {
-                        // Store right side digit in t0
-                        self.code_arr.push(format!("li  t1, {}", num));
-                    },
-                    TokenType::Identifier(id_name) => {
-                        // Get the address needed from memory for the identifier
-                        let value_id_entry: &SymbolTableEntry = symbol_table.get_symbol_with_context(&token.text, token.position).unwrap(); 
-                        
-                        // Load the variable's value into t0
-                        self.code_arr.push(format!("la  t2, {}_{}", id_name, value_id_entry.scope));
-                        self.code_arr.push(format!("lbu  t1, 0(t2)"));
-                    },
-                    _ => error!("Received {:?} when expecting digit or id for right side of addition", token)
-                }
-            },
-            // Nonterminals are always add, so just call it
-            SyntaxTreeNode::NonTerminalAst(_) => self.code_gen_add(ast, children[0], symbol_table, false),
-            _ => error!("Received {:?} when expecting terminal or AST nonterminal for right addition value", right_child)
+    // Loads the value into exactly the given register
+    fn move_value(&mut self, value: &Value, register: &'static str) {
+        let value_register: &str = self.load_value(value, register);
+        if value_register != register {
+            self.code_arr.push(format!("mv  {}, {}", register, value_register));
         }
+    }
 
-        match left_child {
-            SyntaxTreeNode::Terminal(token) => {
-                match &token.token_type {
-                    TokenType::Digit(num) => {
-                        // Load the number to t0
-                        self.code_arr.push(format!("li  t0, {}", num));
-                        if is_first {
-                            // If we are in the outermost add, then store the
-                            // result in t0
-                            self.code_arr.push(format!("add  t0, t0, t1"));
-                        } else {
-                            // Otherwise store it in t1 because there are still
-                            // more elements to add that will be loaded into t0
-                            self.code_arr.push(format!("add  t1, t0, t1"));
-                        }
-                    },
-                    _ => error!("Received {:?} when expecting a digit for left side of addition for code gen", token)
-                }
-            },
-            _ => error!("Received {:?} when expecting a terminal for the left side of addition for code gen", left_child)
+    // Gets the operand for a comparison, where 0 and false do not need to be loaded
+    fn load_compare_operand(&mut self, value: &Value, register: &'static str) -> &'static str {
+        match value {
+            Value::Int(0) | Value::Bool(false) => return "zero",
+            _ => return self.load_value(value, register)
         }
     }
 
-    // Function to generate code for comparisons
-    // Result is left in the Z flag and get_z_flag_vale function can be used
-    // afterwards to place z flag value into the accumulator
-    fn code_gen_compare(&mut self, ast: &SyntaxTree, cur_index: NodeIndex, symbol_table: &mut SymbolTable, is_eq: bool) {
-        nexus_log::log(
-            nexus_log::LogTypes::Debug,
-            nexus_log::LogSources::CodeGenerator,
-            format!("Starting code generation for comparison expression (is_eq = {}) in scope {}", is_eq, symbol_table.cur_scope.unwrap())
-        );
+    // Gets the register the result should be put in, which is t0 if the name was spilled
+    fn get_dest_register(&self, name: &Name) -> &'static str {
+        return self.get_register(name).unwrap_or("t0");
+    }
 
-        // Get the child for comparison
-        let children: Vec<NodeIndex> = (*ast).graph.neighbors(cur_index).collect();
-        let right_child: &SyntaxTreeNode = (*ast).graph.node_weight(children[0]).unwrap();
-        let left_child: &SyntaxTreeNode = (*ast).graph.node_weight(children[1]).unwrap();
-
-        match left_child {
-            SyntaxTreeNode::Terminal(token) => {
-                match &token.token_type {
-                    TokenType::Identifier(id_name) => {
-                        // Get the address needed from memory for the identifier
-                        let value_id_entry: &SymbolTableEntry = symbol_table.get_symbol_with_context(&token.text, token.position).unwrap(); 
-                        
-                        // Get the address of the variable
-                        self.code_arr.push(format!("la  t0, {}_{}", id_name, value_id_entry.scope));
-
-                        // Now store the value of the variable in a0
-                        match value_id_entry.symbol_type {
-                            Type::Int | Type::Boolean => {
-                                self.code_arr.push(format!("lbu  a0, 0(t0)"));
-                            },
-                            Type::String => {
-                                self.code_arr.push(format!("lwu  a0, 0(t0)"));
-                            }
-                        }
-                    },
-                    TokenType::Digit(num) => {
-                        // Store the digit in a0
-                        self.code_arr.push(format!("li  a0, {}", num));
-                    },
-                    TokenType::Char(string) => {
-                        // Store the address of the string in a0
-                        let string_index: usize = self.store_string(string);
-                        self.code_arr.push(format!("la  a0, string_{}", string_index));
-                    },
-                    TokenType::Keyword(keyword) => {
-                        match &keyword {
-                            Keywords::True => self.code_arr.push(format!("li  a0, 1")),
-                            Keywords::False => self.code_arr.push(format!("li  a0, 0")),
-                            _ => error!("Received {:?} when expecting true or false for keywords in boolean expression", keyword)
-                        }
-                    },
-                    _ => error!("Received {:?} when expecting an Id, digit, char, or keyword for left side of boolean expression", token)
+    // Stores the result in memory if the name was spilled
+    fn store_dest(&mut self, name: &Name, register: &str, var_type: &Type) {
+        if self.get_register(name).is_some() {
+            return;
+        }
+        match name {
+            Name::Var(..) => {
+                self.code_arr.push(format!("la  t1, {}", name));
+                match var_type {
+                    Type::Int if self.is_wide_int() => self.code_arr.push(format!("sd  {}, 0(t1)", register)),
+                    // Int and boolean take up only 1 byte
+                    Type::Int | Type::Boolean => self.code_arr.push(format!("sb  {}, 0(t1)", register)),
+                    // Strings take up a full word
+                    Type::String => self.code_arr.push(format!("sw  {}, 0(t1)", register))
                 }
             },
-            SyntaxTreeNode::NonTerminalAst(non_terminal) => {
-                match &non_terminal {
-                    NonTerminalsAst::Add => {
-                        // Run the addition and move the result from t0 to a0
-                        self.code_gen_add(ast, children[1], symbol_table, true);
-                        self.code_arr.push(format!("mv  a0, t0"));
-                    },
-                    NonTerminalsAst::IsEq => {
-                        self.code_gen_compare(ast, children[1], symbol_table, true);
-                    },
-                    NonTerminalsAst::NotEq => {
-                        self.code_gen_compare(ast, children[1], symbol_table, false);
-                    },
-                    _ => error!("Received {:?} for left side of nonterminal boolean expression, when expected Add, IsEq, or NotEq", non_terminal)
+            Name::Temp(temp) => {
+                let offset: usize = self.get_temp_offset(*temp);
+                if self.is_wide_int() {
+                    self.code_arr.push(format!("sd  {}, {}(sp)", register, offset));
+                } else {
+                    self.code_arr.push(format!("sw  {}, {}(sp)", register, offset));
                 }
-            },
-            _ => error!("Received {:?} when expected terminal or AST nonterminal for left side of comparison in code gen", left_child)
+            }
         }
+    }
 
-        match right_child {
-            SyntaxTreeNode::Terminal(token) => {
-                match &token.token_type {
-                    TokenType::Identifier(id_name) => {
-                        // Get the address needed from memory for the identifier
-                        let value_id_entry: &SymbolTableEntry = symbol_table.get_symbol_with_context(&token.text, token.position).unwrap(); 
-
-                        // Get the address of the variable
-                        self.code_arr.push(format!("la  t0, {}_{}", id_name, value_id_entry.scope));
-
-                        // Now store the value of the variable in a1
-                        match value_id_entry.symbol_type {
-                            Type::Int | Type::Boolean => {
-                                self.code_arr.push(format!("lbu  a1, 0(t0)"));
-                            },
-                            Type::String => {
-                                self.code_arr.push(format!("lwu  a1, 0(t0)"));
-                            }
-                        }
-                    },
-                    TokenType::Digit(num) => {
-                        // Store the digit in a1
-                        self.code_arr.push(format!("li  a1, {}", num));
-                    },
-                    TokenType::Char(string) => {
-                        // Store the address of the string in a1
-                        let string_index: usize = self.store_string(string);
-                        self.code_arr.push(format!("la  a1, string_{}", string_index));
-                    },
-                    TokenType::Keyword(keyword) => {
-                        match &keyword {
-                            Keywords::True => self.code_arr.push(format!("li  a1, 1")),
-                            Keywords::False => self.code_arr.push(format!("li  a1, 0")),
-                            _ => error!("Received {:?} when expecting true or false for keywords in boolean expression", keyword)
+    // Function for creating the code for a variable declaration
+    fn code_gen_declare(&mut self, var: &Variable) {
+        let name: Name = Name::from_var(var);
+        let var_register: Option<&'static str> = self.get_register(&name);
+        match var.var_type {
+            // Only integers and booleans are initialized
+            Type::Int | Type::Boolean => {
+                match var_register {
+                    Some(var_register) => self.code_arr.push(format!("li  {}, 0", var_register)),
+                    None => {
+                        if var.var_type == Type::Int && self.is_wide_int() {
+                            self.static_arr.push((8, format!("{}: .dword 0", var)));
+                        } else {
+                            self.static_arr.push((1, format!("{}: .byte 0", var)));
                         }
-                    },
-                    _ => error!("Received {:?} when expecting an Id, digit, char, or keyword for left side of boolean expression", token)
+                        self.store_dest(&name, "zero", &var.var_type);
+                    }
                 }
             },
-            SyntaxTreeNode::NonTerminalAst(non_terminal) => {
-                // We have a nonterminal, so store the left side on the stack so there is no
-                // conflict with the right side evaluation
-                self.code_arr.push(format!("addi  sp, sp, -1"));
-                self.code_arr.push(format!("sb  a0, 0(sp)"));
-
-                match &non_terminal {
-                    NonTerminalsAst::Add => {
-                        // Do the add and move the result from t0 to a1
-                        self.code_gen_add(ast, children[0], symbol_table, true);
-                        self.code_arr.push(format!("mv  a1, t0"));
-                    },
-                    NonTerminalsAst::IsEq => {
-                        // Move the result over to a1
-                        self.code_gen_compare(ast, children[0], symbol_table, true);
-                        self.code_arr.push(format!("mv  a1, a0"));
-                    },
-                    NonTerminalsAst::NotEq => {
-                        self.code_gen_compare(ast, children[0], symbol_table, false);
-                        self.code_arr.push(format!("mv  a1, a0"));
-                    },
-                    _ => error!("Received {:?} for right side of nonterminal boolean expression, when expected Add, IsEq, or NotEq", non_terminal)
+            // Strings do not get initialized
+            Type::String => {
+                // Since it is a string on the heap, we have to store the address
+                // which is a full word
+                if var_register.is_none() {
+                    self.static_arr.push((4, format!("{}: .word 0", var)));
                 }
+            }
+        }
+    }
+
+    // Function for creating the code for an assignment
+    fn code_gen_assignment(&mut self, dest: &Variable, value: &Value) {
+        let name: Name = Name::from_var(dest);
+        let dest_register: &'static str = self.get_dest_register(&name);
+        self.move_value(value, dest_register);
+        self.store_dest(&name, dest_register, &dest.var_type);
+    }
 
-                // Get the left side back to a0
-                self.code_arr.push(format!("lbu  a0, 0(sp)"));
-                self.code_arr.push(format!("addi  sp, sp, 1"));
+    // Function to generate code for an addition
+    fn code_gen_add(&mut self, name: Name, left: &Value, right: &Value) {
+        let dest_register: &'static str = self.get_dest_register(&name);
+        match (left, right) {
+            // Constants that fit in 12 bits can be added directly
+            (Value::Int(constant), other) | (other, Value::Int(constant)) if !matches!(other, Value::Int(_)) && *constant < 0x800 => {
+                let other_register: &str = self.load_value(other, "t0");
+                self.code_arr.push(format!("addi  {}, {}, {}", dest_register, other_register, constant));
             },
-            _ => error!("Received {:?} when expected terminal or AST nonterminal for left side of comparison in code gen", left_child)
+            _ => {
+                let left_register: &str = self.load_value(left, "t0");
+                let right_register: &str = self.load_value(right, "t1");
+                self.code_arr.push(format!("add  {}, {}, {}", dest_register, left_register, right_register));
+            }
+        }
+        // Byte integers wrap around just like on the 6502, and wide integers wrap around with the register
+        if !self.is_wide_int() {
+            self.code_arr.push(format!("andi  {}, {}, 0x{:X}", dest_register, dest_register, ir::max_int(self.int_bits)));
         }
+        self.store_dest(&name, dest_register, &Type::Int);
+    }
+
+    // Function to generate code for comparisons, where the result is 1 for true and 0 for false
+    fn code_gen_compare(&mut self, name: Name, left: &Value, right: &Value, is_eq: bool) {
+        let dest_register: &'static str = self.get_dest_register(&name);
+        let left_register: &str = self.load_compare_operand(left, "t0");
+        let right_register: &str = self.load_compare_operand(right, "t1");
+
+        // The difference is 0 when they are equal, which is not needed when comparing to 0
+        let difference_register: &str = if right_register == "zero" {
+            left_register
+        } else if left_register == "zero" {
+            right_register
+        } else {
+            self.code_arr.push(format!("xor  {}, {}, {}", dest_register, left_register, right_register));
+            dest_register
+        };
 
-        // Perform the appropriate comparison
         if is_eq {
-            self.code_arr.push(format!("call compare_eq"));
+            self.code_arr.push(format!("seqz  {}, {}", dest_register, difference_register));
         } else {
-            self.code_arr.push(format!("call compare_neq"));
+            self.code_arr.push(format!("snez  {}, {}", dest_register, difference_register));
         }
+        self.store_dest(&name, dest_register, &Type::Boolean);
     }
 
-    fn code_gen_if(&mut self, ast: &SyntaxTree, cur_index: NodeIndex, symbol_table: &mut SymbolTable) {
-        nexus_log::log(
-            nexus_log::LogTypes::Debug,
-            nexus_log::LogSources::CodeGenerator,
-            format!("Starting code generation for if statement in scope {}", symbol_table.cur_scope.unwrap())
-        );
-
-        // Get the child for comparison
-        let children: Vec<NodeIndex> = (*ast).graph.neighbors(cur_index).collect();
-        let left_child: &SyntaxTreeNode = (*ast).graph.node_weight(children[1]).unwrap();
-
-        // Get the index of the current if statement
-        let if_index: usize = self.if_count.to_owned();
+    // Function to generate code for a comparison that is only used by the branch after it,
+    // so the branch can do the comparison itself
+    fn code_gen_compare_and_branch(&mut self, left: &Value, right: &Value, is_eq: bool, label: Label) {
+        let left_register: &str = self.load_compare_operand(left, "t0");
+        let right_register: &str = self.load_compare_operand(right, "t1");
+
+        // Branch when the comparison is false
+        let branch: &str = if is_eq { "bne" } else { "beq" };
+        if right_register == "zero" || left_register == "zero" {
+            let other_register: &str = if right_register == "zero" { left_register } else { right_register };
+            self.code_arr.push(format!("{}z  {}, {}", branch, other_register, label));
+        } else {
+            self.code_arr.push(format!("{}  {}, {}, {}", branch, left_register, right_register, label));
+        }
+    }
 
-        match left_child {
-            SyntaxTreeNode::NonTerminalAst(non_terminal) => {
-                match &non_terminal {
-                    // Evaluate the boolean expression for the if statement
-                    NonTerminalsAst::IsEq => self.code_gen_compare(ast, children[1], symbol_table, true),
-                    NonTerminalsAst::NotEq => self.code_gen_compare(ast, children[1], symbol_table, false),
-                    _ => error!("Received {:?} when expecting IsEq or NotEq for nonterminal if expression", non_terminal)
-                }
-                // Add the branch code
-                self.code_arr.push(format!("beq  a0, zero, if_end_{}", if_index)); 
-                self.if_count += 1;
-            },
-            SyntaxTreeNode::Terminal(token) => {
-                match &token.token_type {
-                    TokenType::Keyword(Keywords::True) => { /* Small optimization because no comparison is needed */ }
-                    TokenType::Keyword(Keywords::False) => {
-                        // No code should be generated here because the if-statement is just dead
-                        // code and will never be reached, so no point in trying to store the code
-                        // with the limited space that we already have (256 bytes)
-                        return;
-                    }
-                    _ => error!("Received {:?} when expecting true or false for if expression terminals", token)
-                }
-            },
-            _ => error!("Received {:?} when expecting AST nonterminal or a terminal", left_child)
+    // Function for generating code for a print statement
+    fn code_gen_print(&mut self, value: &Value, value_type: &Type) {
+        // Each print function takes the value in a0
+        self.move_value(value, "a0");
+        match value_type {
+            Type::Int => self.code_arr.push(format!("call print_int")),
+            Type::String => self.code_arr.push(format!("call print_string")),
+            Type::Boolean => self.code_arr.push(format!("call print_boolean"))
         }
 
-        // Generate the code for the body
-        self.code_gen_block(ast, children[0], symbol_table);
+        // Add a new line for cleanliness
+        self.code_arr.push(format!("call print_new_line"));
+    }
 
-        // Only add the label if it is needed
-        if if_index != self.if_count {
-            // Add the label for the end of the if statement
-            self.code_arr.push(format!("if_end_{}:", if_index));
+    // Function for generating code to jump to the label if the condition is false
+    fn code_gen_branch_if_false(&mut self, condition: &Value, label: Label) {
+        match condition {
+            Value::Bool(true) => {},
+            Value::Bool(false) => self.code_arr.push(format!("j  {}", label)),
+            _ => {
+                let condition_register: &str = self.load_value(condition, "t0");
+                self.code_arr.push(format!("beqz  {}, {}", condition_register, label));
+            }
         }
     }
 
-    fn code_gen_while(&mut self, ast: &SyntaxTree, cur_index: NodeIndex, symbol_table: &mut SymbolTable) {
-         nexus_log::log(
+    // Generates the code for the IR instruction at the index and returns the number of instructions it used
+    fn code_gen_instruction(&mut self, instructions: &[Instruction], index: usize) -> usize {
+        let instruction: &Instruction = &instructions[index];
+        nexus_log::log(
             nexus_log::LogTypes::Debug,
             nexus_log::LogSources::CodeGenerator,
-            format!("Starting code generation for while statement in scope {}", symbol_table.cur_scope.unwrap())
+            format!("Starting code generation for {}", instruction)
         );
 
-        // Get the child for comparison
-        let children: Vec<NodeIndex> = (*ast).graph.neighbors(cur_index).collect();
-        let left_child: &SyntaxTreeNode = (*ast).graph.node_weight(children[1]).unwrap();
-
-        // Get the index of the current start
-        let while_index: usize = self.while_count.to_owned();
-        self.while_count += 1;
-
-        self.code_arr.push(format!("while_start_{}:", while_index));
-
-        match left_child {
-            SyntaxTreeNode::NonTerminalAst(non_terminal) => {
-                match &non_terminal {
-                    // Evaluate the boolean expression for the while statement
-                    // The Z flag is set by these function calls
-                    NonTerminalsAst::IsEq => self.code_gen_compare(ast, children[1], symbol_table, true),
-                    NonTerminalsAst::NotEq => self.code_gen_compare(ast, children[1], symbol_table, false),
-                    _ => error!("Received {:?} when expecting IsEq or NotEq for nonterminal if expression", non_terminal)
+        match instruction {
+            // Statements are only used by the 6502 listing
+            Instruction::StatementStart { .. } => {},
+            Instruction::Declare(var) => self.code_gen_declare(var),
+            Instruction::Assign { dest, value } => self.code_gen_assignment(dest, value),
+            Instruction::Add { dest, left, right } => {
+                // Temps are only used once, so a result that is assigned right away can go straight to the variable
+                if let Some(Instruction::Assign { dest: var, value: Value::Temp(temp) }) = instructions.get(index + 1) {
+                    if temp == dest {
+                        self.code_gen_add(Name::from_var(var), left, right);
+                        return 2;
+                    }
                 }
-                // Add the branch code
-                self.code_arr.push(format!("beq  a0, zero, while_end_{}", while_index));
+                self.code_gen_add(Name::Temp(*dest), left, right);
             },
-            SyntaxTreeNode::Terminal(token) => {
-                match &token.token_type {
-                    TokenType::Keyword(Keywords::True) => { /* Small optimization because no comparison is needed */ }
-                    TokenType::Keyword(Keywords::False) => {
-                        // No code should be generated here because the while-statement is just dead
-                        // code and will never be reached, so no point in trying to store the code
-                        // with the limited space that we already have (256 bytes)
-                        return;
-                    }
-                    _ => error!("Received {:?} when expecting true or false for while expression terminals", token)
+            Instruction::Compare { dest, left, right, is_eq } => {
+                match instructions.get(index + 1) {
+                    // A comparison that goes right into a branch does not need its result
+                    Some(Instruction::BranchIfFalse { condition: Value::Temp(temp), target }) if temp == dest => {
+                        self.code_gen_compare_and_branch(left, right, *is_eq, *target);
+                        return 2;
+                    },
+                    Some(Instruction::Assign { dest: var, value: Value::Temp(temp) }) if temp == dest => {
+                        self.code_gen_compare(Name::from_var(var), left, right, *is_eq);
+                        return 2;
+                    },
+                    _ => self.code_gen_compare(Name::Temp(*dest), left, right, *is_eq)
                 }
             },
-            _ => error!("Received {:?} when expecting AST nonterminal or a terminal", left_child)
+            Instruction::Print { value, value_type } => self.code_gen_print(value, value_type),
+            Instruction::Label(label) => self.code_arr.push(format!("{}:", label)),
+            Instruction::Jump(label) => self.code_arr.push(format!("j  {}", label)),
+            Instruction::BranchIfFalse { condition, target } => self.code_gen_branch_if_false(condition, *target)
         }
-
-        // Generate the code for the body
-        self.code_gen_block(ast, children[0], symbol_table);
-
-        // Jump back to the condition
-        self.code_arr.push(format!("j  while_start_{}", while_index));
-
-        // Label for the end of the while block
-        self.code_arr.push(format!("while_end_{}:", while_index));
+        return 1;
     }
 
-    fn display_code(&mut self, program_number: &u32) {
+    fn display_code_tab(code_str: &str, program_number: &u32) {
         let window: Window = web_sys::window().expect("Should be able to get the window");
         let document: Document = window.document().expect("Should be able to get the document");
 
@@ -994,17 +619,15 @@ impl CodeGeneratorRiscV {
         // The div is a container for the content of the ast info
         display_area_class_list.add_3("container", "text-center", "code-gen-pane").expect("Should be able to add the classes");
 
-        // Generate the final assembly output string
-        let code_str: String = self.create_output_string();
-
         // This is the element that the code is in
         let code_elem: Element = document.create_element("p").expect("Should be able to create the element");
         let code_elem_class_list: DomTokenList = code_elem.class_list();
         code_elem_class_list.add_2("overflow-auto", "code-text").expect("Should be able to add the classes");
-        code_elem.set_inner_html(&code_str);
+        // Replace all of the newlines with breaks for the html display
+        code_elem.set_inner_html(&code_str.replace("\n", "<br>"));
 
-        // Replace all of the breaks with newlines for the clipboard copy
-        let code_str_clipboard: String = code_str.as_str().replace("<br>", "\n");
+        // The clipboard gets the plain text version
+        let code_str_clipboard: String = String::from(code_str);
 
         display_area_div.append_child(&code_elem).expect("Should be able to add the child node");
 
@@ -1026,3 +649,116 @@ impl CodeGeneratorRiscV {
         content_area.append_child(&display_area_div).expect("Should be able to add the child node");
     }
 }
+
+impl Backend for CodeGeneratorRiscV {
+    fn name(&self) -> &'static str {
+        return "riscv";
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        return &["risc-v"];
+    }
+
+    fn display_name(&self) -> &'static str {
+        return "RISC-V";
+    }
+
+    fn file_extension(&self) -> &'static str {
+        return "s";
+    }
+
+    fn executable_extension(&self) -> &'static str {
+        return "elf";
+    }
+
+    // Integers can use the full 64 bit registers
+    fn max_int_bits(&self) -> u32 {
+        return 64;
+    }
+
+    // Shows the assembly, which is already readable, so the listing is not needed
+    fn display_code(&self, code: &Artifact, _listing: Option<&str>, program_number: &u32) {
+        CodeGeneratorRiscV::display_code_tab(&code.to_string(), program_number);
+    }
+
+    // Removes the code of every program from the webpage
+    fn clear_display(&self) {
+        // Get the preliminary objects
+        let window: Window = web_sys::window().expect("Should be able to get the window");
+        let document: Document = window.document().expect("Should be able to get the document");
+
+        // Clear the entire area
+        let tabs_area: Element = document.get_element_by_id("code-gen-tabs").expect("Should be able to find the element");
+        tabs_area.set_inner_html("");
+        let content_area: Element = document.get_element_by_id("code-gen-tab-content").expect("Should be able to find the element");
+        content_area.set_inner_html("");
+    }
+
+    // Assembles and links the code into a riscv64 ELF executable
+    fn create_executable(&self, code: &Artifact) -> Result<Vec<u8>, String> {
+        match code {
+            Artifact::Assembly(assembly) => return riscv_assembler::create_executable(assembly).map_err(|error| error.to_string()),
+            _ => return Err(format!("Expected RISC-V assembly"))
+        }
+    }
+
+    // Generates the assembly for the program
+    // There is no memory limit for RISC-V, so it always succeeds
+    fn generate_code(&mut self, ir: &IrProgram) -> PhaseResult<Artifact> {
+        self.code_arr.clear();
+        self.static_arr.clear();
+        self.heap_arr.clear();
+
+        // Initialize the basic data for printing functionality
+        self.heap_arr.push(format!("new_line: .ascii \"\\n\""));
+        self.heap_arr.push(format!("print_int_char: .byte 0"));
+        
+        self.string_history.clear();
+        self.int_bits = ir.int_bits;
+
+        // Store the actual strings "true" and "false"
+        self.store_string("false");
+        self.store_string("true");
+
+        // Only the variables and temps that do not fit in the registers are stored in memory
+        let allocation: Allocation = RegisterAllocator::new(&VAR_REGISTERS, &TEMP_REGISTERS).allocate(ir);
+        self.temp_offsets.clear();
+        for name in allocation.spilled.iter() {
+            if let Name::Temp(temp) = name {
+                self.temp_offsets.insert(*temp, self.temp_offsets.len() * self.get_temp_size());
+            }
+        }
+        self.allocation = Some(allocation);
+
+        // Make room on the stack for the spilled temps, which keeps the stack pointer 16 byte aligned
+        let frame_size: usize = (self.temp_offsets.len() * self.get_temp_size()).div_ceil(16) * 16;
+        if frame_size > 0 {
+            self.code_arr.push(format!("addi  sp, sp, -{}", frame_size));
+        }
+
+        // Generate the code for the program
+        let mut index: usize = 0;
+        while index < ir.instructions.len() {
+            index += self.code_gen_instruction(&ir.instructions, index);
+        }
+        
+        // Add the code to exit the program
+        self.code_arr.push(format!("li  a7, 93"));
+        self.code_arr.push(format!("li  a0, 0"));
+        self.code_arr.push(format!("ecall"));
+
+        // Add a function for printing an integer
+        self.add_print_int_code();
+        self.add_print_string_code();
+        self.add_print_boolean_code();
+        self.add_print_new_line_code();
+       
+        nexus_log::log(
+            nexus_log::LogTypes::Info,
+            nexus_log::LogSources::CodeGenerator,
+            format!("Code generation completed successfully")
+        );
+
+        return PhaseResult::new(Some(Artifact::Assembly(self.create_output_string())), Vec::new());
+    }
+}
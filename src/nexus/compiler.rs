@@ -1,21 +1,141 @@
-use crate::util::{nexus_log, target::Target};
-use crate::nexus::{lexer::Lexer, token::Token, parser::Parser, semantic_analyzer::SemanticAnalyzer, syntax_tree::SyntaxTree};
-use crate::nexus::code_generator_6502::CodeGenerator6502;
-use crate::nexus::code_generator_riscv::CodeGeneratorRiscV;
-use crate::editor::buttons;
+use crate::util::nexus_log;
+use crate::util::diagnostic_sink::{DiagnosticSink, CollectorSink, LogEntry};
+use crate::nexus::{lexer::Lexer, token::Token, parser::Parser, ast_generator::AstGenerator, semantic_analyzer::SemanticAnalyzer, syntax_tree::SyntaxTree, ast::Block};
+use crate::nexus::symbol_table::SymbolTable;
+use crate::nexus::ir::{self, IrProgram};
+use crate::nexus::ir_generator::IrGenerator;
+use crate::nexus::dead_block_pruner::DeadBlockPruner;
+use crate::nexus::constant_folder::ConstantFolder;
+use crate::nexus::dead_code_eliminator::DeadCodeEliminator;
+use crate::nexus::diagnostic::{Diagnostic, PhaseResult};
+use crate::nexus::backend::{self, Backend, Artifact};
+use crate::nexus::memory_layout::MemoryLayout;
+
+// The settings for a compilation
+#[derive (Debug, Clone)]
+pub struct CompileOptions {
+    // The name of the backend to generate code for, which has to be in the backend registry
+    pub target: String,
+    // Where everything goes in memory for the backends that generate an image
+    pub memory_layout: MemoryLayout,
+    // The language extension where integer literals can have more than 1 digit
+    // and integers are as wide as the target allows instead of a single byte
+    pub multi_digit_integers: bool
+}
+
+impl CompileOptions {
+    pub fn new(target: &str) -> Self {
+        return CompileOptions {
+            target: String::from(target),
+            memory_layout: MemoryLayout::default(),
+            multi_digit_integers: false
+        };
+    }
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        // The first backend is the default
+        return CompileOptions::new(backend::get_backend_names()[0]);
+    }
+}
+
+// Everything produced for a single program
+// Each phase's output is only present if that phase was reached and succeeded
+#[derive (Debug)]
+pub struct ProgramResult {
+    pub program_number: u32,
+    pub tokens: Option<Vec<Token>>,
+    pub cst: Option<SyntaxTree>,
+    pub ast: Option<Block>,
+    pub symbol_table: Option<SymbolTable>,
+    // The three-address code that the backend generated the code from
+    pub ir: Option<IrProgram>,
+    pub diagnostics: Vec<Diagnostic>,
+    pub code: Option<Artifact>,
+    // The annotated disassembly of the code for backends that generate an image
+    pub listing: Option<String>
+}
+
+impl ProgramResult {
+    fn new(program_number: u32) -> Self {
+        return ProgramResult {
+            program_number,
+            tokens: None,
+            cst: None,
+            ast: None,
+            symbol_table: None,
+            ir: None,
+            diagnostics: Vec::new(),
+            code: None,
+            listing: None
+        };
+    }
+
+    // A program succeeded if it made it all the way through code generation
+    pub fn is_ok(&self) -> bool {
+        return self.code.is_some();
+    }
+}
+
+// Everything produced by compiling a source file
+#[derive (Debug)]
+pub struct CompilationResult {
+    // The results for each program in the order they are in the source code
+    pub programs: Vec<ProgramResult>,
+    // All of the logs from the compilation, including debug logs
+    pub logs: Vec<LogEntry>
+}
+
+impl CompilationResult {
+    // Only successful if every program compiled
+    pub fn is_ok(&self) -> bool {
+        return self.programs.iter().all(|program| program.is_ok());
+    }
+}
 
 // Function to compile multiple programs
-pub fn compile(source_code: &str) {
-    let mut lexer: Lexer = Lexer::new(source_code);
+// Logs are collected into the result instead of going to the current sink
+// Nothing is compiled if the options are invalid, such as a target that is not in the backend registry
+// These are checked before the sink is swapped so the current sink is never left behind
+pub fn compile(source_code: &str, options: &CompileOptions) -> Result<CompilationResult, String> {
+    let mut backend: Box<dyn Backend> = match backend::get_backend(&options.target) {
+        Some(backend) => backend,
+        None => return Err(format!("No backend named {}; Expected one of {}", options.target, backend::get_backend_names().join(", ")))
+    };
+    if let Err(msg) = options.memory_layout.validate() {
+        return Err(format!("Invalid memory layout: {}", msg));
+    }
+    backend.set_memory_layout(&options.memory_layout);
+
+    // Collect everything for the duration of the compile
+    let collector: CollectorSink = CollectorSink::new();
+    let prev_sink: Box<dyn DiagnosticSink> = nexus_log::set_sink(Box::new(collector.clone()));
+
+    let programs: Vec<ProgramResult> = compile_programs(source_code, options, backend.as_mut());
+
+    // Put the original sink back
+    nexus_log::set_sink(prev_sink);
+
+    return Ok(CompilationResult {
+        programs,
+        logs: collector.entries()
+    });
+}
+
+fn compile_programs(source_code: &str, options: &CompileOptions, backend: &mut dyn Backend) -> Vec<ProgramResult> {
+    // Integers are always a byte without the extension, so every target prints the same thing
+    let int_bits: u32 = if options.multi_digit_integers { backend.max_int_bits() } else { ir::DEFAULT_INT_BITS };
+
+    let mut lexer: Lexer = Lexer::new(source_code, options.multi_digit_integers);
     let mut parser: Parser = Parser::new();
-    let mut semantic_analyzer: SemanticAnalyzer = SemanticAnalyzer::new();
-    let mut code_generator_6502: CodeGenerator6502 = CodeGenerator6502::new();
-    let mut code_generator_riscv: CodeGeneratorRiscV = CodeGeneratorRiscV::new();
-
-    // Clean up the output area
-    SyntaxTree::clear_display();
-    CodeGenerator6502::clear_display();
-    nexus_log::clear_logs();
+    let mut ast_generator: AstGenerator = AstGenerator::new();
+    let mut semantic_analyzer: SemanticAnalyzer = SemanticAnalyzer::new(int_bits);
+    let mut dead_block_pruner: DeadBlockPruner = DeadBlockPruner::new();
+    let mut ir_generator: IrGenerator = IrGenerator::new(int_bits);
+    let mut constant_folder: ConstantFolder = ConstantFolder::new();
+    let mut dead_code_eliminator: DeadCodeEliminator = DeadCodeEliminator::new();
+
     nexus_log::log(
         nexus_log::LogTypes::Info,
         nexus_log::LogSources::Nexus,
@@ -25,9 +145,13 @@ pub fn compile(source_code: &str) {
     // Keep track of the number of programs
     let mut program_number: u32 = 0;
 
+    // The results for each of the programs
+    let mut programs: Vec<ProgramResult> = Vec::new();
+
     // Go through each program
     while lexer.has_program_to_lex() {
         program_number += 1;
+        let mut program: ProgramResult = ProgramResult::new(program_number);
 
         nexus_log::insert_empty_line();
 
@@ -47,7 +171,8 @@ pub fn compile(source_code: &str) {
         );
 
         // Lex the program
-        let lex_res: Result<Vec<Token>, ()> = lexer.lex_program();
+        let mut lex_res: PhaseResult<Vec<Token>> = lexer.lex_program();
+        program.diagnostics.append(&mut lex_res.diagnostics);
 
         nexus_log::insert_empty_line();
 
@@ -61,13 +186,13 @@ pub fn compile(source_code: &str) {
             nexus_log::log(
                 nexus_log::LogTypes::Warning,
                 nexus_log::LogSources::Nexus,
-                String::from("CST display skipped due to lex failure")
+                String::from("CST skipped due to lex failure")
             );
-            
+
             nexus_log::log(
                 nexus_log::LogTypes::Warning,
                 nexus_log::LogSources::Nexus,
-                String::from("AST generation and display skipped due to lex failure")
+                String::from("AST generation skipped due to lex failure")
             );
 
             nexus_log::log(
@@ -79,9 +204,9 @@ pub fn compile(source_code: &str) {
             nexus_log::log(
                 nexus_log::LogTypes::Warning,
                 nexus_log::LogSources::Nexus,
-                String::from("Symbol table display skipped due to lex failure")
+                String::from("Symbol table skipped due to lex failure")
             );
-            
+
             nexus_log::log(
                 nexus_log::LogTypes::Warning,
                 nexus_log::LogSources::Nexus,
@@ -91,10 +216,11 @@ pub fn compile(source_code: &str) {
             nexus_log::log(
                 nexus_log::LogTypes::Warning,
                 nexus_log::LogSources::Nexus,
-                String::from("Executable image display skipped due to lex failure")
+                String::from("Executable image skipped due to lex failure")
             );
 
             // No need to move on if lex failed, so can go to next program
+            programs.push(program);
             continue;
         }
 
@@ -105,23 +231,24 @@ pub fn compile(source_code: &str) {
             format!("Parsing program {}", program_number)
         );
 
-        let token_stream: Vec<Token> = lex_res.unwrap();
-        let parse_res: Result<SyntaxTree, ()> = parser.parse_program(&token_stream);
+        let token_stream: Vec<Token> = lex_res.output.unwrap();
+        let mut parse_res: PhaseResult<SyntaxTree> = parser.parse_program(&token_stream);
+        program.diagnostics.append(&mut parse_res.diagnostics);
 
         if parse_res.is_err() {
             nexus_log::insert_empty_line();
 
-            // Do not show CST unless parse is successful
+            // There is no CST unless parse is successful
             nexus_log::log(
                 nexus_log::LogTypes::Warning,
                 nexus_log::LogSources::Nexus,
-                String::from("CST display skipped due to parse failure")
+                String::from("CST skipped due to parse failure")
             );
-            
+
             nexus_log::log(
                 nexus_log::LogTypes::Warning,
                 nexus_log::LogSources::Nexus,
-                String::from("AST generation and display skipped due to parse failure")
+                String::from("AST generation skipped due to parse failure")
             );
 
             nexus_log::log(
@@ -133,7 +260,7 @@ pub fn compile(source_code: &str) {
             nexus_log::log(
                 nexus_log::LogTypes::Warning,
                 nexus_log::LogSources::Nexus,
-                String::from("Symbol table display skipped due to parse failure")
+                String::from("Symbol table skipped due to parse failure")
             );
 
             nexus_log::log(
@@ -145,35 +272,37 @@ pub fn compile(source_code: &str) {
             nexus_log::log(
                 nexus_log::LogTypes::Warning,
                 nexus_log::LogSources::Nexus,
-                String::from("Executable image display skipped due to parse failure")
+                String::from("Executable image skipped due to parse failure")
             );
 
+            program.tokens = Some(token_stream);
+            programs.push(program);
             continue;
         }
 
         nexus_log::log(
             nexus_log::LogTypes::Info,
             nexus_log::LogSources::Nexus,
-            format!("CST display for program {} is below", program_number)
+            format!("Created the CST for program {}", program_number)
         );
-        let cst: SyntaxTree = parse_res.unwrap();
-        cst.display(&program_number);
+        program.cst = parse_res.output;
 
         nexus_log::insert_empty_line();
-        
+
         nexus_log::log(
             nexus_log::LogTypes::Info,
             nexus_log::LogSources::Nexus,
             format!("Generating AST for program {}", program_number)
         );
 
-        let ast: SyntaxTree = semantic_analyzer.generate_ast(&token_stream);
-        ast.display(&program_number);
+        // The AST is lowered from the CST, so the grammar only lives in the parser
+        let ast: Block = ast_generator.generate_ast(program.cst.as_ref().unwrap());
+        program.tokens = Some(token_stream);
 
         nexus_log::log(
             nexus_log::LogTypes::Info,
             nexus_log::LogSources::Nexus,
-            format!("AST display for program {} is below", program_number)
+            format!("Created the AST for program {}", program_number)
         );
 
         nexus_log::log(
@@ -181,17 +310,18 @@ pub fn compile(source_code: &str) {
             nexus_log::LogSources::SemanticAnalyzer,
             format!("Beginning semantic analysis on program {}", program_number)
         );
-        let semantic_analysis_res: bool = semantic_analyzer.analyze_program(&ast);
+        let mut semantic_analysis_res: PhaseResult<()> = semantic_analyzer.analyze_program(&ast);
+        program.diagnostics.append(&mut semantic_analysis_res.diagnostics);
 
-        if !semantic_analysis_res {
+        if semantic_analysis_res.is_err() {
             nexus_log::insert_empty_line();
 
             nexus_log::log(
                 nexus_log::LogTypes::Warning,
                 nexus_log::LogSources::Nexus,
-                String::from("Symbol table display skipped due to semantic analysis failure")
+                String::from("Symbol table skipped due to semantic analysis failure")
             );
-            
+
             nexus_log::log(
                 nexus_log::LogTypes::Warning,
                 nexus_log::LogSources::Nexus,
@@ -201,30 +331,71 @@ pub fn compile(source_code: &str) {
             nexus_log::log(
                 nexus_log::LogTypes::Warning,
                 nexus_log::LogSources::Nexus,
-                String::from("Executable image display skipped due to semantic analysis failure")
+                String::from("Executable image skipped due to semantic analysis failure")
             );
 
+            program.ast = Some(ast);
+            programs.push(program);
             continue;
         }
 
         nexus_log::log(
             nexus_log::LogTypes::Info,
             nexus_log::LogSources::Nexus,
-            format!("Symbol table for program {} is below", program_number)
+            format!("Created the symbol table for program {}", program_number)
         );
-        semantic_analyzer.symbol_table.display_symbol_table(&program_number);
+
+        // The symbol table belongs to the program now, and the analyzer gets a fresh one
+        let mut symbol_table: SymbolTable = std::mem::replace(&mut semantic_analyzer.symbol_table, SymbolTable::new());
 
         nexus_log::insert_empty_line();
 
+        nexus_log::log(
+            nexus_log::LogTypes::Info,
+            nexus_log::LogSources::CodeGenerator,
+            format!("Generating IR for program {}", program_number)
+        );
+
+        // The AST shown to the user keeps the dead blocks, and only the lowered one loses them
+        let pruned_ast: Block = dead_block_pruner.prune_dead_blocks(ast.clone());
+
+        // Every backend generates its code from the same IR
+        let mut ir: IrProgram = ir_generator.generate_ir(&pruned_ast, &mut symbol_table);
+        constant_folder.fold_constants(&mut ir);
+        dead_code_eliminator.eliminate_dead_code(&mut ir, &symbol_table);
+
         nexus_log::log(
             nexus_log::LogTypes::Info,
             nexus_log::LogSources::CodeGenerator,
             format!("Generating code for program {}", program_number)
         );
-       
-        match buttons::get_current_target() {
-            Target::Target6502 => code_generator_6502.generate_code(&ast, &mut semantic_analyzer.symbol_table, &program_number),
-            Target::TargetRiscV => code_generator_riscv.generate_code(&ast, &mut semantic_analyzer.symbol_table, &program_number)
+
+        let mut code_gen_res: PhaseResult<Artifact> = backend.generate_code(&ir);
+        program.diagnostics.append(&mut code_gen_res.diagnostics);
+
+        if code_gen_res.is_ok() {
+            nexus_log::log(
+                nexus_log::LogTypes::Info,
+                nexus_log::LogSources::Nexus,
+                format!("Created the executable image for program {}", program_number)
+            );
+            program.listing = backend.create_listing(source_code);
+        } else {
+            nexus_log::insert_empty_line();
+
+            nexus_log::log(
+                nexus_log::LogTypes::Warning,
+                nexus_log::LogSources::Nexus,
+                String::from("Executable image skipped due to code generation failure")
+            );
         }
+
+        program.ast = Some(ast);
+        program.symbol_table = Some(symbol_table);
+        program.ir = Some(ir);
+        program.code = code_gen_res.output;
+        programs.push(program);
     }
+
+    return programs;
 }
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use crate::nexus::symbol_table::Type;
+use crate::nexus::diagnostic::Span;
+use crate::nexus::ir::{self, IrProgram, Instruction, Variable, Temp, Value};
+use crate::util::nexus_log;
+
+// Folds the expressions in the IR that only use constants and replaces variables
+// with their values when they are known to be constant
+// Strings are never folded because they are compared by their address in memory
+#[derive (Debug)]
+pub struct ConstantFolder {
+    // The known values of the variables, which are only kept within a basic block
+    var_values: HashMap<(String, usize), Value>,
+
+    // The values of the temps that were folded away
+    temp_values: HashMap<Temp, Value>,
+
+    // Additions with a constant side, which are the index of the instruction, the constant, and the other side
+    partial_adds: HashMap<Temp, (usize, u64, Value)>,
+
+    // The number of bits in an integer for the program being folded
+    int_bits: u32,
+
+    // The statement currently being folded for the logs
+    cur_span: Option<Span>,
+    num_folds: usize
+}
+
+impl ConstantFolder {
+    pub fn new() -> Self {
+        return ConstantFolder {
+            var_values: HashMap::new(),
+            temp_values: HashMap::new(),
+            partial_adds: HashMap::new(),
+            int_bits: ir::DEFAULT_INT_BITS,
+            cur_span: None,
+            num_folds: 0
+        };
+    }
+
+    // Folds the constants in the IR and returns the number of folds that were made
+    pub fn fold_constants(&mut self, ir: &mut IrProgram) -> usize {
+        self.var_values.clear();
+        self.temp_values.clear();
+        self.partial_adds.clear();
+        self.int_bits = ir.int_bits;
+        self.cur_span = None;
+        self.num_folds = 0;
+
+        // Instructions that are folded away are left as None so the indices in partial_adds stay the same
+        let mut instructions: Vec<Option<Instruction>> = Vec::new();
+        for instruction in std::mem::take(&mut ir.instructions).into_iter() {
+            let folded: Option<Instruction> = self.fold_instruction(instruction, &mut instructions);
+            instructions.push(folded);
+        }
+        ir.instructions = instructions.into_iter().flatten().collect();
+        ir.renumber_temps();
+
+        nexus_log::log(
+            nexus_log::LogTypes::Info,
+            nexus_log::LogSources::CodeGenerator,
+            format!("Constant folding completed with {} folds", self.num_folds)
+        );
+
+        return self.num_folds;
+    }
+
+    fn log_fold(&mut self, msg: String) {
+        self.num_folds += 1;
+        let position: String = match self.cur_span {
+            Some(span) => format!(" at {}:{}", span.line, span.col),
+            None => String::new()
+        };
+        nexus_log::log(
+            nexus_log::LogTypes::Debug,
+            nexus_log::LogSources::CodeGenerator,
+            format!("{}{}", msg, position)
+        );
+    }
+
+    // Gets the constant a value is known to be, or the value itself if it is not known
+    fn resolve_value(&mut self, value: Value) -> Value {
+        match &value {
+            Value::Temp(temp) => {
+                if let Some(temp_value) = self.temp_values.get(temp) {
+                    return temp_value.to_owned();
+                }
+            },
+            Value::Var(var) => {
+                if let Some(var_value) = self.var_values.get(&(var.name.to_owned(), var.scope)).cloned() {
+                    self.log_fold(format!("Propagated {} = {}", var, var_value));
+                    return var_value;
+                }
+            },
+            _ => {}
+        }
+        return value;
+    }
+
+    fn set_var_value(&mut self, var: &Variable, value: Option<Value>) {
+        let key: (String, usize) = (var.name.to_owned(), var.scope);
+        match value {
+            Some(value) => { self.var_values.insert(key, value); },
+            None => { self.var_values.remove(&key); }
+        }
+    }
+
+    // Folds a single instruction and returns what should replace it
+    fn fold_instruction(&mut self, instruction: Instruction, instructions: &mut [Option<Instruction>]) -> Option<Instruction> {
+        match instruction {
+            Instruction::StatementStart { span, .. } => {
+                self.cur_span = Some(span);
+                return Some(instruction);
+            },
+            Instruction::Declare(ref var) => {
+                // Integers and booleans are initialized to 0 by both backends
+                let value: Option<Value> = match var.var_type {
+                    Type::Int => Some(Value::Int(0)),
+                    Type::Boolean => Some(Value::Bool(false)),
+                    Type::String => None
+                };
+                self.set_var_value(var, value);
+                return Some(instruction);
+            },
+            Instruction::Assign { dest, value } => {
+                let value: Value = self.resolve_value(value);
+                match value {
+                    Value::Int(_) | Value::Bool(_) => self.set_var_value(&dest, Some(value.to_owned())),
+                    _ => self.set_var_value(&dest, None)
+                }
+                return Some(Instruction::Assign { dest, value });
+            },
+            Instruction::Add { dest, left, right } => return self.fold_add(dest, left, right, instructions),
+            Instruction::Compare { dest, left, right, is_eq } => {
+                let left: Value = self.resolve_value(left);
+                let right: Value = self.resolve_value(right);
+                let result: Option<bool> = match (&left, &right) {
+                    (Value::Int(left_value), Value::Int(right_value)) => Some(left_value == right_value),
+                    (Value::Bool(left_value), Value::Bool(right_value)) => Some(left_value == right_value),
+                    _ => None
+                };
+                match result {
+                    Some(is_equal) => {
+                        let folded: Value = Value::Bool(is_equal == is_eq);
+                        self.log_fold(format!("Folded {} {} {} to {}", left, if is_eq { "==" } else { "!=" }, right, folded));
+                        self.temp_values.insert(dest, folded);
+                        return None;
+                    },
+                    None => return Some(Instruction::Compare { dest, left, right, is_eq })
+                }
+            },
+            Instruction::Print { value, value_type } => {
+                let value: Value = self.resolve_value(value);
+                return Some(Instruction::Print { value, value_type });
+            },
+            Instruction::Label(_) => {
+                // Labels can be reached from more than 1 place, so nothing is known about the variables anymore
+                self.var_values.clear();
+                return Some(instruction);
+            },
+            Instruction::Jump(_) => return Some(instruction),
+            Instruction::BranchIfFalse { condition, target } => {
+                match self.resolve_value(condition) {
+                    Value::Bool(true) => {
+                        self.log_fold(format!("Removed the branch to {} because the condition is always true", target));
+                        return None;
+                    },
+                    Value::Bool(false) => {
+                        self.log_fold(format!("Replaced the branch to {} with a jump because the condition is always false", target));
+                        return Some(Instruction::Jump(target));
+                    },
+                    condition => return Some(Instruction::BranchIfFalse { condition, target })
+                }
+            }
+        }
+    }
+
+    // Adds the integers, which wraps around at the width of the integers like the backends
+    fn wrapping_add(&self, left: u64, right: u64) -> u64 {
+        return left.wrapping_add(right) & ir::max_int(self.int_bits);
+    }
+
+    // Folds an addition, which wraps around like the backends
+    fn fold_add(&mut self, dest: Temp, left: Value, right: Value, instructions: &mut [Option<Instruction>]) -> Option<Instruction> {
+        let left: Value = self.resolve_value(left);
+        let right: Value = self.resolve_value(right);
+
+        // Put the constant on the left side like the grammar
+        let (constant, other): (u64, Value) = match (left, right) {
+            (Value::Int(left_value), Value::Int(right_value)) => {
+                let folded: u64 = self.wrapping_add(left_value, right_value);
+                self.log_fold(format!("Folded {} + {} to {}", left_value, right_value, folded));
+                self.temp_values.insert(dest, Value::Int(folded));
+                return None;
+            },
+            (Value::Int(value), other) | (other, Value::Int(value)) => (value, other),
+            (left, right) => return Some(Instruction::Add { dest, left, right })
+        };
+
+        // Combine the constants when the other side is an addition with a constant,
+        // which is safe because temps are only used once
+        let (constant, other): (u64, Value) = match &other {
+            Value::Temp(temp) if self.partial_adds.contains_key(temp) => {
+                let (index, other_constant, other_value): (usize, u64, Value) = self.partial_adds.remove(temp).unwrap();
+                let combined: u64 = self.wrapping_add(constant, other_constant);
+                self.log_fold(format!("Folded {} + {} to {}", constant, other_constant, combined));
+                instructions[index] = None;
+                (combined, other_value)
+            },
+            _ => (constant, other)
+        };
+
+        if constant == 0 {
+            self.log_fold(format!("Folded 0 + {} to {}", other, other));
+            self.temp_values.insert(dest, other);
+            return None;
+        }
+
+        self.partial_adds.insert(dest, (instructions.len(), constant, other.to_owned()));
+        return Some(Instruction::Add { dest, left: Value::Int(constant), right: other });
+    }
+}
@@ -0,0 +1,55 @@
+use crate::nexus::ast::{Block, Stmt, Expr};
+use crate::nexus::ast_folder::{self, Folder};
+use crate::util::nexus_log;
+
+// Removes the if statements and while loops whose condition is false from the AST before it is lowered,
+// so the IR generator never sees a body that can never run
+// The blocks after a removed one keep their own scopes, so they still line up with the symbol table
+#[derive (Debug)]
+pub struct DeadBlockPruner {
+    num_removed: usize
+}
+
+impl DeadBlockPruner {
+    pub fn new() -> Self {
+        return DeadBlockPruner {
+            num_removed: 0
+        };
+    }
+
+    // Returns the AST without the dead blocks
+    pub fn prune_dead_blocks(&mut self, ast: Block) -> Block {
+        self.num_removed = 0;
+        let pruned: Block = self.fold_block(ast, &mut ());
+
+        nexus_log::log(
+            nexus_log::LogTypes::Info,
+            nexus_log::LogSources::CodeGenerator,
+            format!("Dead block pruning removed {} statements", self.num_removed)
+        );
+
+        return pruned;
+    }
+}
+
+impl Folder for DeadBlockPruner {
+    fn fold_stmt(&mut self, stmt: Stmt, context: &mut ()) -> Option<Stmt> {
+        let (kind, condition): (&str, Option<&Expr>) = match &stmt {
+            Stmt::If(if_stmt) => ("if statement", Some(&if_stmt.condition)),
+            Stmt::While(while_stmt) => ("while loop", Some(&while_stmt.condition)),
+            _ => ("", None)
+        };
+
+        if let Some(Expr::BoolLiteral { value: false, span }) = condition {
+            nexus_log::log(
+                nexus_log::LogTypes::Debug,
+                nexus_log::LogSources::CodeGenerator,
+                format!("Removed the {} that can never run at {}:{}", kind, span.line, span.col)
+            );
+            self.num_removed += 1;
+            return None;
+        }
+
+        return ast_folder::walk_stmt(self, stmt, context);
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::nexus::symbol_table::SymbolTable;
+use crate::nexus::diagnostic::Span;
+use crate::nexus::ir::{IrProgram, Instruction, Variable, Temp, Label, Value};
+use crate::util::nexus_log;
+
+// Removes the code that can never run, the variables that are never read, and the stores
+// that are overwritten before they are read
+// This runs after constant folding because folded conditions and propagated values create most of the dead code
+#[derive (Debug)]
+pub struct DeadCodeEliminator {
+    num_removed: usize
+}
+
+impl DeadCodeEliminator {
+    pub fn new() -> Self {
+        return DeadCodeEliminator {
+            num_removed: 0
+        };
+    }
+
+    // Removes the dead code from the IR and returns the number of instructions that were removed
+    pub fn eliminate_dead_code(&mut self, ir: &mut IrProgram, symbol_table: &SymbolTable) -> usize {
+        self.num_removed = 0;
+
+        // The symbol table knows which variables are never used in the source code
+        let mut unused_vars: HashSet<(String, usize)> = HashSet::new();
+        for scope in 0..symbol_table.num_scopes() {
+            for (id, entry) in symbol_table.get_scope_entries(scope) {
+                if !entry.is_used {
+                    unused_vars.insert((id.to_owned(), scope));
+                }
+            }
+        }
+
+        // Removing some code can make more code dead, so keep going until nothing changes
+        let mut changed: bool = true;
+        while changed {
+            changed = false;
+            changed |= self.remove_unreachable_code(ir);
+            changed |= self.remove_useless_jumps(ir);
+            changed |= self.remove_unused_labels(ir);
+            changed |= self.remove_dead_stores(ir);
+            changed |= self.remove_unused_variables(ir, &unused_vars);
+            changed |= self.remove_unused_temps(ir);
+        }
+        self.remove_empty_statements(ir);
+        ir.renumber_temps();
+
+        nexus_log::log(
+            nexus_log::LogTypes::Info,
+            nexus_log::LogSources::CodeGenerator,
+            format!("Dead code elimination removed {} instructions", self.num_removed)
+        );
+
+        return self.num_removed;
+    }
+
+    fn log_removal(&self, msg: String, span: Option<Span>) {
+        let position: String = match span {
+            Some(span) => format!(" at {}:{}", span.line, span.col),
+            None => String::new()
+        };
+        nexus_log::log(
+            nexus_log::LogTypes::Debug,
+            nexus_log::LogSources::CodeGenerator,
+            format!("{}{}", msg, position)
+        );
+    }
+
+    // Removes the instructions that are not kept and returns if anything was removed
+    fn remove_instructions(&mut self, ir: &mut IrProgram, keep: Vec<bool>) -> bool {
+        let num_instructions: usize = ir.instructions.len();
+        let mut keep_iter = keep.into_iter();
+        ir.instructions.retain(|_| keep_iter.next().unwrap());
+        self.num_removed += num_instructions - ir.instructions.len();
+        return ir.instructions.len() < num_instructions;
+    }
+
+    // Gets the span of the statement each instruction belongs to for the logs
+    fn get_statement_spans(ir: &IrProgram) -> Vec<Option<Span>> {
+        let mut cur_span: Option<Span> = None;
+        return ir.instructions.iter().map(|instruction| {
+            if let Instruction::StatementStart { span, .. } = instruction {
+                cur_span = Some(*span);
+            }
+            return cur_span;
+        }).collect();
+    }
+
+    // Removes the code that cannot be reached from the start of the program
+    fn remove_unreachable_code(&mut self, ir: &mut IrProgram) -> bool {
+        let label_indices: HashMap<Label, usize> = ir.instructions.iter().enumerate()
+            .filter_map(|(i, instruction)| match instruction {
+                Instruction::Label(label) => Some((*label, i)),
+                _ => None
+            })
+            .collect();
+
+        // Follow every path through the code, where a jump is the only instruction that does not go to the next one
+        let mut reachable: Vec<bool> = vec![false; ir.instructions.len()];
+        let mut to_visit: Vec<usize> = vec![0];
+        while let Some(i) = to_visit.pop() {
+            if i >= ir.instructions.len() || reachable[i] {
+                continue;
+            }
+            reachable[i] = true;
+            if let Some(target) = ir.instructions[i].get_target() {
+                to_visit.push(label_indices[&target]);
+            }
+            if !matches!(ir.instructions[i], Instruction::Jump(_)) {
+                to_visit.push(i + 1);
+            }
+        }
+
+        for (i, instruction) in ir.instructions.iter().enumerate() {
+            if let Instruction::StatementStart { span, .. } = instruction {
+                if !reachable[i] {
+                    self.log_removal(String::from("Removed unreachable statement"), Some(*span));
+                }
+            }
+        }
+        return self.remove_instructions(ir, reachable);
+    }
+
+    // Removes the jumps and branches that go to the next instruction
+    fn remove_useless_jumps(&mut self, ir: &mut IrProgram) -> bool {
+        let mut keep: Vec<bool> = vec![true; ir.instructions.len()];
+        for (i, instruction) in ir.instructions.iter().enumerate() {
+            if let Some(target) = instruction.get_target() {
+                // Labels do not generate code, so the jump is useless if any of the labels after it is the target
+                let goes_to_next: bool = ir.instructions[i + 1..].iter()
+                    .take_while(|next| matches!(next, Instruction::Label(_)))
+                    .any(|next| *next == Instruction::Label(target));
+                if goes_to_next {
+                    self.log_removal(format!("Removed the jump to {} because it goes to the next instruction", target), None);
+                    keep[i] = false;
+                }
+            }
+        }
+        return self.remove_instructions(ir, keep);
+    }
+
+    // Removes the labels that nothing jumps to
+    fn remove_unused_labels(&mut self, ir: &mut IrProgram) -> bool {
+        let targets: HashSet<Label> = ir.instructions.iter().filter_map(|instruction| instruction.get_target()).collect();
+        let keep: Vec<bool> = ir.instructions.iter().map(|instruction| match instruction {
+            Instruction::Label(label) => targets.contains(label),
+            _ => true
+        }).collect();
+        return self.remove_instructions(ir, keep);
+    }
+
+    // Removes the assignments that are overwritten before they are read
+    // Only the code up to the next label or jump is checked because anything can happen after that
+    fn remove_dead_stores(&mut self, ir: &mut IrProgram) -> bool {
+        let spans: Vec<Option<Span>> = DeadCodeEliminator::get_statement_spans(ir);
+        let mut keep: Vec<bool> = vec![true; ir.instructions.len()];
+        for (i, instruction) in ir.instructions.iter().enumerate() {
+            if let Instruction::Assign { dest, .. } = instruction {
+                let mut is_dead: bool = true;
+                for next in ir.instructions[i + 1..].iter() {
+                    if next.get_values().iter().any(|value| matches!(value, Value::Var(var) if var == dest)) {
+                        is_dead = false;
+                        break;
+                    }
+                    match next {
+                        Instruction::Assign { dest: next_dest, .. } | Instruction::Declare(next_dest) if next_dest == dest => break,
+                        Instruction::Label(_) | Instruction::Jump(_) | Instruction::BranchIfFalse { .. } => {
+                            is_dead = false;
+                            break;
+                        },
+                        _ => {}
+                    }
+                }
+
+                // Stores at the end of the program are never read either
+                if is_dead {
+                    self.log_removal(format!("Removed the dead store to {}", dest), spans[i]);
+                    keep[i] = false;
+                }
+            }
+        }
+        return self.remove_instructions(ir, keep);
+    }
+
+    // Removes the declarations and assignments of the variables that are never read
+    fn remove_unused_variables(&mut self, ir: &mut IrProgram, unused_vars: &HashSet<(String, usize)>) -> bool {
+        let read_vars: HashSet<(String, usize)> = ir.instructions.iter()
+            .flat_map(|instruction| instruction.get_values())
+            .filter_map(|value| match value {
+                Value::Var(var) => Some((var.name.to_owned(), var.scope)),
+                _ => None
+            })
+            .collect();
+
+        let is_unread = |var: &Variable| -> bool { !read_vars.contains(&(var.name.to_owned(), var.scope)) };
+        let mut keep: Vec<bool> = vec![true; ir.instructions.len()];
+        for (i, instruction) in ir.instructions.iter().enumerate() {
+            match instruction {
+                Instruction::Declare(var) if is_unread(var) => {
+                    // Either it is never used in the source code or every use was replaced by its value
+                    if unused_vars.contains(&(var.name.to_owned(), var.scope)) {
+                        self.log_removal(format!("Removed {} because it is never used", var), None);
+                    } else {
+                        self.log_removal(format!("Removed {} because its value is never read", var), None);
+                    }
+                    keep[i] = false;
+                },
+                Instruction::Assign { dest, .. } if is_unread(dest) => keep[i] = false,
+                _ => {}
+            }
+        }
+        return self.remove_instructions(ir, keep);
+    }
+
+    // Removes the additions and comparisons whose result is never used
+    fn remove_unused_temps(&mut self, ir: &mut IrProgram) -> bool {
+        let mut changed: bool = false;
+        loop {
+            let read_temps: HashSet<Temp> = ir.instructions.iter()
+                .flat_map(|instruction| instruction.get_values())
+                .filter_map(|value| match value {
+                    Value::Temp(temp) => Some(*temp),
+                    _ => None
+                })
+                .collect();
+            let keep: Vec<bool> = ir.instructions.iter().map(|instruction| match instruction {
+                Instruction::Add { dest, .. } | Instruction::Compare { dest, .. } => read_temps.contains(dest),
+                _ => true
+            }).collect();
+            if !self.remove_instructions(ir, keep) {
+                return changed;
+            }
+            changed = true;
+        }
+    }
+
+    // Removes the starts of the simple statements that do not have any code left
+    fn remove_empty_statements(&mut self, ir: &mut IrProgram) {
+        let keep: Vec<bool> = ir.instructions.iter().enumerate().map(|(i, instruction)| match instruction {
+            Instruction::StatementStart { condition: None, .. } => {
+                !matches!(ir.instructions.get(i + 1), None | Some(Instruction::StatementStart { .. }) | Some(Instruction::Label(_)) | Some(Instruction::Jump(_)))
+            },
+            _ => true
+        }).collect();
+        self.remove_instructions(ir, keep);
+    }
+}
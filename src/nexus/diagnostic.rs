@@ -0,0 +1,351 @@
+use std::fmt;
+
+use crate::util::nexus_log::{self, LogSources};
+
+// How bad the diagnostic is
+#[derive (Debug, Clone, Copy, PartialEq, strum::Display)]
+#[strum (serialize_all = "UPPERCASE")]
+pub enum Severity {
+    Error,
+    Warning
+}
+
+// Stable codes for every error and warning the compiler can produce
+// The letter is the phase and the number never changes once it is given out
+#[derive (Debug, Clone, Copy, PartialEq)]
+pub enum DiagnosticCode {
+    // L001: Token that is not part of the grammar
+    UnrecognizedToken,
+    // L002: Character in a string that is not a-z or a space
+    InvalidStringCharacter,
+    // L003: String that is not closed before the end of the line or program
+    UnclosedString,
+    // L004: Comment that is not closed before the end of the program
+    UnclosedComment,
+    // L005: Program that does not end with $
+    MissingEop,
+    // L006: Integer literal that does not fit in 64 bits
+    IntegerTooLarge,
+
+    // P001: Token that does not fit the grammar where it was found
+    UnexpectedToken,
+    // P002: Program ended while more tokens were expected
+    UnexpectedEndOfProgram,
+    // P003: Block without any statements
+    EmptyBlock,
+    // P004: String without any characters
+    EmptyString,
+
+    // S001: Id used without being declared
+    UndeclaredIdentifier,
+    // S002: Id declared twice in the same scope
+    RedeclaredIdentifier,
+    // S003: Assigned value does not match the type of the id
+    AssignmentTypeMismatch,
+    // S004: Addition with a non-int value
+    AdditionTypeMismatch,
+    // S005: Comparison of values with different types
+    ComparisonTypeMismatch,
+    // S006: Id used before it was given a value
+    UninitializedUse,
+    // S007: Id given its first value after it has been used
+    InitializedAfterUse,
+    // S008: Id used, but never given a value
+    UsedButNeverInitialized,
+    // S009: Id never given a value or used
+    NeverInitializedOrUsed,
+    // S010: Id given a value, but never used
+    InitializedButNeverUsed,
+    // S011: Integer literal that is too big for the integers of the target
+    IntegerOutOfRange,
+
+    // C001: Code and static data ran into the heap
+    StackOverflow,
+    // C002: Heap ran into the code and static data
+    HeapOverflow
+}
+
+impl DiagnosticCode {
+    // Gets the stable code string, such as L001
+    pub fn code(&self) -> &'static str {
+        match self {
+            DiagnosticCode::UnrecognizedToken => return "L001",
+            DiagnosticCode::InvalidStringCharacter => return "L002",
+            DiagnosticCode::UnclosedString => return "L003",
+            DiagnosticCode::UnclosedComment => return "L004",
+            DiagnosticCode::MissingEop => return "L005",
+            DiagnosticCode::IntegerTooLarge => return "L006",
+            DiagnosticCode::UnexpectedToken => return "P001",
+            DiagnosticCode::UnexpectedEndOfProgram => return "P002",
+            DiagnosticCode::EmptyBlock => return "P003",
+            DiagnosticCode::EmptyString => return "P004",
+            DiagnosticCode::UndeclaredIdentifier => return "S001",
+            DiagnosticCode::RedeclaredIdentifier => return "S002",
+            DiagnosticCode::AssignmentTypeMismatch => return "S003",
+            DiagnosticCode::AdditionTypeMismatch => return "S004",
+            DiagnosticCode::ComparisonTypeMismatch => return "S005",
+            DiagnosticCode::UninitializedUse => return "S006",
+            DiagnosticCode::InitializedAfterUse => return "S007",
+            DiagnosticCode::UsedButNeverInitialized => return "S008",
+            DiagnosticCode::NeverInitializedOrUsed => return "S009",
+            DiagnosticCode::InitializedButNeverUsed => return "S010",
+            DiagnosticCode::IntegerOutOfRange => return "S011",
+            DiagnosticCode::StackOverflow => return "C001",
+            DiagnosticCode::HeapOverflow => return "C002"
+        }
+    }
+
+    // Each code is always either an error or a warning
+    pub fn severity(&self) -> Severity {
+        match self {
+            DiagnosticCode::UnclosedComment
+            | DiagnosticCode::MissingEop
+            | DiagnosticCode::EmptyBlock
+            | DiagnosticCode::EmptyString
+            | DiagnosticCode::UninitializedUse
+            | DiagnosticCode::InitializedAfterUse
+            | DiagnosticCode::UsedButNeverInitialized
+            | DiagnosticCode::NeverInitializedOrUsed
+            | DiagnosticCode::InitializedButNeverUsed
+            | DiagnosticCode::IntegerOutOfRange => return Severity::Warning,
+            _ => return Severity::Error
+        }
+    }
+
+    // The phase is determined by the letter of the code
+    pub fn phase(&self) -> LogSources {
+        match &self.code()[0..1] {
+            "L" => return LogSources::Lexer,
+            "P" => return LogSources::Parser,
+            "S" => return LogSources::SemanticAnalyzer,
+            _ => return LogSources::CodeGenerator
+        }
+    }
+}
+
+impl fmt::Display for DiagnosticCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+// A location in the source code
+#[derive (Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    // Byte offsets into the source code, where the end is exclusive
+    pub start: usize,
+    pub end: usize,
+    // The line and column of the start, which are both 1-based
+    pub line: usize,
+    pub col: usize
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, col: usize) -> Self {
+        return Span {
+            start,
+            end,
+            line,
+            col
+        };
+    }
+
+    // Creates a span that starts at this span and ends at the other one
+    pub fn to(&self, other: Span) -> Span {
+        return Span::new(self.start, other.end.max(self.end), self.line, self.col);
+    }
+
+    // Gets the start of the span as a (line, col) tuple
+    pub fn position(&self) -> (usize, usize) {
+        return (self.line, self.col);
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.line, self.col)
+    }
+}
+
+// Extra location that helps explain a diagnostic
+#[derive (Debug, Clone, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String
+}
+
+// An error or warning produced by one of the phases
+#[derive (Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub phase: LogSources,
+    pub code: DiagnosticCode,
+    // The message shown in the logs
+    pub message: String,
+    // Where the problem is, if it is tied to a location
+    pub span: Option<Span>,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>
+}
+
+impl Diagnostic {
+    // Creates a new diagnostic, where the severity and phase come from the code
+    pub fn new(code: DiagnosticCode, message: String, span: Option<Span>) -> Self {
+        return Diagnostic {
+            severity: code.severity(),
+            phase: code.phase(),
+            code,
+            message,
+            span,
+            labels: Vec::new(),
+            notes: Vec::new()
+        };
+    }
+
+    // Adds a secondary location to the diagnostic
+    pub fn with_label(mut self, span: Span, message: &str) -> Self {
+        self.labels.push(Label {
+            span,
+            message: String::from(message)
+        });
+        return self;
+    }
+
+    // Adds a note to the diagnostic
+    pub fn with_note(mut self, note: &str) -> Self {
+        self.notes.push(String::from(note));
+        return self;
+    }
+
+    pub fn is_error(&self) -> bool {
+        return self.severity == Severity::Error;
+    }
+
+    pub fn is_warning(&self) -> bool {
+        return self.severity == Severity::Warning;
+    }
+
+    // Gets the message for the logs, which do not show the source code, so the position goes in front of it
+    pub fn get_log_message(&self) -> String {
+        let kind: &str = match self.severity {
+            Severity::Error => "Error",
+            Severity::Warning => "Warning"
+        };
+        match self.span {
+            Some(span) => return format!("{} at {:?}; {}", kind, span.position(), self.message),
+            None => return self.message.to_owned()
+        }
+    }
+
+    // Renders the diagnostic with the lines of source code that it points at
+    // The main span is underlined with ^ and each label is underlined with -
+    pub fn render(&self, source_code: &str) -> String {
+        let mut out: String = format!("{}[{}]: {}\n", self.severity.to_string().to_lowercase(), self.code, self.message);
+
+        // Everything that gets underlined, starting with the main span
+        let mut marks: Vec<(Span, char, &str)> = Vec::new();
+        if let Some(span) = self.span {
+            marks.push((span, '^', ""));
+        }
+        for label in self.labels.iter() {
+            marks.push((label.span, '-', &label.message));
+        }
+
+        // The gutter has to fit the largest line number
+        let gutter_width: usize = marks.iter().map(|(span, _, _)| span.line.to_string().len()).max().unwrap_or(1);
+        let gutter: String = " ".repeat(gutter_width);
+
+        if let Some(span) = self.span {
+            out.push_str(&format!("{}--> {}:{}\n", gutter, span.line, span.col));
+        }
+
+        if !marks.is_empty() {
+            out.push_str(&format!("{} |\n", gutter));
+        }
+
+        // The labels can be before the main span, so the lines are shown in the order they are in the source code
+        marks.sort_by_key(|(span, _, _)| span.line);
+
+        let mut prev_line: Option<usize> = None;
+        for (span, marker, message) in marks.iter() {
+            let (line_text, underline_start, underline_len): (&str, usize, usize) = Diagnostic::get_source_line(source_code, span);
+
+            // Marks on the same line share the line of source code
+            if prev_line != Some(span.line) {
+                out.push_str(&format!("{:>width$} | {}\n", span.line, line_text, width = gutter_width));
+            }
+            prev_line = Some(span.line);
+
+            // Tabs are kept so the underline lines up with the source code
+            let padding: String = line_text.chars().take(underline_start).map(|c| if c == '\t' { '\t' } else { ' ' }).collect();
+            let underline: String = marker.to_string().repeat(underline_len);
+            if message.is_empty() {
+                out.push_str(&format!("{} | {}{}\n", gutter, padding, underline));
+            } else {
+                out.push_str(&format!("{} | {}{} {}\n", gutter, padding, underline, message));
+            }
+        }
+
+        for note in self.notes.iter() {
+            out.push_str(&format!("{} = note: {}\n", gutter, note));
+        }
+
+        return out;
+    }
+
+    // Gets the line of source code that a span starts on, along with the character
+    // column and length of the underline for the span within that line
+    fn get_source_line<'a>(source_code: &'a str, span: &Span) -> (&'a str, usize, usize) {
+        let start: usize = span.start.min(source_code.len());
+
+        let line_start: usize = source_code[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line_end: usize = source_code[start..].find('\n').map(|i| i + start).unwrap_or(source_code.len());
+        let line_text: &str = source_code[line_start..line_end].trim_end_matches('\r');
+
+        // Spans that go over multiple lines are only underlined until the end of the first one
+        let end: usize = span.end.min(line_start + line_text.len()).max(start);
+        let underline_start: usize = source_code[line_start..start].chars().count();
+        let underline_len: usize = source_code[start..end].chars().count().max(1);
+
+        return (line_text, underline_start, underline_len);
+    }
+}
+
+// The result of running a phase
+// The output is only present if the phase succeeded, but the diagnostics are always there
+#[derive (Debug)]
+pub struct PhaseResult<T> {
+    pub output: Option<T>,
+    pub diagnostics: Vec<Diagnostic>
+}
+
+impl<T> PhaseResult<T> {
+    pub fn new(output: Option<T>, diagnostics: Vec<Diagnostic>) -> Self {
+        return PhaseResult {
+            output,
+            diagnostics
+        };
+    }
+
+    pub fn is_ok(&self) -> bool {
+        return self.output.is_some();
+    }
+
+    pub fn is_err(&self) -> bool {
+        return self.output.is_none();
+    }
+
+    pub fn num_errors(&self) -> usize {
+        return self.diagnostics.iter().filter(|diagnostic| diagnostic.is_error()).count();
+    }
+
+    pub fn num_warnings(&self) -> usize {
+        return self.diagnostics.iter().filter(|diagnostic| diagnostic.is_warning()).count();
+    }
+}
+
+// Sends the diagnostic to the logs and keeps track of it for the phase's result
+pub fn report(diagnostics: &mut Vec<Diagnostic>, diagnostic: Diagnostic) {
+    nexus_log::report(&diagnostic);
+    diagnostics.push(diagnostic);
+}
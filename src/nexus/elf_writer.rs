@@ -0,0 +1,167 @@
+use crate::nexus::riscv_assembler::AssembledProgram;
+
+// Where the executable is loaded, which is the same as the GNU linker default
+const BASE_ADDRESS: u64 = 0x10000;
+
+// Segments have to start on a new page
+const PAGE_SIZE: u64 = 0x1000;
+
+const ELF_HEADER_SIZE: u64 = 64;
+const PROGRAM_HEADER_SIZE: u64 = 56;
+const SECTION_HEADER_SIZE: u64 = 64;
+
+// The text and data segments
+const NUM_PROGRAM_HEADERS: u64 = 2;
+
+// EM_RISCV
+const MACHINE_RISCV: u16 = 243;
+
+// The names of the null, .text, .data, and .shstrtab sections
+const SECTION_NAMES: &[u8] = b"\0.text\0.data\0.shstrtab\0";
+
+fn align_up(value: u64, alignment: u64) -> u64 {
+    return value.div_ceil(alignment) * alignment;
+}
+
+// The text goes right after the headers in the file
+fn get_text_offset() -> u64 {
+    return ELF_HEADER_SIZE + PROGRAM_HEADER_SIZE * NUM_PROGRAM_HEADERS;
+}
+
+fn get_data_offset(text_len: usize) -> u64 {
+    return align_up(get_text_offset() + text_len as u64, 8);
+}
+
+// Gets the addresses of the text and data for a text section of the given size
+// The data is on the page after the text, but keeps the same offset in its page as in the file
+pub fn get_layout(text_len: usize) -> (u64, u64) {
+    let text_address: u64 = BASE_ADDRESS + get_text_offset();
+    let data_offset: u64 = get_data_offset(text_len);
+    let data_address: u64 = BASE_ADDRESS + align_up(data_offset, PAGE_SIZE) + data_offset % PAGE_SIZE;
+    return (text_address, data_address);
+}
+
+fn push_u16(bytes: &mut Vec<u8>, value: u16) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+fn push_u64(bytes: &mut Vec<u8>, value: u64) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+// Adds a PT_LOAD program header
+fn push_program_header(bytes: &mut Vec<u8>, flags: u32, offset: u64, address: u64, size: u64) {
+    push_u32(bytes, 1);
+    push_u32(bytes, flags);
+    push_u64(bytes, offset);
+    push_u64(bytes, address);
+    push_u64(bytes, address);
+    push_u64(bytes, size);
+    push_u64(bytes, size);
+    push_u64(bytes, PAGE_SIZE);
+}
+
+// The fields of a section header that are not always 0
+struct SectionHeader {
+    // Offset of the name in SECTION_NAMES
+    name: u32,
+    section_type: u32,
+    flags: u64,
+    address: u64,
+    offset: u64,
+    size: u64,
+    alignment: u64
+}
+
+fn push_section_header(bytes: &mut Vec<u8>, header: SectionHeader) {
+    push_u32(bytes, header.name);
+    push_u32(bytes, header.section_type);
+    push_u64(bytes, header.flags);
+    push_u64(bytes, header.address);
+    push_u64(bytes, header.offset);
+    push_u64(bytes, header.size);
+    // No link, info, or entry size
+    push_u32(bytes, 0);
+    push_u32(bytes, 0);
+    push_u64(bytes, header.alignment);
+    push_u64(bytes, 0);
+}
+
+// Creates a statically linked riscv64 ELF executable for the assembled program
+// The text is read and execute and the data is read and write, so variables can be changed
+pub fn create_riscv_executable(program: &AssembledProgram) -> Vec<u8> {
+    let text_offset: u64 = get_text_offset();
+    let data_offset: u64 = get_data_offset(program.text.len());
+    let names_offset: u64 = data_offset + program.data.len() as u64;
+    let section_headers_offset: u64 = align_up(names_offset + SECTION_NAMES.len() as u64, 8);
+
+    let mut bytes: Vec<u8> = Vec::new();
+
+    // ELF header for a 64-bit little endian executable
+    bytes.extend_from_slice(&[0x7F, b'E', b'L', b'F', 2, 1, 1, 0]);
+    bytes.extend_from_slice(&[0; 8]);
+    push_u16(&mut bytes, 2);
+    push_u16(&mut bytes, MACHINE_RISCV);
+    push_u32(&mut bytes, 1);
+    push_u64(&mut bytes, program.entry);
+    push_u64(&mut bytes, ELF_HEADER_SIZE);
+    push_u64(&mut bytes, section_headers_offset);
+    // No flags because there are no compressed or floating point instructions
+    push_u32(&mut bytes, 0);
+    push_u16(&mut bytes, ELF_HEADER_SIZE as u16);
+    push_u16(&mut bytes, PROGRAM_HEADER_SIZE as u16);
+    push_u16(&mut bytes, NUM_PROGRAM_HEADERS as u16);
+    push_u16(&mut bytes, SECTION_HEADER_SIZE as u16);
+    push_u16(&mut bytes, 4);
+    push_u16(&mut bytes, 3);
+
+    // The text segment also has the headers, so it starts at the beginning of the file
+    push_program_header(&mut bytes, 0x5, 0, BASE_ADDRESS, text_offset + program.text.len() as u64);
+    push_program_header(&mut bytes, 0x6, data_offset, program.data_address, program.data.len() as u64);
+
+    bytes.extend_from_slice(&program.text);
+    bytes.resize(data_offset as usize, 0x00);
+    bytes.extend_from_slice(&program.data);
+    bytes.extend_from_slice(SECTION_NAMES);
+    bytes.resize(section_headers_offset as usize, 0x00);
+
+    // Section headers so tools like objdump can find the code
+    // Index 0 is always the null section
+    push_section_header(&mut bytes, SectionHeader { name: 0, section_type: 0, flags: 0, address: 0, offset: 0, size: 0, alignment: 0 });
+    // .text is program data that is allocated and executable
+    push_section_header(&mut bytes, SectionHeader {
+        name: 1,
+        section_type: 1,
+        flags: 0x6,
+        address: program.text_address,
+        offset: text_offset,
+        size: program.text.len() as u64,
+        alignment: 4
+    });
+    // .data is program data that is allocated and writable
+    push_section_header(&mut bytes, SectionHeader {
+        name: 7,
+        section_type: 1,
+        flags: 0x3,
+        address: program.data_address,
+        offset: data_offset,
+        size: program.data.len() as u64,
+        alignment: 1
+    });
+    // .shstrtab is the string table with the section names
+    push_section_header(&mut bytes, SectionHeader {
+        name: 13,
+        section_type: 3,
+        flags: 0,
+        address: 0,
+        offset: names_offset,
+        size: SECTION_NAMES.len() as u64,
+        alignment: 1
+    });
+
+    return bytes;
+}
@@ -0,0 +1,234 @@
+use std::fmt;
+
+// Enough for every sample program, while still stopping infinite loops quickly
+pub const DEFAULT_INSTRUCTION_LIMIT: usize = 100_000;
+
+// The reason the emulator stopped running the program
+#[derive (Debug, Clone, PartialEq)]
+pub enum StopReason {
+    // Reached a BRK, which is how every program ends
+    Break,
+    // Ran the maximum number of instructions, such as from a while true loop
+    InstructionLimit,
+    // The byte at the address is not an instruction in the subset
+    InvalidOpcode { opcode: u8, address: u16 },
+    // A system call with an X register value other than 1 or 2
+    InvalidSystemCall { x_register: u8, address: u16 },
+    // An absolute address past the end of memory
+    InvalidAddress { target: u16, address: u16 }
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopReason::Break => write!(f, "Reached BRK"),
+            StopReason::InstructionLimit => write!(f, "Reached the instruction limit"),
+            StopReason::InvalidOpcode { opcode, address } => write!(f, "Invalid opcode 0x{:02X} at 0x{:02X}", opcode, address),
+            StopReason::InvalidSystemCall { x_register, address } => write!(f, "Invalid system call with X = 0x{:02X} at 0x{:02X}", x_register, address),
+            StopReason::InvalidAddress { target, address } => write!(f, "Invalid memory address 0x{:04X} at 0x{:02X}", target, address)
+        }
+    }
+}
+
+// Everything produced by running a program
+#[derive (Debug, Clone)]
+pub struct EmulatorResult {
+    // Everything printed by the system calls
+    pub output: String,
+    // All of memory when the program stopped
+    pub memory: Vec<u8>,
+    pub stop_reason: StopReason,
+    pub num_instructions: usize
+}
+
+impl EmulatorResult {
+    // A program only finished if it reached a BRK
+    pub fn is_ok(&self) -> bool {
+        return self.stop_reason == StopReason::Break;
+    }
+}
+
+// Emulator for the subset of the 6502 from class that the code generator uses, plus JMP for images bigger than 256 bytes
+// Only CPX sets the Z flag and ADC does not use a carry, just like the emulator from class
+#[derive (Debug)]
+pub struct Emulator6502 {
+    // All of memory, where the image starts at 0x0000
+    memory: Vec<u8>,
+
+    accumulator: u8,
+    x_register: u8,
+    y_register: u8,
+    z_flag: bool,
+
+    // The address of the next byte to read
+    program_counter: u16,
+
+    // Everything printed so far
+    output: String
+}
+
+impl Emulator6502 {
+    // Loads the image into memory, where any missing bytes are 0x00
+    // Memory is at least the 256 bytes from class and at most the 64 KiB that 16 bit addresses can reach
+    pub fn new(image: &[u8]) -> Self {
+        let mut memory: Vec<u8> = image.iter().take(0x10000).copied().collect();
+        memory.resize(memory.len().max(0x100), 0x00);
+
+        return Emulator6502 {
+            memory,
+            accumulator: 0x00,
+            x_register: 0x00,
+            y_register: 0x00,
+            z_flag: false,
+            program_counter: 0x00,
+            output: String::new()
+        };
+    }
+
+    // Starts the program somewhere other than 0x0000, such as the load address of the code
+    pub fn with_start_address(mut self, start_address: u16) -> Self {
+        self.program_counter = start_address;
+        return self;
+    }
+
+    // Runs the program until it stops or runs max_instructions instructions
+    pub fn run(&mut self, max_instructions: usize) -> EmulatorResult {
+        let mut num_instructions: usize = 0;
+        let mut stop_reason: StopReason = StopReason::InstructionLimit;
+
+        while num_instructions < max_instructions {
+            num_instructions += 1;
+            let step_res: Option<StopReason> = self.step();
+            if step_res.is_some() {
+                stop_reason = step_res.unwrap();
+                break;
+            }
+        }
+
+        return EmulatorResult {
+            output: self.output.to_owned(),
+            memory: self.memory.to_owned(),
+            stop_reason,
+            num_instructions
+        };
+    }
+
+    // Moves the program counter by the offset, which wraps around memory
+    fn move_program_counter(&mut self, offset: isize) {
+        let memory_size: isize = self.memory.len() as isize;
+        self.program_counter = (self.program_counter as isize + offset).rem_euclid(memory_size) as u16;
+    }
+
+    // Reads the byte at the program counter and moves to the next one
+    fn fetch(&mut self) -> u8 {
+        let byte: u8 = self.memory[self.program_counter as usize];
+        self.move_program_counter(1);
+        return byte;
+    }
+
+    // Reads the little endian address of an absolute instruction
+    fn fetch_address(&mut self, instruction_addr: u16) -> Result<usize, StopReason> {
+        let low_order: u8 = self.fetch();
+        let high_order: u8 = self.fetch();
+        let target: u16 = u16::from_le_bytes([low_order, high_order]);
+        if target as usize >= self.memory.len() {
+            return Err(StopReason::InvalidAddress { target, address: instruction_addr });
+        }
+        return Ok(target as usize);
+    }
+
+    // Runs a single instruction and returns the reason to stop, if any
+    fn step(&mut self) -> Option<StopReason> {
+        let instruction_addr: u16 = self.program_counter;
+        let opcode: u8 = self.fetch();
+
+        match opcode {
+            // LDA with a constant
+            0xA9 => self.accumulator = self.fetch(),
+            // LDA from memory
+            0xAD => match self.fetch_address(instruction_addr) {
+                Ok(addr) => self.accumulator = self.memory[addr],
+                Err(reason) => return Some(reason)
+            },
+            // STA
+            0x8D => match self.fetch_address(instruction_addr) {
+                Ok(addr) => self.memory[addr] = self.accumulator,
+                Err(reason) => return Some(reason)
+            },
+            // TXA
+            0x8A => self.accumulator = self.x_register,
+            // TYA
+            0x98 => self.accumulator = self.y_register,
+            // ADC, which wraps around at 8 bits
+            0x6D => match self.fetch_address(instruction_addr) {
+                Ok(addr) => self.accumulator = self.accumulator.wrapping_add(self.memory[addr]),
+                Err(reason) => return Some(reason)
+            },
+            // LDX with a constant
+            0xA2 => self.x_register = self.fetch(),
+            // LDX from memory
+            0xAE => match self.fetch_address(instruction_addr) {
+                Ok(addr) => self.x_register = self.memory[addr],
+                Err(reason) => return Some(reason)
+            },
+            // TAX
+            0xAA => self.x_register = self.accumulator,
+            // LDY with a constant
+            0xA0 => self.y_register = self.fetch(),
+            // LDY from memory
+            0xAC => match self.fetch_address(instruction_addr) {
+                Ok(addr) => self.y_register = self.memory[addr],
+                Err(reason) => return Some(reason)
+            },
+            // TAY
+            0xA8 => self.y_register = self.accumulator,
+            // NOP
+            0xEA => {},
+            // BRK
+            0x00 => return Some(StopReason::Break),
+            // CPX sets Z if the X register equals the byte in memory
+            0xEC => match self.fetch_address(instruction_addr) {
+                Ok(addr) => self.z_flag = self.x_register == self.memory[addr],
+                Err(reason) => return Some(reason)
+            },
+            // BNE, where the offset is signed and wraps around memory
+            0xD0 => {
+                let offset: u8 = self.fetch();
+                if !self.z_flag {
+                    self.move_program_counter(offset as i8 as isize);
+                }
+            },
+            // JMP
+            0x4C => match self.fetch_address(instruction_addr) {
+                Ok(addr) => self.program_counter = addr as u16,
+                Err(reason) => return Some(reason)
+            },
+            // INC
+            0xEE => match self.fetch_address(instruction_addr) {
+                Ok(addr) => self.memory[addr] = self.memory[addr].wrapping_add(1),
+                Err(reason) => return Some(reason)
+            },
+            // SYS
+            0xFF => return self.system_call(instruction_addr),
+            _ => return Some(StopReason::InvalidOpcode { opcode, address: instruction_addr })
+        }
+
+        return None;
+    }
+
+    // X = 1 prints the integer in Y and X = 2 prints the 0x00 terminated string at the address in Y
+    fn system_call(&mut self, instruction_addr: u16) -> Option<StopReason> {
+        match self.x_register {
+            0x01 => self.output.push_str(&format!("{}", self.y_register)),
+            0x02 => {
+                let string_bytes: Vec<u8> = self.memory[self.y_register as usize..].iter()
+                    .take_while(|byte| **byte != 0x00)
+                    .copied()
+                    .collect();
+                self.output.push_str(&String::from_utf8_lossy(&string_bytes));
+            },
+            _ => return Some(StopReason::InvalidSystemCall { x_register: self.x_register, address: instruction_addr })
+        }
+        return None;
+    }
+}
@@ -0,0 +1,492 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::nexus::riscv_assembler::{self, AssembleError, SourceLine, Statement};
+
+// Enough for every sample program, while still stopping infinite loops quickly
+pub const DEFAULT_INSTRUCTION_LIMIT: usize = 1_000_000;
+
+// Where the program is loaded, which is the same as the GNU linker default
+const BASE_ADDRESS: u64 = 0x10000;
+
+// The number of bytes after the program for the stack
+const STACK_SIZE: u64 = 0x1000;
+
+// The reason the emulator stopped running the program
+#[derive (Debug, Clone, PartialEq)]
+pub enum StopReason {
+    // The exit system call with its exit code
+    Exit(u64),
+    // Ran the maximum number of instructions, such as from a while true loop
+    InstructionLimit,
+    // Jumped to an address that does not have an instruction
+    NotAnInstruction { address: u64 },
+    // A system call other than write (64) and exit (93)
+    InvalidSystemCall { code: u64, address: u64 },
+    // A load or store outside of the program and its stack
+    InvalidAddress { target: u64, address: u64 }
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopReason::Exit(code) => write!(f, "Exited with code {}", code),
+            StopReason::InstructionLimit => write!(f, "Reached the instruction limit"),
+            StopReason::NotAnInstruction { address } => write!(f, "No instruction at 0x{:X}", address),
+            StopReason::InvalidSystemCall { code, address } => write!(f, "Invalid system call {} at 0x{:X}", code, address),
+            StopReason::InvalidAddress { target, address } => write!(f, "Invalid memory address 0x{:X} at 0x{:X}", target, address)
+        }
+    }
+}
+
+// Everything produced by running a program
+#[derive (Debug, Clone)]
+pub struct EmulatorResult {
+    // Everything written to stdout
+    pub output: String,
+    pub stop_reason: StopReason,
+    pub num_instructions: usize
+}
+
+impl EmulatorResult {
+    // A program only finished if it exited with code 0
+    pub fn is_ok(&self) -> bool {
+        return self.stop_reason == StopReason::Exit(0);
+    }
+}
+
+// The size and sign extension of a load or store
+#[derive (Debug, Clone, Copy, PartialEq)]
+enum Width {
+    Byte,
+    ByteUnsigned,
+    Half,
+    HalfUnsigned,
+    Word,
+    WordUnsigned,
+    Double
+}
+
+impl Width {
+    fn num_bytes(&self) -> u64 {
+        match self {
+            Width::Byte | Width::ByteUnsigned => return 1,
+            Width::Half | Width::HalfUnsigned => return 2,
+            Width::Word | Width::WordUnsigned => return 4,
+            Width::Double => return 8
+        }
+    }
+}
+
+// The address of a load or store
+#[derive (Debug, Clone, Copy, PartialEq)]
+enum MemoryOperand {
+    // offset(register)
+    Offset(i64, usize),
+    // The address of a label
+    Absolute(u64)
+}
+
+#[derive (Debug, Clone, Copy, PartialEq)]
+enum BranchCondition {
+    Eq,
+    Ne,
+    Lt,
+    Ge,
+    Ltu,
+    Geu
+}
+
+#[derive (Debug, Clone, Copy, PartialEq)]
+enum Operation {
+    Add,
+    Sub,
+    Xor,
+    SetLessThanUnsigned,
+    Mul,
+    Divu,
+    Remu
+}
+
+// The assembled instructions, where pseudo instructions are kept as one instruction
+#[derive (Debug, Clone, Copy, PartialEq)]
+enum Instruction {
+    Nop,
+    // li and la both load a constant, where la has the address of the label
+    LoadImmediate { rd: usize, value: u64 },
+    Move { rd: usize, rs: usize },
+    Register { op: Operation, rd: usize, rs1: usize, rs2: usize },
+    AddImmediate { rd: usize, rs: usize, value: i64 },
+    AndImmediate { rd: usize, rs: usize, value: i64 },
+    SetLessThanImmediateUnsigned { rd: usize, rs: usize, value: i64 },
+    Load { width: Width, rd: usize, operand: MemoryOperand },
+    Store { width: Width, rs: usize, operand: MemoryOperand },
+    Branch { condition: BranchCondition, rs1: usize, rs2: usize, target: u64 },
+    Jump { target: u64 },
+    Call { target: u64 },
+    Ret,
+    Ecall
+}
+
+// A line of assembly that still needs its labels resolved
+struct PendingInstruction {
+    line_number: usize,
+    address: u64,
+    mnemonic: String,
+    operands: Vec<String>
+}
+
+// Assembler and emulator for the RV64 subset that the RISC-V code generator uses
+// Every instruction takes 4 bytes, including pseudo instructions like la and call
+#[derive (Debug)]
+pub struct EmulatorRiscV {
+    // The instructions at each address of the text
+    instructions: HashMap<u64, Instruction>,
+
+    // The program and the stack, starting at BASE_ADDRESS
+    memory: Vec<u8>,
+
+    // x0 through x31, where x0 is always 0
+    registers: [u64; 32],
+
+    program_counter: u64,
+
+    // Everything written to stdout so far
+    output: String
+}
+
+impl EmulatorRiscV {
+    // Assembles the program and loads it into memory
+    pub fn new(assembly: &str) -> Result<Self, AssembleError> {
+        let mut memory: Vec<u8> = Vec::new();
+        let mut labels: HashMap<String, u64> = HashMap::new();
+        let mut pending: Vec<PendingInstruction> = Vec::new();
+        let mut entry_label: String = String::from("_start");
+
+        // First pass lays out the data and finds the address of every label
+        let lines: Vec<SourceLine> = riscv_assembler::parse_lines(assembly)?;
+        for line in lines.into_iter() {
+            for label in line.labels.iter() {
+                if labels.insert(label.to_owned(), BASE_ADDRESS + memory.len() as u64).is_some() {
+                    return Err(AssembleError { line_number: line.line_number, message: format!("Label '{}' is defined more than once", label) });
+                }
+            }
+
+            match line.statement {
+                None => {},
+                // Sections are ignored, so everything is in one block of memory in the order it is written
+                Some(Statement::Directive { name, args }) => {
+                    if name == ".global" || name == ".globl" {
+                        entry_label = args;
+                    } else if name == ".align" || name == ".p2align" {
+                        let alignment: usize = riscv_assembler::parse_alignment(&args, line.line_number)?;
+                        memory.resize(memory.len().next_multiple_of(alignment), 0x00);
+                    } else if let Some(mut bytes) = riscv_assembler::parse_data(&name, &args, line.line_number)? {
+                        memory.append(&mut bytes);
+                    }
+                },
+                Some(Statement::Instruction { mnemonic, operands }) => {
+                    pending.push(PendingInstruction {
+                        line_number: line.line_number,
+                        address: BASE_ADDRESS + memory.len() as u64,
+                        mnemonic,
+                        operands
+                    });
+                    // The bytes are not used, but they keep the labels at the right addresses
+                    memory.extend_from_slice(&[0x00; 4]);
+                }
+            }
+        }
+
+        // Second pass parses the instructions now that every label is known
+        let mut instructions: HashMap<u64, Instruction> = HashMap::new();
+        for pending_instruction in pending.iter() {
+            let instruction: Instruction = parse_instruction(pending_instruction, &labels)
+                .map_err(|message| AssembleError { line_number: pending_instruction.line_number, message })?;
+            instructions.insert(pending_instruction.address, instruction);
+        }
+
+        let entry: u64 = *labels.get(&entry_label)
+            .ok_or_else(|| AssembleError { line_number: 0, message: format!("Entry point {} is never defined", entry_label) })?;
+
+        memory.resize(memory.len() + STACK_SIZE as usize, 0x00);
+        let mut registers: [u64; 32] = [0; 32];
+        // The stack starts at the end of memory and grows down
+        registers[2] = BASE_ADDRESS + memory.len() as u64;
+
+        return Ok(EmulatorRiscV {
+            instructions,
+            memory,
+            registers,
+            program_counter: entry,
+            output: String::new()
+        });
+    }
+
+    // Runs the program until it stops or runs max_instructions instructions
+    pub fn run(&mut self, max_instructions: usize) -> EmulatorResult {
+        let mut num_instructions: usize = 0;
+        let mut stop_reason: StopReason = StopReason::InstructionLimit;
+
+        while num_instructions < max_instructions {
+            num_instructions += 1;
+            let step_res: Option<StopReason> = self.step();
+            if step_res.is_some() {
+                stop_reason = step_res.unwrap();
+                break;
+            }
+        }
+
+        return EmulatorResult {
+            output: self.output.to_owned(),
+            stop_reason,
+            num_instructions
+        };
+    }
+
+    fn set_register(&mut self, register: usize, value: u64) {
+        // x0 is hardwired to 0
+        if register != 0 {
+            self.registers[register] = value;
+        }
+    }
+
+    // Gets the index into memory for the bytes at the address
+    fn get_memory_index(&self, target: u64, num_bytes: u64) -> Result<usize, StopReason> {
+        if target < BASE_ADDRESS || target + num_bytes > BASE_ADDRESS + self.memory.len() as u64 {
+            return Err(StopReason::InvalidAddress { target, address: self.program_counter });
+        }
+        return Ok((target - BASE_ADDRESS) as usize);
+    }
+
+    fn get_address(&self, operand: MemoryOperand) -> u64 {
+        match operand {
+            MemoryOperand::Offset(offset, register) => return self.registers[register].wrapping_add(offset as u64),
+            MemoryOperand::Absolute(address) => return address
+        }
+    }
+
+    // Runs a single instruction and returns the reason to stop, if any
+    fn step(&mut self) -> Option<StopReason> {
+        let instruction: Instruction = match self.instructions.get(&self.program_counter) {
+            Some(instruction) => *instruction,
+            None => return Some(StopReason::NotAnInstruction { address: self.program_counter })
+        };
+        let mut next_pc: u64 = self.program_counter + 4;
+
+        match instruction {
+            Instruction::Nop => {},
+            Instruction::LoadImmediate { rd, value } => self.set_register(rd, value),
+            Instruction::Move { rd, rs } => self.set_register(rd, self.registers[rs]),
+            Instruction::Register { op, rd, rs1, rs2 } => {
+                let left: u64 = self.registers[rs1];
+                let right: u64 = self.registers[rs2];
+                let value: u64 = match op {
+                    Operation::Add => left.wrapping_add(right),
+                    Operation::Sub => left.wrapping_sub(right),
+                    Operation::Xor => left ^ right,
+                    Operation::SetLessThanUnsigned => (left < right) as u64,
+                    Operation::Mul => left.wrapping_mul(right),
+                    // Division by 0 does not trap on RISC-V
+                    Operation::Divu => left.checked_div(right).unwrap_or(u64::MAX),
+                    Operation::Remu => left.checked_rem(right).unwrap_or(left)
+                };
+                self.set_register(rd, value);
+            },
+            Instruction::AddImmediate { rd, rs, value } => self.set_register(rd, self.registers[rs].wrapping_add(value as u64)),
+            Instruction::AndImmediate { rd, rs, value } => self.set_register(rd, self.registers[rs] & value as u64),
+            Instruction::SetLessThanImmediateUnsigned { rd, rs, value } => self.set_register(rd, (self.registers[rs] < value as u64) as u64),
+            Instruction::Load { width, rd, operand } => {
+                let target: u64 = self.get_address(operand);
+                let index: usize = match self.get_memory_index(target, width.num_bytes()) {
+                    Ok(index) => index,
+                    Err(reason) => return Some(reason)
+                };
+                let mut bytes: [u8; 8] = [0; 8];
+                bytes[..width.num_bytes() as usize].copy_from_slice(&self.memory[index..index + width.num_bytes() as usize]);
+                let value: u64 = u64::from_le_bytes(bytes);
+                let extended: u64 = match width {
+                    Width::Byte => value as u8 as i8 as i64 as u64,
+                    Width::Half => value as u16 as i16 as i64 as u64,
+                    Width::Word => value as u32 as i32 as i64 as u64,
+                    _ => value
+                };
+                self.set_register(rd, extended);
+            },
+            Instruction::Store { width, rs, operand } => {
+                let target: u64 = self.get_address(operand);
+                let index: usize = match self.get_memory_index(target, width.num_bytes()) {
+                    Ok(index) => index,
+                    Err(reason) => return Some(reason)
+                };
+                let bytes: [u8; 8] = self.registers[rs].to_le_bytes();
+                self.memory[index..index + width.num_bytes() as usize].copy_from_slice(&bytes[..width.num_bytes() as usize]);
+            },
+            Instruction::Branch { condition, rs1, rs2, target } => {
+                let left: u64 = self.registers[rs1];
+                let right: u64 = self.registers[rs2];
+                let is_taken: bool = match condition {
+                    BranchCondition::Eq => left == right,
+                    BranchCondition::Ne => left != right,
+                    BranchCondition::Lt => (left as i64) < (right as i64),
+                    BranchCondition::Ge => (left as i64) >= (right as i64),
+                    BranchCondition::Ltu => left < right,
+                    BranchCondition::Geu => left >= right
+                };
+                if is_taken {
+                    next_pc = target;
+                }
+            },
+            Instruction::Jump { target } => next_pc = target,
+            Instruction::Call { target } => {
+                self.set_register(1, next_pc);
+                next_pc = target;
+            },
+            Instruction::Ret => next_pc = self.registers[1],
+            Instruction::Ecall => {
+                let stop_reason: Option<StopReason> = self.system_call();
+                if stop_reason.is_some() {
+                    return stop_reason;
+                }
+            }
+        }
+
+        self.program_counter = next_pc;
+        return None;
+    }
+
+    // The system call number is in a7 and the arguments start at a0
+    fn system_call(&mut self) -> Option<StopReason> {
+        match self.registers[17] {
+            // write(fd, buf, len), where everything is treated as stdout
+            64 => {
+                let len: u64 = self.registers[12];
+                let index: usize = match self.get_memory_index(self.registers[11], len) {
+                    Ok(index) => index,
+                    Err(reason) => return Some(reason)
+                };
+                let text: String = String::from_utf8_lossy(&self.memory[index..index + len as usize]).to_string();
+                self.output.push_str(&text);
+                self.set_register(10, len);
+                return None;
+            },
+            // exit(code)
+            93 => return Some(StopReason::Exit(self.registers[10])),
+            code => return Some(StopReason::InvalidSystemCall { code, address: self.program_counter })
+        }
+    }
+}
+
+// Parses offset(register) or a label for loads and stores
+fn parse_memory_operand(operand: &str, labels: &HashMap<String, u64>) -> Result<MemoryOperand, String> {
+    match riscv_assembler::parse_offset_operand(operand)? {
+        Some((offset, register)) => return Ok(MemoryOperand::Offset(offset, register)),
+        None => return Ok(MemoryOperand::Absolute(riscv_assembler::parse_label(operand, labels)?))
+    }
+}
+
+fn parse_instruction(pending: &PendingInstruction, labels: &HashMap<String, u64>) -> Result<Instruction, String> {
+    let operands: &Vec<String> = &pending.operands;
+    let expected_operands: usize = riscv_assembler::get_num_operands(&pending.mnemonic)?;
+    if operands.len() != expected_operands {
+        return Err(format!("Expected {} operands for {}, but received {}", expected_operands, pending.mnemonic, operands.len()));
+    }
+
+    let instruction: Instruction = match pending.mnemonic.as_str() {
+        "nop" => Instruction::Nop,
+        "ret" => Instruction::Ret,
+        "ecall" => Instruction::Ecall,
+        "j" => Instruction::Jump { target: riscv_assembler::parse_label(&operands[0], labels)? },
+        "call" => Instruction::Call { target: riscv_assembler::parse_label(&operands[0], labels)? },
+        "li" => Instruction::LoadImmediate {
+            rd: riscv_assembler::parse_register(&operands[0])?,
+            value: riscv_assembler::parse_immediate(&operands[1]).ok_or_else(|| format!("Invalid immediate '{}'", operands[1]))? as u64
+        },
+        "la" => Instruction::LoadImmediate { rd: riscv_assembler::parse_register(&operands[0])?, value: riscv_assembler::parse_label(&operands[1], labels)? },
+        "mv" => Instruction::Move { rd: riscv_assembler::parse_register(&operands[0])?, rs: riscv_assembler::parse_register(&operands[1])? },
+        "add" | "sub" | "xor" | "sltu" | "mul" | "divu" | "remu" => Instruction::Register {
+            op: match pending.mnemonic.as_str() {
+                "add" => Operation::Add,
+                "sub" => Operation::Sub,
+                "xor" => Operation::Xor,
+                "sltu" => Operation::SetLessThanUnsigned,
+                "mul" => Operation::Mul,
+                "divu" => Operation::Divu,
+                _ => Operation::Remu
+            },
+            rd: riscv_assembler::parse_register(&operands[0])?,
+            rs1: riscv_assembler::parse_register(&operands[1])?,
+            rs2: riscv_assembler::parse_register(&operands[2])?
+        },
+        "addi" => Instruction::AddImmediate {
+            rd: riscv_assembler::parse_register(&operands[0])?,
+            rs: riscv_assembler::parse_register(&operands[1])?,
+            value: riscv_assembler::parse_immediate(&operands[2]).ok_or_else(|| format!("Invalid immediate '{}'", operands[2]))?
+        },
+        "andi" => Instruction::AndImmediate {
+            rd: riscv_assembler::parse_register(&operands[0])?,
+            rs: riscv_assembler::parse_register(&operands[1])?,
+            value: riscv_assembler::parse_immediate(&operands[2]).ok_or_else(|| format!("Invalid immediate '{}'", operands[2]))?
+        },
+        "sltiu" => Instruction::SetLessThanImmediateUnsigned {
+            rd: riscv_assembler::parse_register(&operands[0])?,
+            rs: riscv_assembler::parse_register(&operands[1])?,
+            value: riscv_assembler::parse_immediate(&operands[2]).ok_or_else(|| format!("Invalid immediate '{}'", operands[2]))?
+        },
+        // seqz is sltiu rd, rs, 1 and snez is sltu rd, zero, rs
+        "seqz" => Instruction::SetLessThanImmediateUnsigned {
+            rd: riscv_assembler::parse_register(&operands[0])?,
+            rs: riscv_assembler::parse_register(&operands[1])?,
+            value: 1
+        },
+        "snez" => Instruction::Register {
+            op: Operation::SetLessThanUnsigned,
+            rd: riscv_assembler::parse_register(&operands[0])?,
+            rs1: 0,
+            rs2: riscv_assembler::parse_register(&operands[1])?
+        },
+        "lb" | "lbu" | "lh" | "lhu" | "lw" | "lwu" | "ld" => Instruction::Load {
+            width: match pending.mnemonic.as_str() {
+                "lb" => Width::Byte,
+                "lbu" => Width::ByteUnsigned,
+                "lh" => Width::Half,
+                "lhu" => Width::HalfUnsigned,
+                "lw" => Width::Word,
+                "lwu" => Width::WordUnsigned,
+                _ => Width::Double
+            },
+            rd: riscv_assembler::parse_register(&operands[0])?,
+            operand: parse_memory_operand(&operands[1], labels)?
+        },
+        "sb" | "sh" | "sw" | "sd" => Instruction::Store {
+            width: match pending.mnemonic.as_str() {
+                "sb" => Width::Byte,
+                "sh" => Width::Half,
+                "sw" => Width::Word,
+                _ => Width::Double
+            },
+            rs: riscv_assembler::parse_register(&operands[0])?,
+            operand: parse_memory_operand(&operands[1], labels)?
+        },
+        "beqz" | "bnez" => Instruction::Branch {
+            condition: if pending.mnemonic == "beqz" { BranchCondition::Eq } else { BranchCondition::Ne },
+            rs1: riscv_assembler::parse_register(&operands[0])?,
+            rs2: 0,
+            target: riscv_assembler::parse_label(&operands[1], labels)?
+        },
+        _ => Instruction::Branch {
+            condition: match pending.mnemonic.as_str() {
+                "beq" => BranchCondition::Eq,
+                "bne" => BranchCondition::Ne,
+                "blt" => BranchCondition::Lt,
+                "bge" => BranchCondition::Ge,
+                "bltu" => BranchCondition::Ltu,
+                _ => BranchCondition::Geu
+            },
+            rs1: riscv_assembler::parse_register(&operands[0])?,
+            rs2: riscv_assembler::parse_register(&operands[1])?,
+            target: riscv_assembler::parse_label(&operands[2], labels)?
+        }
+    };
+    return Ok(instruction);
+}
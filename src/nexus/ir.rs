@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::nexus::symbol_table::Type;
+use crate::nexus::diagnostic::Span;
+
+// A variable in static storage, which is unique by its name and the scope it was declared in
+#[derive (Debug, Clone, PartialEq)]
+pub struct Variable {
+    pub name: String,
+    pub scope: usize,
+    pub var_type: Type
+}
+
+// Variables are named like a_0, which is the same as the labels in the RISC-V code
+impl fmt::Display for Variable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.name, self.scope)
+    }
+}
+
+// A temporary value from an expression
+// Temps are numbered in the order they are created and are only used once by a later instruction
+#[derive (Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Temp(pub usize);
+
+impl fmt::Display for Temp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "t{}", self.0)
+    }
+}
+
+// A position in the code that can be jumped to
+#[derive (Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Label(pub usize);
+
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "L{}", self.0)
+    }
+}
+
+// Anything an instruction can read
+#[derive (Debug, Clone, PartialEq)]
+pub enum Value {
+    Var(Variable),
+    Temp(Temp),
+    // Always fits in the integers of the program
+    Int(u64),
+    Bool(bool),
+    // The backends decide where the string is stored
+    String(String)
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Var(var) => write!(f, "{}", var),
+            Value::Temp(temp) => write!(f, "{}", temp),
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::String(value) => write!(f, "\"{}\"", value)
+        }
+    }
+}
+
+// The three-address code instructions
+// Integers are int_bits wide, so additions wrap around at the same place for every backend
+#[derive (Debug, Clone, PartialEq)]
+pub enum Instruction {
+    // Marks where the code for a statement in the source code starts, which does not generate any code
+    // If and while statements only point at a keyword and their condition instead of the whole body
+    StatementStart { span: Span, condition: Option<(&'static str, Span)> },
+    // Creates the storage for a variable, where integers and booleans start as 0 and strings are not initialized
+    Declare(Variable),
+    // dest = value
+    Assign { dest: Variable, value: Value },
+    // dest = left + right
+    Add { dest: Temp, left: Value, right: Value },
+    // dest = left == right, or left != right if is_eq is false
+    Compare { dest: Temp, left: Value, right: Value, is_eq: bool },
+    // Prints the value as the given type
+    Print { value: Value, value_type: Type },
+    Label(Label),
+    Jump(Label),
+    // Jumps to the target if the condition is false, or goes to the next instruction if it is true
+    BranchIfFalse { condition: Value, target: Label }
+}
+
+impl Instruction {
+    // Gets the values the instruction reads
+    pub fn get_values(&self) -> Vec<&Value> {
+        match self {
+            Instruction::Add { left, right, .. } | Instruction::Compare { left, right, .. } => return vec![left, right],
+            Instruction::Assign { value, .. } | Instruction::Print { value, .. } | Instruction::BranchIfFalse { condition: value, .. } => return vec![value],
+            _ => return Vec::new()
+        }
+    }
+
+    pub fn get_values_mut(&mut self) -> Vec<&mut Value> {
+        match self {
+            Instruction::Add { left, right, .. } | Instruction::Compare { left, right, .. } => return vec![left, right],
+            Instruction::Assign { value, .. } | Instruction::Print { value, .. } | Instruction::BranchIfFalse { condition: value, .. } => return vec![value],
+            _ => return Vec::new()
+        }
+    }
+
+    // Gets the label the instruction can jump to
+    pub fn get_target(&self) -> Option<Label> {
+        match self {
+            Instruction::Jump(target) | Instruction::BranchIfFalse { target, .. } => return Some(*target),
+            _ => return None
+        }
+    }
+}
+
+// Integers are bytes unless the multi-digit integer extension lets them use the full width of the target
+pub const DEFAULT_INT_BITS: u32 = 8;
+
+// The largest integer that fits in the number of bits, which is at most 64
+pub fn max_int(int_bits: u32) -> u64 {
+    return u64::MAX >> (64 - int_bits);
+}
+
+pub fn type_name(value_type: &Type) -> &'static str {
+    match value_type {
+        Type::Int => return "int",
+        Type::String => return "string",
+        Type::Boolean => return "boolean"
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::StatementStart { span, condition: Some((keyword, _)) } => write!(f, "# {}:{} {}", span.line, span.col, keyword),
+            Instruction::StatementStart { span, condition: None } => write!(f, "# {}:{}", span.line, span.col),
+            Instruction::Declare(var) => write!(f, "declare {} {}", type_name(&var.var_type), var),
+            Instruction::Assign { dest, value } => write!(f, "{} = {}", dest, value),
+            Instruction::Add { dest, left, right } => write!(f, "{} = {} + {}", dest, left, right),
+            Instruction::Compare { dest, left, right, is_eq } => write!(f, "{} = {} {} {}", dest, left, if *is_eq { "==" } else { "!=" }, right),
+            Instruction::Print { value, value_type } => write!(f, "print {} {}", type_name(value_type), value),
+            Instruction::Label(label) => write!(f, "{}:", label),
+            Instruction::Jump(label) => write!(f, "goto {}", label),
+            Instruction::BranchIfFalse { condition, target } => write!(f, "ifFalse {} goto {}", condition, target)
+        }
+    }
+}
+
+// The IR for a program, which is what the backends generate code from
+#[derive (Debug, Clone, PartialEq)]
+pub struct IrProgram {
+    pub instructions: Vec<Instruction>,
+    // Temps and labels are numbered from 0 up to these
+    pub num_temps: usize,
+    pub num_labels: usize,
+    // The number of bits in an integer, where additions wrap around
+    pub int_bits: u32
+}
+
+impl IrProgram {
+    // Creates the text version of the IR with 1 instruction per line
+    // Labels are not indented so the jumps are easy to follow
+    pub fn create_text(&self) -> String {
+        let mut text: String = String::new();
+        for instruction in self.instructions.iter() {
+            match instruction {
+                Instruction::Label(_) => text.push_str(&format!("{}\n", instruction)),
+                _ => text.push_str(&format!("    {}\n", instruction))
+            }
+        }
+        return text;
+    }
+
+    // Numbers the temps in the order they are created so the backends do not reserve space
+    // for temps that were optimized away
+    pub fn renumber_temps(&mut self) {
+        let mut new_temps: HashMap<Temp, Temp> = HashMap::new();
+        for instruction in self.instructions.iter_mut() {
+            for value in instruction.get_values_mut() {
+                if let Value::Temp(temp) = value {
+                    *temp = new_temps[temp];
+                }
+            }
+            if let Instruction::Add { dest, .. } | Instruction::Compare { dest, .. } = instruction {
+                let new_temp: Temp = Temp(new_temps.len());
+                new_temps.insert(*dest, new_temp);
+                *dest = new_temp;
+            }
+        }
+        self.num_temps = new_temps.len();
+    }
+}
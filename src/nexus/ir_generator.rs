@@ -0,0 +1,185 @@
+use log::*;
+
+use crate::nexus::symbol_table::*;
+use crate::nexus::ast::{Block, VarDecl, Assign, Print, If, While, Ident, Expr};
+use crate::nexus::ast_visitor::Visitor;
+use crate::nexus::ir::{self, IrProgram, Instruction, Variable, Temp, Label, Value};
+use crate::util::nexus_log;
+
+// Lowers the AST into three-address code, which is the same for every backend
+#[derive (Debug)]
+pub struct IrGenerator {
+    instructions: Vec<Instruction>,
+
+    // The number of temps and labels used so far
+    num_temps: usize,
+    num_labels: usize,
+
+    // The number of bits in an integer, where literals that are too big wrap around
+    int_bits: u32
+}
+
+impl IrGenerator {
+    pub fn new(int_bits: u32) -> Self {
+        return IrGenerator {
+            instructions: Vec::new(),
+            num_temps: 0,
+            num_labels: 0,
+            int_bits
+        };
+    }
+
+    // Generates the IR for a program that passed semantic analysis
+    pub fn generate_ir(&mut self, ast: &Block, symbol_table: &mut SymbolTable) -> IrProgram {
+        self.instructions.clear();
+        self.num_temps = 0;
+        self.num_labels = 0;
+
+        self.visit_block(ast, symbol_table);
+
+        nexus_log::log(
+            nexus_log::LogTypes::Info,
+            nexus_log::LogSources::CodeGenerator,
+            format!("IR generation completed with {} instructions", self.instructions.len())
+        );
+
+        return IrProgram {
+            instructions: std::mem::take(&mut self.instructions),
+            num_temps: self.num_temps,
+            num_labels: self.num_labels,
+            int_bits: self.int_bits
+        };
+    }
+
+    fn new_temp(&mut self) -> Temp {
+        self.num_temps += 1;
+        return Temp(self.num_temps - 1);
+    }
+
+    fn new_label(&mut self) -> Label {
+        self.num_labels += 1;
+        return Label(self.num_labels - 1);
+    }
+
+    // Gets the variable an identifier refers to
+    fn get_variable(&self, id: &Ident, symbol_table: &mut SymbolTable) -> Variable {
+        let id_entry: &SymbolTableEntry = symbol_table.get_symbol_with_context(&id.name, id.span).unwrap();
+        return Variable {
+            name: id.name.to_owned(),
+            scope: id_entry.scope,
+            var_type: id_entry.symbol_type.to_owned()
+        };
+    }
+
+    // Adds the instructions for an expression and returns where its value is
+    fn lower_expr(&mut self, expr: &Expr, symbol_table: &mut SymbolTable) -> Value {
+        match expr {
+            Expr::Id(id) => return Value::Var(self.get_variable(id, symbol_table)),
+            // The semantic analyzer already warned about the literals that do not fit
+            Expr::IntLiteral { value, .. } => return Value::Int(value & ir::max_int(self.int_bits)),
+            Expr::StringLiteral { value, .. } => return Value::String(value.to_owned()),
+            Expr::BoolLiteral { value, .. } => return Value::Bool(*value),
+            Expr::Add { left, right, .. } => {
+                // The right side is done first because it is the one that can be another addition
+                let right_value: Value = self.lower_expr(right, symbol_table);
+                let left_value: Value = self.lower_expr(left, symbol_table);
+                let dest: Temp = self.new_temp();
+                self.instructions.push(Instruction::Add { dest, left: left_value, right: right_value });
+                return Value::Temp(dest);
+            },
+            Expr::IsEq { left, right, .. } | Expr::NotEq { left, right, .. } => {
+                let left_value: Value = self.lower_expr(left, symbol_table);
+                let right_value: Value = self.lower_expr(right, symbol_table);
+                let dest: Temp = self.new_temp();
+                self.instructions.push(Instruction::Compare {
+                    dest,
+                    left: left_value,
+                    right: right_value,
+                    is_eq: matches!(expr, Expr::IsEq { .. })
+                });
+                return Value::Temp(dest);
+            }
+        }
+    }
+
+    // Adds the branch to the label if the condition is false
+    // A true condition does not need a branch, so nothing is added
+    fn lower_condition(&mut self, condition: &Expr, false_label: Label, symbol_table: &mut SymbolTable) {
+        match condition {
+            Expr::BoolLiteral { value: true, .. } => {},
+            Expr::IsEq { .. } | Expr::NotEq { .. } => {
+                let condition_value: Value = self.lower_expr(condition, symbol_table);
+                self.instructions.push(Instruction::BranchIfFalse { condition: condition_value, target: false_label });
+            },
+            _ => error!("Received {:?} when expecting a boolean expression or true for a condition", condition)
+        }
+    }
+}
+
+// The Visitor walks the blocks and sends each statement to its function
+impl Visitor<SymbolTable> for IrGenerator {
+    fn enter_scope(&mut self, scope: usize, symbol_table: &mut SymbolTable) {
+        // Manually set the current scope because we are not able to look down
+        // in the symbol table
+        symbol_table.set_cur_scope(scope);
+    }
+
+    fn exit_scope(&mut self, _scope: usize, symbol_table: &mut SymbolTable) {
+        symbol_table.end_cur_scope();
+    }
+
+    fn visit_var_decl(&mut self, var_decl: &VarDecl, symbol_table: &mut SymbolTable) {
+        self.instructions.push(Instruction::StatementStart { span: var_decl.span, condition: None });
+        self.instructions.push(Instruction::Declare(Variable {
+            name: var_decl.id.name.to_owned(),
+            scope: symbol_table.cur_scope.unwrap(),
+            var_type: var_decl.var_type.to_owned()
+        }));
+    }
+
+    fn visit_assign(&mut self, assign: &Assign, symbol_table: &mut SymbolTable) {
+        self.instructions.push(Instruction::StatementStart { span: assign.span, condition: None });
+        let value: Value = self.lower_expr(&assign.value, symbol_table);
+        let dest: Variable = self.get_variable(&assign.id, symbol_table);
+        self.instructions.push(Instruction::Assign { dest, value });
+    }
+
+    fn visit_print(&mut self, print: &Print, symbol_table: &mut SymbolTable) {
+        self.instructions.push(Instruction::StatementStart { span: print.span, condition: None });
+
+        // Additions are integers and comparisons are booleans
+        let value_type: Type = match &print.expr {
+            Expr::Id(id) => self.get_variable(id, symbol_table).var_type,
+            Expr::IntLiteral { .. } | Expr::Add { .. } => Type::Int,
+            Expr::StringLiteral { .. } => Type::String,
+            Expr::BoolLiteral { .. } | Expr::IsEq { .. } | Expr::NotEq { .. } => Type::Boolean
+        };
+        let value: Value = self.lower_expr(&print.expr, symbol_table);
+        self.instructions.push(Instruction::Print { value, value_type });
+    }
+
+    fn visit_if(&mut self, if_stmt: &If, symbol_table: &mut SymbolTable) {
+        self.instructions.push(Instruction::StatementStart { span: if_stmt.span, condition: Some(("if", if_stmt.condition.span())) });
+        let end_label: Label = self.new_label();
+        self.lower_condition(&if_stmt.condition, end_label, symbol_table);
+
+        self.visit_block(&if_stmt.body, symbol_table);
+        self.instructions.push(Instruction::Label(end_label));
+    }
+
+    fn visit_while(&mut self, while_stmt: &While, symbol_table: &mut SymbolTable) {
+        let start_label: Label = self.new_label();
+        let end_label: Label = self.new_label();
+
+        self.instructions.push(Instruction::StatementStart { span: while_stmt.span, condition: Some(("while", while_stmt.condition.span())) });
+        self.instructions.push(Instruction::Label(start_label));
+        self.lower_condition(&while_stmt.condition, end_label, symbol_table);
+
+        self.visit_block(&while_stmt.body, symbol_table);
+
+        // The jump back to the condition does not belong to the last statement in the body
+        self.instructions.push(Instruction::StatementStart { span: while_stmt.span, condition: Some(("end of while", while_stmt.condition.span())) });
+        self.instructions.push(Instruction::Jump(start_label));
+        self.instructions.push(Instruction::Label(end_label));
+    }
+}
@@ -0,0 +1,181 @@
+use petgraph::graph::NodeIndex;
+use serde_json::{json, Value};
+
+use crate::nexus::compiler::{CompilationResult, ProgramResult};
+use crate::nexus::diagnostic::{Diagnostic, Span};
+use crate::nexus::symbol_table::{SymbolTable, SymbolTableEntry};
+use crate::nexus::syntax_tree::SyntaxTree;
+use crate::nexus::syntax_tree_node::SyntaxTreeNode;
+use crate::nexus::token::{Token, TokenType};
+use crate::nexus::backend::Artifact;
+use crate::util::diagnostic_sink::{LogEntry, get_log_type};
+
+// Converts the result of a compilation into a JSON document for frontends that are not the webpage
+pub fn compilation_to_json(result: &CompilationResult, target: &str) -> Value {
+    let programs: Vec<Value> = result.programs.iter().map(program_to_json).collect();
+
+    // Only keep the actual messages from the logs
+    let logs: Vec<Value> = result.logs.iter().filter_map(|entry| {
+        match entry {
+            LogEntry::Message { log_type, src, msg } => Some(json!({
+                "type": log_type.to_string(),
+                "source": src.to_string(),
+                "message": msg
+            })),
+            LogEntry::Diagnostic(diagnostic) => Some(json!({
+                "type": get_log_type(diagnostic).to_string(),
+                "source": diagnostic.phase.to_string(),
+                "message": diagnostic.get_log_message()
+            })),
+            LogEntry::EmptyLine => None
+        }
+    }).collect();
+
+    return json!({
+        "target": target,
+        "success": result.is_ok(),
+        "programs": programs,
+        "logs": logs
+    });
+}
+
+// Creates the JSON document for when the compiler could not be run
+pub fn error_to_json(msg: &str) -> Value {
+    return json!({
+        "success": false,
+        "error": msg,
+        "programs": []
+    });
+}
+
+fn program_to_json(program: &ProgramResult) -> Value {
+    return json!({
+        "program_number": program.program_number,
+        "success": program.is_ok(),
+        "tokens": program.tokens.as_ref().map(|tokens| tokens.iter().map(token_to_json).collect::<Vec<Value>>()),
+        "cst": program.cst.as_ref().map(tree_to_json),
+        "ast": program.ast.as_ref().map(|ast| tree_to_json(&ast.create_syntax_tree())),
+        "symbol_table": program.symbol_table.as_ref().map(symbol_table_to_json),
+        "ir": program.ir.as_ref().map(|ir| ir.create_text()),
+        "diagnostics": program.diagnostics.iter().map(diagnostic_to_json).collect::<Vec<Value>>(),
+        "code": program.code.as_ref().map(code_to_json),
+        "listing": program.listing
+    });
+}
+
+fn position_to_json(position: &(usize, usize)) -> Value {
+    return json!({ "line": position.0, "col": position.1 });
+}
+
+fn span_to_json(span: &Span) -> Value {
+    return json!({ "start": span.start, "end": span.end, "line": span.line, "col": span.col });
+}
+
+fn token_to_json(token: &Token) -> Value {
+    // Split the token type into its kind and the value it holds
+    let (kind, value): (&str, Value) = match &token.token_type {
+        TokenType::Keyword(keyword) => ("Keyword", json!(format!("{:?}", keyword))),
+        TokenType::Identifier(id) => ("Identifier", json!(id)),
+        TokenType::Symbol(symbol) => ("Symbol", json!(format!("{:?}", symbol))),
+        TokenType::Digit(num) => ("Digit", json!(num)),
+        TokenType::Char(char) => ("Char", json!(char)),
+        TokenType::Unrecognized(text) => ("Unrecognized", json!(text))
+    };
+
+    return json!({
+        "kind": kind,
+        "value": value,
+        "text": token.text,
+        "position": position_to_json(&token.position()),
+        "span": span_to_json(&token.span)
+    });
+}
+
+fn tree_to_json(tree: &SyntaxTree) -> Value {
+    return json!({
+        "dot": tree.create_dot(),
+        "text": tree.create_text(),
+        "root": tree.root.map(|root| tree_node_to_json(tree, NodeIndex::new(root)))
+    });
+}
+
+fn tree_node_to_json(tree: &SyntaxTree, cur_index: NodeIndex) -> Value {
+    // Children are stored in reverse order
+    let children: Vec<Value> = tree.graph.neighbors(cur_index)
+        .collect::<Vec<NodeIndex>>()
+        .into_iter()
+        .rev()
+        .map(|child| tree_node_to_json(tree, child))
+        .collect();
+
+    match tree.graph.node_weight(cur_index).unwrap() {
+        SyntaxTreeNode::Terminal(token) => return json!({
+            "kind": "Terminal",
+            "label": token.text,
+            "token": token_to_json(token),
+            "children": children
+        }),
+        SyntaxTreeNode::NonTerminalCst(non_terminal) => return json!({
+            "kind": "NonTerminal",
+            "label": non_terminal.to_string(),
+            "children": children
+        }),
+        SyntaxTreeNode::NonTerminalAst(non_terminal) => return json!({
+            "kind": "NonTerminal",
+            "label": non_terminal.to_string(),
+            "children": children
+        })
+    }
+}
+
+fn symbol_table_to_json(symbol_table: &SymbolTable) -> Value {
+    let scopes: Vec<Value> = (0..symbol_table.num_scopes()).map(|scope| {
+        let symbols: Vec<Value> = symbol_table.get_scope_entries(scope).into_iter()
+            .map(|(id, entry)| symbol_to_json(id, entry))
+            .collect();
+
+        json!({
+            "scope": scope,
+            "parent": symbol_table.get_parent_scope(scope),
+            "symbols": symbols
+        })
+    }).collect();
+
+    return json!({ "scopes": scopes });
+}
+
+fn symbol_to_json(id: &str, entry: &SymbolTableEntry) -> Value {
+    return json!({
+        "id": id,
+        "type": format!("{:?}", entry.symbol_type),
+        "position": position_to_json(&entry.span.position()),
+        "span": span_to_json(&entry.span),
+        "initialized": entry.is_initialized,
+        "used": entry.is_used
+    });
+}
+
+fn diagnostic_to_json(diagnostic: &Diagnostic) -> Value {
+    let labels: Vec<Value> = diagnostic.labels.iter().map(|label| json!({
+        "span": span_to_json(&label.span),
+        "message": label.message
+    })).collect();
+
+    return json!({
+        "severity": diagnostic.severity.to_string(),
+        "phase": diagnostic.phase.to_string(),
+        "code": diagnostic.code.code(),
+        "message": diagnostic.message,
+        "span": diagnostic.span.as_ref().map(span_to_json),
+        "labels": labels,
+        "notes": diagnostic.notes
+    });
+}
+
+fn code_to_json(code: &Artifact) -> Value {
+    match code {
+        // Give both the actual bytes and the hex string of the image
+        Artifact::Image(bytes) => return json!({ "image": bytes, "text": code.to_string() }),
+        Artifact::Assembly(assembly) => return json!({ "assembly": assembly })
+    }
+}
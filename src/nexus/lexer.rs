@@ -1,5 +1,12 @@
 use crate::{nexus::token::{Token, TokenType, Keywords, Symbols}, util::nexus_log};
-use regex::{Regex, RegexSet, SetMatches};
+use crate::nexus::diagnostic::{self, Diagnostic, DiagnosticCode, PhaseResult, Span};
+
+// A state in the DFA that recognizes keywords
+// Each state is a prefix of at least one keyword
+struct KeywordState {
+    transitions: [Option<usize>; 26], // The next state for each letter a-z
+    keyword: Option<Keywords> // The keyword if this state is the end of one
+}
 
 // Struct to maintain the state of the line numbers when compiling multiple programs
 pub struct Lexer {
@@ -7,72 +14,65 @@ pub struct Lexer {
     line_number: usize, // The line number we are on
     col_number: usize, // The current column number
     current_position: usize, // The current position in the string
-    keywords: RegexSet, // The regex set for keywords
-    characters: Regex, // The regex for characters
-    symbols: RegexSet, // The regex set for symbols
-    digits: Regex, // The regex for digits
-    terminal_chars: RegexSet // The regex set for terminal characters
+    keyword_dfa: Vec<KeywordState>, // The states of the keyword DFA, where the start state is 0
+    multi_digit_integers: bool, // Whether consecutive digits are a single integer literal, which is a language extension
+    diagnostics: Vec<Diagnostic> // The errors and warnings for the current program
 }
 
 impl Lexer {
     // Creates the new lexer and initializes the starting position to be (1, 1)
-    pub fn new(program_code: &str) -> Self {
+    pub fn new(program_code: &str, multi_digit_integers: bool) -> Self {
         return Lexer {
             source_code: String::from(program_code),
             line_number: 1,
             col_number: 1,
             current_position: 0,
-            
-            // All of the acceptable keywords
-            keywords: RegexSet::new(&[
-                r"^if$",
-                r"^while$",
-                r"^print$",
-                r"^string$",
-                r"^int$",
-                r"^boolean$",
-                r"^true$",
-                r"^false$",
-            ]).unwrap(),
-
-            // a-z
-            characters: Regex::new(r"^[a-z]$").unwrap(),
-
-            // (, ), {, }, ==, =, +, ", !=, or $
-            symbols: RegexSet::new(&[
-                r"^\($",
-                r"^\)$",
-                r"^\{$",
-                r"^\}$",
-                r"^\+$",
-                r"^==$",
-                r"^!=$",
-                r"^=$",
-                r#"^"$"#,
-                r"^\$$"
-            ]).unwrap(),
-
-            // 0-9
-            digits: Regex::new(r"^[0-9]$").unwrap(),
-
-            // White space and simplified symbols (only 1 char each)
-            terminal_chars: RegexSet::new(&[
-                r"^(\n|\t| )$",
-                r"^=$",
-                r#"^"$"#,
-                r"^!$",
-                r"^\($",
-                r"^\)$",
-                r"^\{$",
-                r"^\}$",
-                r"^\+$",
-                r"^\$$"
-            ]).unwrap()
+            keyword_dfa: Lexer::create_keyword_dfa(),
+            multi_digit_integers,
+            diagnostics: Vec::new()
+        }
+    }
+
+    // Builds the DFA for all of the acceptable keywords
+    fn create_keyword_dfa() -> Vec<KeywordState> {
+        let keywords: [(&str, Keywords); 8] = [
+            ("if", Keywords::If),
+            ("while", Keywords::While),
+            ("print", Keywords::Print),
+            ("string", Keywords::String),
+            ("int", Keywords::Int),
+            ("boolean", Keywords::Boolean),
+            ("true", Keywords::True),
+            ("false", Keywords::False)
+        ];
+
+        // Start with only the start state
+        let mut states: Vec<KeywordState> = vec![KeywordState { transitions: [None; 26], keyword: None }];
+
+        for (text, keyword) in keywords {
+            // Follow the existing transitions and add new states where there are none
+            let mut cur_state: usize = 0;
+            for c in text.bytes() {
+                let letter: usize = (c - b'a') as usize;
+                if states[cur_state].transitions[letter].is_none() {
+                    states.push(KeywordState { transitions: [None; 26], keyword: None });
+                    states[cur_state].transitions[letter] = Some(states.len() - 1);
+                }
+                cur_state = states[cur_state].transitions[letter].unwrap();
+            }
+
+            // The last state accepts the keyword
+            states[cur_state].keyword = Some(keyword);
         }
+
+        return states;
     }
 
     // Function to lex a program
-    pub fn lex_program(&mut self) -> Result<Vec<Token>, ()> {
+    pub fn lex_program(&mut self) -> PhaseResult<Vec<Token>> {
+        // Each program gets its own set of diagnostics
+        self.diagnostics.clear();
+
         let lex_out: Result<(Vec<Token>, i32), (i32, i32)> = self.lex();
         if lex_out.is_ok() {
             // Grab the token stream and number of warnings
@@ -90,7 +90,7 @@ impl Lexer {
             );
 
             // Return the token stream
-            return Ok(token_stream);
+            return PhaseResult::new(Some(token_stream), std::mem::take(&mut self.diagnostics));
         } else {
             // Get the number of errors and warnings
             let (num_errors, num_warnings): (i32, i32) = lex_out.unwrap_err();
@@ -117,8 +117,8 @@ impl Lexer {
                 out_string
             );
 
-            // Nothing has to be returned because error messages have been logged already so just let the compiler know it failed
-            return Err(());
+            // The errors have already been logged, so just let the compiler know it failed
+            return PhaseResult::new(None, std::mem::take(&mut self.diagnostics));
         }
     }
 
@@ -135,219 +135,201 @@ impl Lexer {
         // Better than initially allocating a ton of memory considering that these programs are small
         let mut token_stream: Vec<Token> = Vec::new();
 
-        // The start and end indices in the source code string for the token
-        // current_position == best_end means that the token is empty (space or newline by itself)
-        let mut best_end: usize = self.current_position.to_owned();
-
-        // The cur token type
-        let mut cur_token_type: TokenType = TokenType::Unrecognized(String::from(""));
-
-        // The current position in the source code
-        let mut trailer: usize = self.current_position.to_owned();
-
         // Initially not in a string
         let mut in_string: bool = false;
 
         // Initially not in a comment
         let mut in_comment: bool = false;
-        let mut comment_position: (usize, usize) = (0, 0);
-        let comment_regex: RegexSet = RegexSet::new(&[r"^/\*$", r"^\*/$"]).unwrap();
+        let mut comment_span: Span = Span::new(0, 0, 0, 0);
 
         let mut end_found: bool = false;
 
-        // Iterate through the end of the string
+        // Every pass through the loop starts a new token, so each character is only looked at
+        // once aside from the lookahead for keywords, == and !=
         while !end_found && self.current_position < self.source_code.len() {
-            // If it is the start of a search and we have space for a comment (/* or */)
-            if self.current_position == trailer && self.current_position < self.source_code.len() - 1 {
-                // Get the next 2 characters
-                let next_2: &str = &self.source_code[self.current_position..self.current_position + 2];
-
-                let comment_matches = comment_regex.matches(next_2);
-                // If it is a comment symbol
-                if !in_comment && comment_matches.matched(0) || in_comment && comment_matches.matched(1) {
+            let source: &[u8] = self.source_code.as_bytes();
+
+            // Comment symbols (/* or */) are only checked for at the start of a token
+            if self.current_position + 1 < source.len() {
+                let next_2: &[u8] = &source[self.current_position..self.current_position + 2];
+                if !in_comment && next_2 == b"/*" || in_comment && next_2 == b"*/" {
                     // Get the updated comment start position
                     if !in_comment {
-                        comment_position = (self.line_number, self.col_number);
+                        comment_span = Span::new(self.current_position, self.current_position + 2, self.line_number, self.col_number);
                     }
 
                     // Flip and skip both characters
                     in_comment = !in_comment;
                     self.current_position += 2;
-                    best_end += 2;
-                    trailer += 2;
+                    self.col_number += 2;
+
+                    if self.current_position == source.len() {
+                        break;
+                    }
                 }
             }
-            
-            // Get the current character if legal
-            let mut cur_char: &str = "";
-            if trailer < self.source_code.len() {
-                cur_char = &self.source_code[trailer..trailer + 1];
-            }
 
-            let mut terminal_found: bool = false;
-            // Check prevents index out of bounds on the low end
-            if trailer > 0 {
-                // Check to see if we hit a terminal character
-                terminal_found = self.check_terminal(&cur_char, &self.source_code[trailer - 1..trailer], &in_string, &trailer);
+            let cur_char: u8 = source[self.current_position];
+
+            // New lines always separate tokens, but spaces and tabs are part of strings
+            // The very first character of the source is always treated as the start of a token
+            let is_whitespace: bool = self.current_position > 0 && (cur_char == b'\n' || !in_string && (cur_char == b' ' || cur_char == b'\t'));
+
+            if in_comment || is_whitespace {
+                // Nothing to add to the token stream
+                self.current_position += 1;
+
+                if cur_char == b'\n' {
+                    if in_string {
+                        // Get the starting position of the string
+                        let string_start: Span = self.get_string_start(&token_stream);
+                        diagnostic::report(
+                            &mut self.diagnostics,
+                            Diagnostic::new(
+                                DiagnosticCode::UnclosedString,
+                                String::from("Unclosed string"),
+                                Some(string_start)
+                            ).with_note("Strings must be closed on the same line they start on")
+                        );
+                        num_errors += 1;
+
+                        // Will finish lexing, so reset in_string
+                        in_string = false;
+                    }
+
+                    // New line should update the line and column numbers
+                    self.line_number += 1;
+                    self.col_number = 1;
+                } else if cur_char & 0xC0 != 0x80 {
+                    // Columns count characters, so the continuation bytes of a character are skipped
+                    self.col_number += 1;
+                }
+                continue;
             }
 
-            // Check if it is a terminal character or in a comment
-            if !in_comment && !cur_char.is_empty() && !terminal_found {
-                // Need to check the substring from current_position
-                // Get the current substring in question
-                let cur_sub: &str = &self.source_code[self.current_position..trailer + 1];
+            // Find the next token and how many bytes it takes up
+            let (token_type, token_len): (TokenType, usize) = self.next_token(&mut in_string);
+
+            // Create the new token and add it to the stream
+            let token_end: usize = self.current_position + token_len;
+            let token_span: Span = Span::new(self.current_position, token_end, self.line_number, self.col_number);
+            let new_token: Token = Token::new(token_type, self.source_code[self.current_position..token_end].to_string(), token_span);
+            token_stream.push(new_token);
+
+            let new_token_ref: &Token = &token_stream[token_stream.len() - 1];
+            match &new_token_ref.token_type {
+                // Log the keyword information
+                TokenType::Keyword(keyword_type) => nexus_log::log(
+                    nexus_log::LogTypes::Debug,
+                    nexus_log::LogSources::Lexer,
+                    format!("Keyword - {:?} [ {} ] found at {:?}", keyword_type, new_token_ref.text, new_token_ref.position())
+                ),
+
+                // Log the identifier information
+                TokenType::Identifier(id) => nexus_log::log(
+                    nexus_log::LogTypes::Debug, 
+                    nexus_log::LogSources::Lexer,
+                    format!("Identifier [ {} ] found at {:?}", id, new_token_ref.position())
+                ),
                 
-                // Check to see if we need to upgrade the token
-                if self.upgrade_token(cur_sub, &mut cur_token_type, &mut in_string) {
-                    // Move the end to the character after the substring ends
-                    best_end = trailer + 1;
-                }
-            } else {
-                // Make sure we have something
-                if best_end - self.current_position > 0 {
-                    // Create the new token and add it to the stream
-                    let new_token: Token = Token::new(cur_token_type.to_owned(), self.source_code[self.current_position..best_end].to_string(), self.line_number, self.col_number);
-                    token_stream.push(new_token);
-
-                    let new_token_ref: &Token = &token_stream[token_stream.len() - 1];
-                    match &new_token_ref.token_type {
-                        // Log the keyword information
-                        TokenType::Keyword(keyword_type) => nexus_log::log(
+                // Log the symbol information
+                TokenType::Symbol(symbol_type) => {
+                    nexus_log::log(
+                        nexus_log::LogTypes::Debug,
+                        nexus_log::LogSources::Lexer,
+                        format!("Symbol - {:?} [ {} ] found at {:?}", symbol_type, new_token_ref.text, new_token_ref.position())
+                    );
+
+                    // Mark the end found if needed
+                    match symbol_type {
+                        Symbols::EOP => end_found = true,
+                        _ => {}
+                    }
+                },
+
+                // Log the digit information
+                TokenType::Digit(num) => {
+                    if new_token_ref.text.parse::<u64>().is_ok() {
+                        nexus_log::log(
                             nexus_log::LogTypes::Debug,
                             nexus_log::LogSources::Lexer,
-                            format!("Keyword - {:?} [ {} ] found at {:?}", keyword_type, new_token_ref.text, new_token_ref.position)
-                        ),
-
-                        // Log the identifier information
-                        TokenType::Identifier(id) => nexus_log::log(
-                            nexus_log::LogTypes::Debug, 
+                            format!("Digit [ {} ] found at {:?}", num, new_token_ref.position())
+                        );
+                    } else {
+                        // Not even the widest target can hold the literal
+                        diagnostic::report(
+                            &mut self.diagnostics,
+                            Diagnostic::new(
+                                DiagnosticCode::IntegerTooLarge,
+                                format!("Integer literal [ {} ] does not fit in 64 bits", new_token_ref.text),
+                                Some(new_token_ref.span)
+                            ).with_note(&format!("The largest integer is {}", u64::MAX))
+                        );
+                        num_errors += 1;
+                    }
+                },
+                
+                // Log the char information
+                TokenType::Char(char) => {
+                    match char.as_str() {
+                        // Make sure space is verbally mentioned in the output and not just a space character
+                        " " => nexus_log::log(
+                            nexus_log::LogTypes::Debug,
                             nexus_log::LogSources::Lexer,
-                            format!("Identifier [ {} ] found at {:?}", id, new_token_ref.position)
+                            format!("Char [ SPACE ] found at {:?}", new_token_ref.position())
                         ),
-                        
-                        // Log the symbol information
-                        TokenType::Symbol(symbol_type) => {
-                            nexus_log::log(
-                                nexus_log::LogTypes::Debug,
-                                nexus_log::LogSources::Lexer,
-                                format!("Symbol - {:?} [ {} ] found at {:?}", symbol_type, new_token_ref.text, new_token_ref.position)
-                            );
-
-                            // Mark the end found if needed
-                            match symbol_type {
-                                Symbols::EOP => end_found = true,
-                                _ => {}
-                            }
-                        },
-
-                        // Log the digit information
-                        TokenType::Digit(num) => nexus_log::log(
+                        _ => nexus_log::log(
                             nexus_log::LogTypes::Debug,
                             nexus_log::LogSources::Lexer,
-                            format!("Digit [ {} ] found at {:?}", num, new_token_ref.position)
-                        ),
-                        
-                        // Log the char information
-                        TokenType::Char(char) => {
-                            match char.as_str() {
-                                // Make sure space is verbally mentioned in the output and not just a space character
-                                " " => nexus_log::log(
-                                    nexus_log::LogTypes::Debug,
-                                    nexus_log::LogSources::Lexer,
-                                    format!("Char [ SPACE ] found at {:?}", new_token_ref.position)
-                                ),
-                                _ => nexus_log::log(
-                                    nexus_log::LogTypes::Debug,
-                                    nexus_log::LogSources::Lexer,
-                                    format!("Char [ {} ] found at {:?}", char, new_token_ref.position)
-                                )
-                            }
-                        },
-
-                        // Unrecognized tokens throw errors
-                        TokenType::Unrecognized(token) => {
-                            if in_string {
-                                // Get the index of the open quote token by doing a backwards linear search
-                                let mut open_quote_pos: i32 = token_stream.len() as i32 - 1;
-                                while open_quote_pos >= 0 {
-                                    match &token_stream[open_quote_pos as usize].token_type {
-                                        // Can break upon finding the token
-                                        TokenType::Symbol(Symbols::Quote) => break,
-                                        _ => open_quote_pos -= 1,
-                                    };
-                                }
-                                match token.as_str() {
-                                    // Make sure the tab gets noticed in the error message
-                                    "\t" => nexus_log::log(
-                                        nexus_log::LogTypes::Error,
-                                        nexus_log::LogSources::Lexer,
-                                        format!("Error at {:?}; Unrecognized token 'TAB' in string starting at {:?}; Strings may only contain lowercase letters (a - z) and spaces", new_token_ref.position, token_stream[open_quote_pos as usize].position)
-                                    ),
-                                    _ => nexus_log::log(
-                                        nexus_log::LogTypes::Error,
-                                        nexus_log::LogSources::Lexer,
-                                        format!("Error at {:?}; Unrecognized token '{}' in string starting at {:?}; Strings may only contain lowercase letters (a - z) and spaces", new_token_ref.position, new_token_ref.text, token_stream[open_quote_pos as usize].position)
-                                    )
-                                }
-                            } else {
-                                nexus_log::log(
-                                    nexus_log::LogTypes::Error,
-                                    nexus_log::LogSources::Lexer,
-                                    format!("Error at {:?}; Unrecognized token '{}'", new_token_ref.position, new_token_ref.text)
-                                )
-                            }
-                            num_errors += 1;
-                        },
+                            format!("Char [ {} ] found at {:?}", char, new_token_ref.position())
+                        )
                     }
-
-                    // Go back to an unrecognized empty token
-                    cur_token_type = TokenType::Unrecognized(String::from(""));
-
-                    // Update the column number to accommodate the length of the token
-                    self.col_number += best_end - self.current_position;
-
-                    // Move the trailer to the best end - 1 (will get incremented at the loop bottom)
-                    trailer = best_end - 1;
-                    // Move current_position to the beginning of the next possible token
-                    self.current_position = trailer + 1;
-                } else {
-                    // Token is empty
-                    self.current_position += 1;
-                    best_end += 1;
-
-                    if cur_char.eq("\n") {
-                        if in_string {
-                            // Get the starting position of the string
-                            let string_start: (usize, usize) = self.get_string_start(&token_stream);
-                            nexus_log::log(
-                                nexus_log::LogTypes::Error,
-                                nexus_log::LogSources::Lexer,
-                                format!("Unclosed string starting at {:?}", string_start)
-                            );
-                            num_errors += 1;
-
-                            // Will finish lexing, so reset in_string
-                            in_string = false;
-                        }
-
-                        // New line should update the line and column numbers
-                        self.line_number += 1;
-                        self.col_number = 1;
+                },
+
+                // Unrecognized tokens throw errors
+                TokenType::Unrecognized(token) => {
+                    if in_string {
+                        let string_start: Span = self.get_string_start(&token_stream);
+                        let msg: String = match token.as_str() {
+                            // Make sure the tab gets noticed in the error message
+                            "\t" => String::from("Unrecognized token 'TAB' in string; Strings may only contain lowercase letters (a - z) and spaces"),
+                            _ => format!("Unrecognized token '{}' in string; Strings may only contain lowercase letters (a - z) and spaces", new_token_ref.text)
+                        };
+                        diagnostic::report(
+                            &mut self.diagnostics,
+                            Diagnostic::new(DiagnosticCode::InvalidStringCharacter, msg, Some(new_token_ref.span))
+                                .with_label(string_start, "String starts here")
+                        );
                     } else {
-                        self.col_number += 1;
+                        diagnostic::report(
+                            &mut self.diagnostics,
+                            Diagnostic::new(
+                                DiagnosticCode::UnrecognizedToken,
+                                format!("Unrecognized token '{}'", new_token_ref.text),
+                                Some(new_token_ref.span)
+                            )
+                        );
                     }
-                }
+                    num_errors += 1;
+                },
             }
 
-            trailer += 1;
+            // Update the column number to accommodate the length of the token
+            self.col_number += self.source_code[self.current_position..token_end].chars().count();
+
+            // Move current_position to the beginning of the next possible token
+            self.current_position = token_end;
         }
 
         // If comment is still open at end of program, the user should be warned
         if in_comment {
-            nexus_log::log(
-                nexus_log::LogTypes::Warning,
-                nexus_log::LogSources::Lexer,
-                format!("Unclosed comment starting at {:?}", comment_position)
+            diagnostic::report(
+                &mut self.diagnostics,
+                Diagnostic::new(
+                    DiagnosticCode::UnclosedComment,
+                    String::from("Unclosed comment"),
+                    Some(comment_span)
+                )
             );
             num_warnings += 1;
         }
@@ -355,11 +337,14 @@ impl Lexer {
         // If string is still open at end of program, an error will be thrown for consistency with the other instance
         if in_string {
             // Get the starting position of the string
-            let string_start: (usize, usize) = self.get_string_start(&token_stream);
-            nexus_log::log(
-                nexus_log::LogTypes::Error,
-                nexus_log::LogSources::Lexer,
-                format!("Unclosed string starting at {:?}", string_start)
+            let string_start: Span = self.get_string_start(&token_stream);
+            diagnostic::report(
+                &mut self.diagnostics,
+                Diagnostic::new(
+                    DiagnosticCode::UnclosedString,
+                    String::from("Unclosed string"),
+                    Some(string_start)
+                )
             );
             num_errors += 1;
         }
@@ -371,20 +356,22 @@ impl Lexer {
                 TokenType::Symbol(Symbols::EOP) => {},
                 // Otherwise log out the warning
                 _ => {
-                    nexus_log::log(
-                        nexus_log::LogTypes::Warning,
-                        nexus_log::LogSources::Lexer,
-                        String::from("Program did not end with EOP symbol [ $ ]")
+                    diagnostic::report(
+                        &mut self.diagnostics,
+                        Diagnostic::new(
+                            DiagnosticCode::MissingEop,
+                            String::from("Program did not end with EOP symbol [ $ ]"),
+                            Some(token_stream[token_stream.len() - 1].span)
+                        )
                     );
                     num_warnings += 1;
                 }
             }
         } else {
             // Empty programs by definition have no tokens and, thus, no EOP token
-            nexus_log::log(
-                nexus_log::LogTypes::Warning,
-                nexus_log::LogSources::Lexer,
-                String::from("Program did not end with EOP symbol [ $ ]")
+            diagnostic::report(
+                &mut self.diagnostics,
+                Diagnostic::new(DiagnosticCode::MissingEop, String::from("Program did not end with EOP symbol [ $ ]"), None)
             );
             num_warnings += 1;
         }
@@ -399,129 +386,101 @@ impl Lexer {
         }
     }
 
-    // Function to upgrade a token based on new information
-    fn upgrade_token(&self, substr: &str, best_token_type: &mut TokenType, in_string: &mut bool) -> bool {
-        // See if we are in a string
+    // Runs the DFA from the current position to find the type and length of the next token
+    // Only keywords, == and != are longer than 1 character, so the longest match is found
+    // by looking at most 7 characters ahead, unless integer literals can have multiple digits
+    fn next_token(&self, in_string: &mut bool) -> (TokenType, usize) {
+        let source: &[u8] = self.source_code.as_bytes();
+        let cur_char: u8 = source[self.current_position];
+        let next_char: Option<u8> = source.get(self.current_position + 1).copied();
+
         if *in_string {
-            // Spaces and characters are valid
-            if self.characters.is_match(substr) || substr.eq(" ") {
-                *best_token_type = TokenType::Char(String::from(substr));
-                return true;
-            } else if substr.eq("\"") {
+            match cur_char {
+                // Spaces and characters are valid
+                b'a'..=b'z' | b' ' => return (TokenType::Char(String::from(cur_char as char)), 1),
                 // " is the end of the string
-                *best_token_type = TokenType::Symbol(Symbols::Quote);
-                *in_string = false;
-                return true;
-            } else if substr.len() == 1 {
+                b'"' => {
+                    *in_string = false;
+                    return (TokenType::Symbol(Symbols::Quote), 1);
+                },
                 // Invalid token
-                *best_token_type = TokenType::Unrecognized(String::from(substr));
-                return true;
-            }
-        } else {
-            if self.keywords.is_match(substr) {
-                // Get the possible keyword matches
-                let keyword_matches: Vec<usize> = self.keywords.matches(substr).into_iter().collect();
-                if keyword_matches.len() > 0 {
-                    // The order here matches the order in which they are defined in the constructor
-                    match keyword_matches[0] {
-                        0 => *best_token_type = TokenType::Keyword(Keywords::If),
-                        1 => *best_token_type = TokenType::Keyword(Keywords::While),
-                        2 => *best_token_type = TokenType::Keyword(Keywords::Print),
-                        3 => *best_token_type = TokenType::Keyword(Keywords::String),
-                        4 => *best_token_type = TokenType::Keyword(Keywords::Int),
-                        5 => *best_token_type = TokenType::Keyword(Keywords::Boolean),
-                        6 => *best_token_type = TokenType::Keyword(Keywords::True),
-                        7 => *best_token_type = TokenType::Keyword(Keywords::False),
-                        // Should never be reached
-                        _ => panic!("Invalid regex found for keywords")
-                    }
-                    return true;
-                }
-            } else if self.characters.is_match(substr) {
-                // Otherwise it may be an identifier, digit, symbol, or unrecognized
-                // We have an identifier
-                *best_token_type = TokenType::Identifier(String::from(substr));
-                return true;
-            } else if self.symbols.is_match(substr) {
-                // Get the possible symbol matches
-                let symbol_matches: Vec<usize> = self.symbols.matches(substr).into_iter().collect();
-                if symbol_matches.len() > 0 {
-                    // The order here matches the order in which they are defined in the constructor
-                    match symbol_matches[0] {
-                        0 => *best_token_type = TokenType::Symbol(Symbols::LParen),
-                        1 => *best_token_type = TokenType::Symbol(Symbols::RParen),
-                        2 => *best_token_type = TokenType::Symbol(Symbols::LBrace),
-                        3 => *best_token_type = TokenType::Symbol(Symbols::RBrace),
-                        4 => *best_token_type = TokenType::Symbol(Symbols::AdditionOp),
-                        5 => *best_token_type = TokenType::Symbol(Symbols::EqOp),
-                        6 => *best_token_type = TokenType::Symbol(Symbols::NeqOp),
-                        7 => *best_token_type = TokenType::Symbol(Symbols::AssignmentOp),
-                        8 => {
-                            *best_token_type = TokenType::Symbol(Symbols::Quote);
-                            *in_string = true;
-                        },
-                        9 => *best_token_type = TokenType::Symbol(Symbols::EOP),
-                        // Should never be reached
-                        _ => panic!("Invalid regex found for symbols")
-                    }
-                    return true;
-                }
-            } else if self.digits.is_match(substr) {
-                // We have a digit
-                *best_token_type = TokenType::Digit(substr.parse::<u8>().unwrap());
-                return true;
-            } else if substr.len() == 1 {
-                // We have an unrecognized symbol
-                *best_token_type = TokenType::Unrecognized(String::from(substr));
-                return true;
+                _ => return self.unrecognized_token()
             }
         }
-        // No upgrade
-        return false;
-    }
-
-    fn check_terminal(&self, current_char: &str, prev_char: &str, in_string: &bool, trailer: &usize) -> bool {
-        // Check to see if there is a match for terminal characters
-        let terminal_match: SetMatches = self.terminal_chars.matches(current_char);
 
-        // Assume we have not found a terminal character
-        let mut out: bool = false;
+        match cur_char {
+            b'a'..=b'z' => {
+                // Follow the keyword DFA as far as it goes and remember the last keyword it accepted
+                let mut cur_state: usize = 0;
+                let mut best_keyword: Option<(Keywords, usize)> = None;
+                let mut i: usize = self.current_position;
+                while i < source.len() && source[i].is_ascii_lowercase() {
+                    let next_state: Option<usize> = self.keyword_dfa[cur_state].transitions[(source[i] - b'a') as usize];
+                    if next_state.is_none() {
+                        break;
+                    }
+                    cur_state = next_state.unwrap();
+                    i += 1;
 
-        // We have found a terminal character
-        if terminal_match.matched_any() {
-            if terminal_match.matched(0) {
-                if current_char.eq(" ") || current_char.eq("\t") {
-                    if !*in_string {
-                        out = true;
+                    if self.keyword_dfa[cur_state].keyword.is_some() {
+                        best_keyword = Some((self.keyword_dfa[cur_state].keyword.to_owned().unwrap(), i - self.current_position));
                     }
+                }
+
+                if best_keyword.is_some() {
+                    let (keyword, len): (Keywords, usize) = best_keyword.unwrap();
+                    return (TokenType::Keyword(keyword), len);
                 } else {
-                    out = true;
+                    // Otherwise it is a single character identifier
+                    return (TokenType::Identifier(String::from(cur_char as char)), 1);
                 }
-            } else if terminal_match.matched(1) {
-                // Equal sign character
-                // Make sure that we have at least 1 other character in consideration
-                // = can be assignment or can become == with the next character
-                if *trailer > self.current_position {
-                    // Narrow the search range by checking if we have characters in front
-                    if *trailer > self.current_position + 1 {
-                        // Treat the = as a terminal (= or == is not important yet)
-                        out = true;
-                    } else if *trailer == self.current_position + 1 {
-                        // If there is exactly 1 character in front
-                        // = is a terminal character only if it is not the second character of an == or != symbol
-                        if prev_char.ne("=") && prev_char.ne("!") {
-                            out = true;
-                        }
-                    }
+            },
+            b'0'..=b'9' => {
+                if !self.multi_digit_integers {
+                    return (TokenType::Digit((cur_char - b'0') as u64), 1);
                 }
-            } else {
-                // These symbols are all terminal if they are not the first character in the checked range
-                if *trailer > self.current_position {
-                    out = true;
+
+                // The literal goes until the last digit in a row, and lex reports the ones that are too large
+                let len: usize = source[self.current_position..].iter().take_while(|c| c.is_ascii_digit()).count();
+                let text: &str = &self.source_code[self.current_position..self.current_position + len];
+                return (TokenType::Digit(text.parse::<u64>().unwrap_or(u64::MAX)), len);
+            },
+            b'(' => return (TokenType::Symbol(Symbols::LParen), 1),
+            b')' => return (TokenType::Symbol(Symbols::RParen), 1),
+            b'{' => return (TokenType::Symbol(Symbols::LBrace), 1),
+            b'}' => return (TokenType::Symbol(Symbols::RBrace), 1),
+            b'+' => return (TokenType::Symbol(Symbols::AdditionOp), 1),
+            b'$' => return (TokenType::Symbol(Symbols::EOP), 1),
+            b'"' => {
+                // " is the start of a string
+                *in_string = true;
+                return (TokenType::Symbol(Symbols::Quote), 1);
+            },
+            // = can be assignment or can become == with the next character
+            b'=' => {
+                if next_char == Some(b'=') {
+                    return (TokenType::Symbol(Symbols::EqOp), 2);
+                } else {
+                    return (TokenType::Symbol(Symbols::AssignmentOp), 1);
                 }
-            }
+            },
+            // ! is only valid as part of !=
+            b'!' => {
+                if next_char == Some(b'=') {
+                    return (TokenType::Symbol(Symbols::NeqOp), 2);
+                } else {
+                    return self.unrecognized_token();
+                }
+            },
+            _ => return self.unrecognized_token()
         }
-        return out;
+    }
+
+    // Creates an unrecognized token for the character at the current position
+    fn unrecognized_token(&self) -> (TokenType, usize) {
+        // Get the whole character in case it is more than 1 byte
+        let cur_char: char = self.source_code[self.current_position..].chars().next().unwrap();
+        return (TokenType::Unrecognized(String::from(cur_char)), cur_char.len_utf8());
     }
 
     // Check to see if we can lex another program
@@ -532,19 +491,12 @@ impl Lexer {
 
     // Function to make sure there is still content to go through
     fn has_content(&self) -> bool {
-        // String only has whitespace
-        let whitespace_regex: Regex = Regex::new(r"^\s*$").unwrap();
-
         // Determine if it is only whitespace or if there is content
-        if whitespace_regex.is_match(&self.source_code[self.current_position..]) {
-            return false;
-        } else {
-            return true;
-        }
+        return !self.source_code[self.current_position..].trim().is_empty();
     }
 
     // Get the starting position
-    fn get_string_start(&self, token_stream: &Vec<Token>) -> (usize, usize) {
+    fn get_string_start(&self, token_stream: &Vec<Token>) -> Span {
         // Get the index of the open quote token by doing a backwards linear search
         let mut i: i32 = token_stream.len() as i32 - 1;
         while i >= 0 {
@@ -557,10 +509,10 @@ impl Lexer {
 
         if i < 0 {
             // Return this in case the quote is not found (should never happen)
-            return (0, 0)
+            return Span::new(0, 0, 0, 0)
         } else {
-            // Return a copy of the string starting position
-            return token_stream[i as usize].position.clone();
+            // Return a copy of the span of the open quote
+            return token_stream[i as usize].span;
         }
     }
 }
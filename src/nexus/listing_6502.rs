@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+// How the operand of an instruction is used
+#[derive (Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Operand {
+    None,
+    // A single byte constant
+    Immediate,
+    // A 2 byte little endian address
+    Absolute,
+    // A single byte branch offset
+    Relative
+}
+
+// Gets the mnemonic and operand for each instruction in the 6502 subset from class and JMP
+pub(crate) fn get_instruction(opcode: u8) -> Option<(&'static str, Operand)> {
+    match opcode {
+        0xA9 => return Some(("LDA", Operand::Immediate)),
+        0xAD => return Some(("LDA", Operand::Absolute)),
+        0x8D => return Some(("STA", Operand::Absolute)),
+        0x8A => return Some(("TXA", Operand::None)),
+        0x98 => return Some(("TYA", Operand::None)),
+        0x6D => return Some(("ADC", Operand::Absolute)),
+        0xA2 => return Some(("LDX", Operand::Immediate)),
+        0xAE => return Some(("LDX", Operand::Absolute)),
+        0xAA => return Some(("TAX", Operand::None)),
+        0xA0 => return Some(("LDY", Operand::Immediate)),
+        0xAC => return Some(("LDY", Operand::Absolute)),
+        0xA8 => return Some(("TAY", Operand::None)),
+        0xEA => return Some(("NOP", Operand::None)),
+        0x00 => return Some(("BRK", Operand::None)),
+        0xEC => return Some(("CPX", Operand::Absolute)),
+        0xD0 => return Some(("BNE", Operand::Relative)),
+        0x4C => return Some(("JMP", Operand::Absolute)),
+        0xEE => return Some(("INC", Operand::Absolute)),
+        0xFF => return Some(("SYS", Operand::None)),
+        _ => return None
+    }
+}
+
+pub(crate) fn get_operand_size(operand: Operand) -> usize {
+    match operand {
+        Operand::None => return 0,
+        Operand::Immediate | Operand::Relative => return 1,
+        Operand::Absolute => return 2
+    }
+}
+
+// Everything the code generator knows about the image that is not in the bytes
+#[derive (Debug, Clone, PartialEq)]
+pub struct ImageSymbols {
+    // Where the code was loaded
+    pub code_start: u16,
+    // The first address after the code
+    pub code_end: u16,
+    // The address and name of each static variable
+    pub variables: Vec<(u16, String)>,
+    // The address of the first temp, where the rest of them are below it
+    pub temp_start: u16,
+    pub num_temps: usize,
+    // The address that always holds 0x00
+    pub zero_address: u16,
+    // The address and text of each string on the heap
+    pub strings: Vec<(u8, String)>,
+    // The address where the code for each statement starts and a description of the statement
+    // Statements without any code start at the same address as the code after them
+    pub statements: Vec<(u16, String)>
+}
+
+impl ImageSymbols {
+    // Names the address if it is a variable, temporary value, or the 0x00 at the end of memory
+    fn get_address_name(&self, address: u16) -> Option<String> {
+        if let Some((_, name)) = self.variables.iter().find(|(var_addr, _)| *var_addr == address) {
+            return Some(name.to_owned());
+        }
+        if address <= self.temp_start && ((self.temp_start - address) as usize) < self.num_temps {
+            return Some(format!("temp_{}", self.temp_start - address));
+        }
+        if address == self.zero_address {
+            return Some(String::from("zero"));
+        }
+        return None;
+    }
+
+    fn get_string(&self, address: u8) -> Option<&String> {
+        return self.strings.iter().find(|(string_addr, _)| *string_addr == address).map(|(_, string)| string);
+    }
+}
+
+// Gets the address a branch goes to, where the offset is signed and wraps around memory like the emulator
+fn get_branch_target(address: usize, offset: u8, memory_size: usize) -> u16 {
+    let memory_size: isize = memory_size.max(0x100) as isize;
+    return (address as isize + 2 + offset as i8 as isize).rem_euclid(memory_size) as u16;
+}
+
+// Disassembles the code in the image and annotates it with the symbols from the code generator
+pub fn create_listing(image: &[u8], symbols: &ImageSymbols) -> String {
+    let code_start: usize = symbols.code_start as usize;
+    let code_end: usize = symbols.code_end as usize;
+
+    // Find every branch and jump target first so they can be labeled in address order
+    let mut branch_targets: Vec<u16> = Vec::new();
+    let mut i: usize = code_start;
+    while i < code_end {
+        let (mnemonic, operand): (&str, Operand) = get_instruction(image[i]).unwrap_or((".byte", Operand::None));
+        if operand == Operand::Relative && i + 1 < image.len() {
+            branch_targets.push(get_branch_target(i, image[i + 1], image.len()));
+        } else if mnemonic == "JMP" && i + 2 < image.len() {
+            branch_targets.push(u16::from_le_bytes([image[i + 1], image[i + 2]]));
+        }
+        i += 1 + get_operand_size(operand);
+    }
+    branch_targets.sort();
+    branch_targets.dedup();
+    let labels: HashMap<u16, String> = branch_targets.iter().enumerate()
+        .map(|(label_num, target)| (*target, format!("L{}", label_num)))
+        .collect();
+
+    let mut listing: String = String::new();
+    listing.push_str(&format!("; Code 0x{:02X}-0x{:02X}\n", code_start, code_end.saturating_sub(1)));
+
+    let mut statement_index: usize = 0;
+    let mut i: usize = code_start;
+    while i < code_end {
+        if let Some(label) = labels.get(&(i as u16)) {
+            listing.push_str(&format!("{}:\n", label));
+        }
+        // Show the statement that produced the code before its first instruction
+        while statement_index < symbols.statements.len() && symbols.statements[statement_index].0 as usize <= i {
+            listing.push_str(&format!("                      ; {}\n", symbols.statements[statement_index].1));
+            statement_index += 1;
+        }
+
+        let (mnemonic, operand): (&str, Operand) = get_instruction(image[i]).unwrap_or((".byte", Operand::None));
+        let size: usize = (1 + get_operand_size(operand)).min(image.len() - i);
+        let bytes: Vec<String> = image[i..i + size].iter().map(|byte| format!("{:02X}", byte)).collect();
+
+        let (operand_str, comment): (String, Option<String>) = match operand {
+            Operand::None if mnemonic == ".byte" => (format!("${:02X}", image[i]), Some(String::from("not an instruction"))),
+            Operand::None => (String::new(), None),
+            Operand::Immediate => {
+                let value: u8 = image[i + 1];
+                // Strings are loaded by their address
+                (format!("#${:02X}", value), symbols.get_string(value).map(|string| format!("\"{}\"", string)))
+            },
+            Operand::Absolute if mnemonic == "JMP" => {
+                let target: u16 = u16::from_le_bytes([image[i + 1], image[i + 2]]);
+                let label: String = labels.get(&target).cloned().unwrap_or(format!("${:04X}", target));
+                (label, Some(format!("${:04X}", target)))
+            },
+            Operand::Absolute => {
+                let address: u16 = u16::from_le_bytes([image[i + 1], image[i + 2]]);
+                (format!("${:04X}", address), symbols.get_address_name(address))
+            },
+            Operand::Relative => {
+                let target: u16 = get_branch_target(i, image[i + 1], image.len());
+                let label: String = labels.get(&target).cloned().unwrap_or(format!("${:02X}", target));
+                (label, Some(format!("${:02X}", target)))
+            }
+        };
+
+        let instruction: String = format!("{} {}", mnemonic, operand_str);
+        match comment {
+            Some(comment) => listing.push_str(&format!("{:04X}  {:<9} {:<11} ; {}\n", i, bytes.join(" "), instruction.trim_end(), comment)),
+            None => listing.push_str(&format!("{:04X}  {:<9} {}\n", i, bytes.join(" "), instruction.trim_end()))
+        }
+        i += size;
+    }
+
+    if !symbols.variables.is_empty() {
+        listing.push_str("\n; Static variables\n");
+        for (address, name) in symbols.variables.iter() {
+            listing.push_str(&format!("{:04X}  {:02X}        {}\n", address, image[*address as usize], name));
+        }
+    }
+
+    // Strings are stored from the end of memory, so show the lowest address first
+    let mut strings: Vec<&(u8, String)> = symbols.strings.iter().collect();
+    strings.sort();
+    listing.push_str("\n; Heap\n");
+    for (address, string) in strings {
+        listing.push_str(&format!("{:04X}  \"{}\"\n", address, string));
+    }
+    return listing;
+}
@@ -0,0 +1,83 @@
+// Where everything goes in the memory image for the backends that generate one, such as the 6502
+// The image always starts at 0x0000, so it is all of the memory that the program can use
+#[derive (Debug, Clone, Copy, PartialEq)]
+pub struct MemoryLayout {
+    // The number of bytes in the image, which can be up to 64 KiB
+    pub image_size: usize,
+
+    // Where the code is loaded and where the program starts running
+    pub load_address: u16,
+
+    // The address of the last byte of the heap, which grows down with the temps below it
+    // Strings are addressed by a single byte, so the heap has to be in the first 256 bytes
+    // The byte after the heap always holds 0x00 for comparisons
+    pub heap_start: u8,
+
+    // Where the static variables start, or None to put them right after the code
+    pub static_address: Option<u16>
+}
+
+impl Default for MemoryLayout {
+    // The 256 bytes from class, where the code starts at 0x00 and the heap starts at 0xFE
+    fn default() -> Self {
+        return MemoryLayout {
+            image_size: 0x100,
+            load_address: 0x0000,
+            heap_start: 0xFE,
+            static_address: None
+        };
+    }
+}
+
+impl MemoryLayout {
+    // The address that always holds 0x00
+    pub fn zero_address(&self) -> u16 {
+        return self.heap_start as u16 + 1;
+    }
+
+    // Makes sure every region starts inside of the image
+    pub fn validate(&self) -> Result<(), String> {
+        if self.image_size == 0 || self.image_size > 0x10000 {
+            return Err(format!("The image size has to be between 1 and 65536 bytes, but it is {}", self.image_size));
+        }
+        if self.load_address as usize >= self.image_size {
+            return Err(format!("The load address 0x{:04X} is past the end of the {} byte image", self.load_address, self.image_size));
+        }
+        if self.zero_address() as usize >= self.image_size {
+            return Err(format!("The heap start 0x{:02X} has to leave room for the 0x00 after it in the {} byte image", self.heap_start, self.image_size));
+        }
+        if let Some(static_address) = self.static_address {
+            if static_address as usize >= self.image_size {
+                return Err(format!("The static address 0x{:04X} is past the end of the {} byte image", static_address, self.image_size));
+            }
+        }
+        return Ok(());
+    }
+
+    // Parses a comma-separated layout such as size=0x1000,load=0x100,heap=0xFE,static=0x800
+    // Anything that is not given keeps its default value
+    pub fn parse(layout: &str) -> Result<Self, String> {
+        let mut memory_layout: MemoryLayout = MemoryLayout::default();
+        for setting in layout.split(',').filter(|setting| !setting.trim().is_empty()) {
+            let (name, value): (&str, &str) = setting.split_once('=').ok_or(format!("expected name=value but got '{}'", setting))?;
+            let value: usize = MemoryLayout::parse_number(value.trim()).ok_or(format!("invalid number '{}' for {}", value.trim(), name.trim()))?;
+            match name.trim() {
+                "size" => memory_layout.image_size = value,
+                "load" => memory_layout.load_address = u16::try_from(value).map_err(|_| format!("the load address 0x{:X} is not a 16 bit address", value))?,
+                "heap" => memory_layout.heap_start = u8::try_from(value).map_err(|_| format!("the heap start 0x{:X} has to be in the first 256 bytes", value))?,
+                "static" => memory_layout.static_address = Some(u16::try_from(value).map_err(|_| format!("the static address 0x{:X} is not a 16 bit address", value))?),
+                name => return Err(format!("unknown memory setting '{}'; expected size, load, heap, or static", name))
+            }
+        }
+        memory_layout.validate()?;
+        return Ok(memory_layout);
+    }
+
+    // Numbers can be in decimal or in hex with 0x in front
+    fn parse_number(number: &str) -> Option<usize> {
+        match number.strip_prefix("0x").or(number.strip_prefix("0X")) {
+            Some(hex) => return usize::from_str_radix(hex, 16).ok(),
+            None => return number.parse::<usize>().ok()
+        }
+    }
+}
@@ -1,10 +1,30 @@
 pub mod compiler;
+pub mod diagnostic;
+pub mod json_output;
 pub mod lexer;
 pub mod token;
 pub mod parser;
+pub mod ast;
+pub mod ast_generator;
+pub mod ast_visitor;
+pub mod ast_folder;
 pub mod semantic_analyzer;
 pub mod symbol_table;
+pub mod ir;
+pub mod dead_block_pruner;
+pub mod ir_generator;
+pub mod constant_folder;
+pub mod dead_code_eliminator;
+pub mod register_allocator;
 pub mod syntax_tree;
 pub mod syntax_tree_node;
+pub mod backend;
+pub mod memory_layout;
 pub mod code_generator_6502;
+pub mod listing_6502;
+pub mod peephole_6502;
 pub mod code_generator_riscv;
+pub mod emulator_6502;
+pub mod emulator_riscv;
+pub mod riscv_assembler;
+pub mod elf_writer;
@@ -1,11 +1,14 @@
 use crate::{nexus::token::{Token, TokenType, Symbols, Keywords}, util::nexus_log};
+use crate::nexus::diagnostic::{self, Diagnostic, DiagnosticCode, PhaseResult};
 
 use crate::nexus::syntax_tree::{SyntaxTree, SyntaxTreeTypes};
 use crate::nexus::syntax_tree_node::{SyntaxTreeNode, NonTerminalsCst, SyntaxTreeNodeTypes};
 
 pub struct Parser {
     cur_token_index: usize,
-    num_warnings: i32
+    num_errors: i32,
+    num_warnings: i32,
+    diagnostics: Vec<Diagnostic>
 }
 
 impl Parser {
@@ -13,11 +16,13 @@ impl Parser {
     pub fn new() -> Self {
         return Parser {
             cur_token_index: 0,
-            num_warnings: 0
+            num_errors: 0,
+            num_warnings: 0,
+            diagnostics: Vec::new()
         };
     }
     // Calls for a program to be parsed
-    pub fn parse_program(&mut self, token_stream: &Vec<Token>) -> Result<SyntaxTree, ()> {
+    pub fn parse_program(&mut self, token_stream: &Vec<Token>) -> PhaseResult<SyntaxTree> {
         // Log that we are parsing the program
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -29,32 +34,25 @@ impl Parser {
         self.cur_token_index = 0;
         let mut cst: SyntaxTree = SyntaxTree::new(SyntaxTreeTypes::Cst);
 
-        let mut success: bool = true;
+        self.num_errors = 0;
         self.num_warnings = 0;
+        self.diagnostics.clear();
 
         // Add the program node
         cst.add_node(SyntaxTreeNodeTypes::Root, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::Program));
 
         // A program consists of a block followed by an EOP marker
         // First will check block and then the token
-        let program_block_res: Result<(), String> = self.parse_block(token_stream, &mut cst);
+        // Errors inside of the block are recovered from in the statement lists, so
+        // anything that makes it here could not be recovered from
+        let program_block_res: Result<(), Diagnostic> = self.parse_block(token_stream, &mut cst);
         if program_block_res.is_ok() {
-            let eop_res: Result<(), String> = self.match_token(token_stream, TokenType::Symbol(Symbols::EOP), &mut cst);
+            let eop_res: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Symbol(Symbols::EOP), &mut cst);
             if eop_res.is_err() {
-                success = false;
-                nexus_log::log(
-                    nexus_log::LogTypes::Error,
-                    nexus_log::LogSources::Parser,
-                    eop_res.unwrap_err()
-                );
+                self.report_error(eop_res.unwrap_err());
             }
         } else {
-            success = false;
-            nexus_log::log(
-                nexus_log::LogTypes::Error,
-                nexus_log::LogSources::Parser,
-                program_block_res.unwrap_err()
-            );
+            self.report_error(program_block_res.unwrap_err());
         }
 
         let mut warnings_str: String = format!("{} warning", self.num_warnings);
@@ -62,15 +60,20 @@ impl Parser {
             warnings_str.push_str("s");
         }
 
-        if !success {
-            // Log that we are parsing the program
+        if self.num_errors > 0 {
+            let mut errors_str: String = format!("{} error", self.num_errors);
+            if self.num_errors != 1 {
+                errors_str.push_str("s");
+            }
+
+            // Log that the parse failed
             nexus_log::log(
                 nexus_log::LogTypes::Error,
                 nexus_log::LogSources::Parser,
-                format!("Parser failed and had {}", warnings_str)
+                format!("Parser failed with {} and {}", errors_str, warnings_str)
             );
             // Parse error
-            return Err(());
+            return PhaseResult::new(None, std::mem::take(&mut self.diagnostics));
         } else {
             nexus_log::log(
                 nexus_log::LogTypes::Info,
@@ -78,11 +81,11 @@ impl Parser {
                 format!("Parser completed successfully with {}", warnings_str)
             );
             // Parsing was successful
-            return Ok(cst);
+            return PhaseResult::new(Some(cst), std::mem::take(&mut self.diagnostics));
         }
     }
 
-    fn parse_block(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_block(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Log that we are parsing a block
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -93,19 +96,19 @@ impl Parser {
         cst.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::Block));
 
         // Check for left brace
-        let lbrace_err: Result<(), String> = self.match_token(token_stream, TokenType::Symbol(Symbols::LBrace), cst);
+        let lbrace_err: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Symbol(Symbols::LBrace), cst);
         if lbrace_err.is_err() {
             // Return the error message if the left brace does not exist
             return lbrace_err;
         }
 
-        let statement_list_res: Result<(), String> = self.parse_statement_list(token_stream, cst);
+        let statement_list_res: Result<(), Diagnostic> = self.parse_statement_list(token_stream, cst);
         if statement_list_res.is_err() {
             return statement_list_res;
         }
 
         // Check for right brace
-        let rbrace_err: Result<(), String> = self.match_token(token_stream, TokenType::Symbol(Symbols::RBrace), cst);
+        let rbrace_err: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Symbol(Symbols::RBrace), cst);
         if rbrace_err.is_err() {
             // Return the error message if the right brace does not exist
             return rbrace_err;
@@ -114,10 +117,13 @@ impl Parser {
             // No need to check for going out of bounds because both left and right brace will already have been consumed
             match &token_stream[self.cur_token_index - 2].token_type {
                 TokenType::Symbol(Symbols::LBrace) => {
-                    nexus_log::log(
-                        nexus_log::LogTypes::Warning,
-                        nexus_log::LogSources::Parser,
-                        format!("Empty block found starting at {:?}", token_stream[self.cur_token_index - 2].position)
+                    diagnostic::report(
+                        &mut self.diagnostics,
+                        Diagnostic::new(
+                            DiagnosticCode::EmptyBlock,
+                            String::from("Empty block found"),
+                            Some(token_stream[self.cur_token_index - 2].span)
+                        )
                     );
                     self.num_warnings += 1;
                 },
@@ -133,7 +139,7 @@ impl Parser {
     }
 
     // Function to ensure the token is correct
-    fn match_token(&mut self, token_stream: &Vec<Token>, expected_token: TokenType, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn match_token(&mut self, token_stream: &Vec<Token>, expected_token: TokenType, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Get the next token
         let cur_token_res: Option<Token> = self.peek_next_token(token_stream);
 
@@ -147,8 +153,8 @@ impl Parser {
                     if cur_token.token_type.ne(&expected_token) {
                         // Return an error message if the expected token does not line up
                         match expected_token {
-                            TokenType::Digit(_) => return Err(format!("Invalid token [ {:?} ] at {:?}; Expected [Digit(0-9)]", cur_token.token_type, cur_token.position)),
-                            _ => return Err(format!("Invalid token [ {:?} ] at {:?}; Expected [{:?}]", cur_token.token_type, cur_token.position, expected_token))
+                            TokenType::Digit(_) => return Err(Diagnostic::new(DiagnosticCode::UnexpectedToken, format!("Invalid token [ {:?} ]; Expected [Digit(0-9)]", cur_token.token_type), Some(cur_token.span))),
+                            _ => return Err(Diagnostic::new(DiagnosticCode::UnexpectedToken, format!("Invalid token [ {:?} ]; Expected [{:?}]", cur_token.token_type, expected_token), Some(cur_token.span)))
                         }
                     } else {
                         // Add the node to the CST
@@ -160,8 +166,8 @@ impl Parser {
                         // Add the node to the cst
                         TokenType::Identifier(_) => cst.add_node(SyntaxTreeNodeTypes::Leaf, SyntaxTreeNode::Terminal(cur_token.to_owned())),
                         // Otherwise return an error
-                        TokenType::Digit(_) => return Err(format!("Invalid token [ {:?} ] at {:?}; Expected [Digit(0-9)]", cur_token.token_type, cur_token.position)),
-                        _ => return Err(format!("Invalid token [ {:?} ] at {:?}; Expected [{:?}]", cur_token.token_type, cur_token.position, expected_token)),
+                        TokenType::Digit(_) => return Err(Diagnostic::new(DiagnosticCode::UnexpectedToken, format!("Invalid token [ {:?} ]; Expected [Digit(0-9)]", cur_token.token_type), Some(cur_token.span))),
+                        _ => return Err(Diagnostic::new(DiagnosticCode::UnexpectedToken, format!("Invalid token [ {:?} ]; Expected [{:?}]", cur_token.token_type, expected_token), Some(cur_token.span))),
                     }
                 },
                 TokenType::Digit(_) => {
@@ -169,7 +175,7 @@ impl Parser {
                         // Add the new node to the cst
                         TokenType::Digit(_) => cst.add_node(SyntaxTreeNodeTypes::Leaf, SyntaxTreeNode::Terminal(cur_token.to_owned())),
                         // Otherwise return an error
-                        _ => return Err(format!("Invalid token [ {:?} ] at {:?}; Expected [{:?}]", cur_token.token_type, cur_token.position, expected_token))
+                        _ => return Err(Diagnostic::new(DiagnosticCode::UnexpectedToken, format!("Invalid token [ {:?} ]; Expected [{:?}]", cur_token.token_type, expected_token), Some(cur_token.span)))
                     }
                 },
                 TokenType::Char(_) => {
@@ -177,8 +183,8 @@ impl Parser {
                         // Add the node to the cst
                         TokenType::Char(_) => cst.add_node(SyntaxTreeNodeTypes::Leaf, SyntaxTreeNode::Terminal(cur_token.to_owned())),
                         // Otherwise return an error
-                        TokenType::Digit(_) => return Err(format!("Invalid token [ {:?} ] at {:?}; Expected [Digit(0-9)]", cur_token.token_type, cur_token.position)),
-                        _ => return Err(format!("Invalid token [ {:?} ] at {:?}; Expected [{:?}]", cur_token.token_type, cur_token.position, expected_token))
+                        TokenType::Digit(_) => return Err(Diagnostic::new(DiagnosticCode::UnexpectedToken, format!("Invalid token [ {:?} ]; Expected [Digit(0-9)]", cur_token.token_type), Some(cur_token.span))),
+                        _ => return Err(Diagnostic::new(DiagnosticCode::UnexpectedToken, format!("Invalid token [ {:?} ]; Expected [{:?}]", cur_token.token_type, expected_token), Some(cur_token.span)))
                     }
                 },
                 TokenType::Keyword(keyword_actual) => {
@@ -187,24 +193,24 @@ impl Parser {
                         TokenType::Keyword(keyword_expected) => {
                             // See if there is a discrepancy is the actual keywords
                             if keyword_actual.ne(&keyword_expected) {
-                                return Err(format!("Invalid token at {:?}; Found {:?}, but expected [{:?}]", cur_token.position, cur_token.token_type, expected_token));
+                                return Err(Diagnostic::new(DiagnosticCode::UnexpectedToken, format!("Invalid token; Found {:?}, but expected [{:?}]", cur_token.token_type, expected_token), Some(cur_token.span)));
                             } else {
                                 // Add the node to the cst
                                 cst.add_node(SyntaxTreeNodeTypes::Leaf, SyntaxTreeNode::Terminal(cur_token.to_owned()));
                             }
                         },
-                        TokenType::Digit(_) => return Err(format!("Invalid token [ {:?} ] at {:?}; Expected [Digit(0-9)]", cur_token.token_type, cur_token.position)),
-                        _ => return Err(format!("Invalid token [ {:?} ] at {:?}; Expected [{:?}]", cur_token.token_type, cur_token.position, expected_token))
+                        TokenType::Digit(_) => return Err(Diagnostic::new(DiagnosticCode::UnexpectedToken, format!("Invalid token [ {:?} ]; Expected [Digit(0-9)]", cur_token.token_type), Some(cur_token.span))),
+                        _ => return Err(Diagnostic::new(DiagnosticCode::UnexpectedToken, format!("Invalid token [ {:?} ]; Expected [{:?}]", cur_token.token_type, expected_token), Some(cur_token.span)))
                     }
                 },
                 _ => {
                     // This should never be reached
-                    return Err(format!("Unrecognized token [ {:?} ] at {:?}", cur_token.text, cur_token.position))
+                    return Err(Diagnostic::new(DiagnosticCode::UnexpectedToken, format!("Unrecognized token [ {:?} ]", cur_token.text), Some(cur_token.span)))
                 }
             }
         } else {
             // Error if no more tokens and expected something
-            return Err(format!("Missing token [{:?}] at end of program", expected_token));
+            return Err(Diagnostic::new(DiagnosticCode::UnexpectedEndOfProgram, format!("Missing token [{:?}] at end of program", expected_token), None));
         }
 
         // Consume the token if it is ok
@@ -212,7 +218,7 @@ impl Parser {
         return Ok(());
     }
 
-    fn match_token_collection(&mut self, token_stream: &Vec<Token>, expected_tokens: Vec<TokenType>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn match_token_collection(&mut self, token_stream: &Vec<Token>, expected_tokens: Vec<TokenType>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Get the next token
         let cur_token_res: Option<Token> = self.peek_next_token(token_stream);
 
@@ -227,17 +233,20 @@ impl Parser {
                 self.cur_token_index += 1;
                 return Ok(());
             } else {
-                return Err(format!("Invalid token [ {:?} ] at {:?}; Expected {:?}", cur_token.token_type, cur_token.position, expected_tokens));
+                return Err(Diagnostic::new(DiagnosticCode::UnexpectedToken, format!("Invalid token [ {:?} ]; Expected {:?}", cur_token.token_type, expected_tokens), Some(cur_token.span)));
             }
         } else {
             // Error if no more tokens and expected something
-            return Err(format!("Missing token {:?} at end of program", expected_tokens));
+            return Err(Diagnostic::new(DiagnosticCode::UnexpectedEndOfProgram, format!("Missing token {:?} at end of program", expected_tokens), None));
         }
     }
 
-    fn parse_statement_list(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_statement_list(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Make sure that the statement list is not empty
-        if !self.peek_and_match_next_token(token_stream, TokenType::Symbol(Symbols::RBrace)) {
+        // The end of the program also ends the list, so the block can report the missing right brace
+        if !self.peek_and_match_next_token(token_stream, TokenType::Symbol(Symbols::RBrace))
+            && !self.peek_and_match_next_token(token_stream, TokenType::Symbol(Symbols::EOP))
+            && self.peek_next_token(token_stream).is_some() {
             // Log that we are parsing a statement list
             nexus_log::log(
                 nexus_log::LogTypes::Debug,
@@ -245,20 +254,27 @@ impl Parser {
                 String::from("Parsing StatementList")
             );
             cst.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::StatementList));
+            let statement_list_node: usize = cst.current_node().unwrap();
+            let statement_start: usize = self.cur_token_index;
+
             // Parse the statement
-            let statement_res: Result<(), String> = self.parse_statement(token_stream, cst);
+            let statement_res: Result<(), Diagnostic> = self.parse_statement(token_stream, cst);
             if statement_res.is_err() {
-                // There was an error so break here
-                return statement_res;
-            } else {
-                // StatementList = Statement StatementList, so call parse on the next statement list
-                let statement_list_res: Result<(), String> = self.parse_statement_list(token_stream, cst);
-                if statement_list_res.is_ok() {
-                    cst.move_up();
-                }
-                return statement_list_res;
+                // Report the error and skip ahead to where the next statement can be parsed
+                self.report_error(statement_res.unwrap_err());
+                self.synchronize(token_stream, statement_start);
+
+                // The CST will not be used, but it has to be back at the statement list to keep going
+                cst.move_to(statement_list_node);
             }
 
+            // StatementList = Statement StatementList, so call parse on the next statement list
+            let statement_list_res: Result<(), Diagnostic> = self.parse_statement_list(token_stream, cst);
+            if statement_list_res.is_ok() {
+                cst.move_up();
+            }
+            return statement_list_res;
+
         } else {
             nexus_log::log(
                 nexus_log::LogTypes::Debug,
@@ -270,7 +286,7 @@ impl Parser {
         }
     }
 
-    fn parse_statement(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_statement(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Log that we are parsing a statement
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -287,7 +303,7 @@ impl Parser {
             let next_token: Token = next_token_peek.unwrap();
 
             // Assign a result object to statement_res based on the next token in the stream
-            let statement_res: Result<(), String> = match next_token.token_type {
+            let statement_res: Result<(), Diagnostic> = match next_token.token_type {
                 // Print statements
                 TokenType::Keyword(Keywords::Print) => self.parse_print_statement(token_stream, cst),
 
@@ -307,7 +323,7 @@ impl Parser {
                 TokenType::Symbol(Symbols::LBrace) => self.parse_block(token_stream, cst),
 
                 // Invalid statement starter tokens
-                _ => Err(format!("Invalid statement token [ {:?} ] at {:?}; Valid statement beginning tokens are {:?}", next_token.token_type, next_token.position, vec![TokenType::Keyword(Keywords::Print), TokenType::Identifier(String::from("a-z")), TokenType::Keyword(Keywords::Int), TokenType::Keyword(Keywords::String), TokenType::Keyword(Keywords::Boolean), TokenType::Keyword(Keywords::While), TokenType::Keyword(Keywords::If), TokenType::Symbol(Symbols::LBrace)]))
+                _ => Err(Diagnostic::new(DiagnosticCode::UnexpectedToken, format!("Invalid statement token [ {:?} ]; Valid statement beginning tokens are {:?}", next_token.token_type, vec![TokenType::Keyword(Keywords::Print), TokenType::Identifier(String::from("a-z")), TokenType::Keyword(Keywords::Int), TokenType::Keyword(Keywords::String), TokenType::Keyword(Keywords::Boolean), TokenType::Keyword(Keywords::While), TokenType::Keyword(Keywords::If), TokenType::Symbol(Symbols::LBrace)]), Some(next_token.span)))
             };
             // We have parsed through the statement and can move up
             if statement_res.is_ok() {
@@ -316,11 +332,11 @@ impl Parser {
             return statement_res;
         } else {
             // Return an error because there is no token for the statement
-            return Err(format!("Missing statement token at end of program; Valid statement beginning tokens are {:?}", vec![TokenType::Keyword(Keywords::Print), TokenType::Identifier(String::from("a-z")), TokenType::Keyword(Keywords::Int), TokenType::Keyword(Keywords::String), TokenType::Keyword(Keywords::Boolean), TokenType::Keyword(Keywords::While), TokenType::Keyword(Keywords::If), TokenType::Symbol(Symbols::LBrace)]));
+            return Err(Diagnostic::new(DiagnosticCode::UnexpectedEndOfProgram, format!("Missing statement token at end of program; Valid statement beginning tokens are {:?}", vec![TokenType::Keyword(Keywords::Print), TokenType::Identifier(String::from("a-z")), TokenType::Keyword(Keywords::Int), TokenType::Keyword(Keywords::String), TokenType::Keyword(Keywords::Boolean), TokenType::Keyword(Keywords::While), TokenType::Keyword(Keywords::If), TokenType::Symbol(Symbols::LBrace)]), None));
         }
     }
 
-    fn parse_print_statement(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_print_statement(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Log that we are parsing a print statement
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -332,13 +348,13 @@ impl Parser {
         cst.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::PrintStatement));
 
         // Check for the print keyword
-        let keyword_res: Result<(), String> = self.match_token(token_stream, TokenType::Keyword(Keywords::Print), cst);
+        let keyword_res: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Keyword(Keywords::Print), cst);
         if keyword_res.is_err() {
             return keyword_res;
         }
 
         // Check for the left paren
-        let lparen_res: Result<(), String> = self.match_token(token_stream, TokenType::Symbol(Symbols::LParen), cst);
+        let lparen_res: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Symbol(Symbols::LParen), cst);
         if lparen_res.is_err() {
             return lparen_res;
         }
@@ -346,14 +362,14 @@ impl Parser {
         // First make sure that we have tokens available for an expression
         if self.peek_next_token(token_stream).is_some() {
             // Check to make sure we have a valid expression to print
-            let expr_res: Result<(), String> = self.parse_expression(token_stream, cst);
+            let expr_res: Result<(), Diagnostic> = self.parse_expression(token_stream, cst);
             if expr_res.is_err() {
                 return expr_res;
             }
         }
 
         // Check for the right paren
-        let rparen_res: Result<(), String> = self.match_token(token_stream, TokenType::Symbol(Symbols::RParen), cst);
+        let rparen_res: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Symbol(Symbols::RParen), cst);
         if rparen_res.is_err() {
             return rparen_res;
         }
@@ -363,7 +379,7 @@ impl Parser {
         return Ok(());
     }
 
-    fn parse_assignment_statement(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_assignment_statement(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Log that we are parsing a print statement
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -375,19 +391,19 @@ impl Parser {
         cst.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::AssignmentStatement));
 
         // Assignment statements begin with an identifier
-        let id_res: Result<(), String> = self.parse_identifier(token_stream, cst);
+        let id_res: Result<(), Diagnostic> = self.parse_identifier(token_stream, cst);
         if id_res.is_err() {
             return id_res;
         }
 
         // Check for a =
-        let assignment_op_res: Result<(), String> = self.match_token(token_stream, TokenType::Symbol(Symbols::AssignmentOp), cst);
+        let assignment_op_res: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Symbol(Symbols::AssignmentOp), cst);
         if assignment_op_res.is_err() {
             return assignment_op_res;
         }
 
         // The right hand side of the statement is an expression
-        let expr_res: Result<(), String> = self.parse_expression(token_stream, cst);
+        let expr_res: Result<(), Diagnostic> = self.parse_expression(token_stream, cst);
         if expr_res.is_err() {
             return expr_res;
         }
@@ -396,7 +412,7 @@ impl Parser {
         return Ok(());
     }
 
-    fn parse_var_declaration(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String>{
+    fn parse_var_declaration(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic>{
         // Log that we are parsing a variable declaration
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -408,13 +424,13 @@ impl Parser {
         cst.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::VarDecl));
 
         // Make sure we have a valid type
-        let type_res: Result<(), String> = self.parse_type(token_stream, cst);
+        let type_res: Result<(), Diagnostic> = self.parse_type(token_stream, cst);
         if type_res.is_err() {
             return type_res;
         }
 
         // Then make sure there is a valid identifier
-        let id_res: Result<(), String> = self.parse_identifier(token_stream, cst);
+        let id_res: Result<(), Diagnostic> = self.parse_identifier(token_stream, cst);
         if id_res.is_err() {
             return id_res;
         }
@@ -423,7 +439,7 @@ impl Parser {
         return Ok(());
     }
 
-    fn parse_while_statement(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_while_statement(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Log that we are parsing a while statement
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -435,19 +451,19 @@ impl Parser {
         cst.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::WhileStatement));
 
         // Make sure we have the while token
-        let while_res: Result<(), String> = self.match_token(token_stream, TokenType::Keyword(Keywords::While), cst);
+        let while_res: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Keyword(Keywords::While), cst);
         if while_res.is_err() {
             return while_res;
         }
 
         // While has a boolean expression
-        let bool_expr_res: Result<(), String> = self.parse_bool_expression(token_stream, cst);
+        let bool_expr_res: Result<(), Diagnostic> = self.parse_bool_expression(token_stream, cst);
         if bool_expr_res.is_err() {
             return bool_expr_res;
         }
 
         // The body of the loop is defined by a block
-        let block_res: Result<(), String> = self.parse_block(token_stream, cst);
+        let block_res: Result<(), Diagnostic> = self.parse_block(token_stream, cst);
         if block_res.is_err() {
             return block_res;
         }
@@ -456,7 +472,7 @@ impl Parser {
         return Ok(());
     }
 
-    fn parse_if_statement(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_if_statement(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Log that we are parsing an if statement
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -468,19 +484,19 @@ impl Parser {
         cst.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::IfStatement));
 
         // Make sure we have the if token
-        let if_res: Result<(), String> = self.match_token(token_stream, TokenType::Keyword(Keywords::If), cst);
+        let if_res: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Keyword(Keywords::If), cst);
         if if_res.is_err() {
             return if_res;
         }
 
         // If has a boolean expression
-        let bool_expr_res: Result<(), String> = self.parse_bool_expression(token_stream, cst);
+        let bool_expr_res: Result<(), Diagnostic> = self.parse_bool_expression(token_stream, cst);
         if bool_expr_res.is_err() {
             return bool_expr_res;
         }
 
         // The body of the if-statement is a block
-        let block_res: Result<(), String> = self.parse_block(token_stream, cst);
+        let block_res: Result<(), Diagnostic> = self.parse_block(token_stream, cst);
         if block_res.is_err() {
             return block_res;
         }
@@ -489,7 +505,7 @@ impl Parser {
         return Ok(());
     }
 
-    fn parse_expression(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_expression(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Log that we are parsing an expression
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -506,7 +522,7 @@ impl Parser {
             let next_token: Token = next_token_peek.unwrap();
 
             // Assign a result object to expression_res based on the next token in the stream
-            let expression_res: Result<(), String> = match next_token.token_type {
+            let expression_res: Result<(), Diagnostic> = match next_token.token_type {
                 // IntExpr
                 TokenType::Digit(_) => self.parse_int_expression(token_stream, cst),
 
@@ -519,7 +535,7 @@ impl Parser {
                 // Id
                 TokenType::Identifier(_) => self.parse_identifier(token_stream, cst),
 
-                _ => Err(format!("Invalid expression token [ {:?} ] at {:?}; Valid expression beginning tokens are [Digit(0-9), {:?}, {:?}, {:?}, {:?}, {:?}]", next_token.token_type, next_token.position, TokenType::Symbol(Symbols::Quote), TokenType::Symbol(Symbols::LParen), TokenType::Keyword(Keywords::False), TokenType::Keyword(Keywords::True), TokenType::Identifier(String::from("a-z")))),
+                _ => Err(Diagnostic::new(DiagnosticCode::UnexpectedToken, format!("Invalid expression token [ {:?} ]; Valid expression beginning tokens are [Digit(0-9), {:?}, {:?}, {:?}, {:?}, {:?}]", next_token.token_type, TokenType::Symbol(Symbols::Quote), TokenType::Symbol(Symbols::LParen), TokenType::Keyword(Keywords::False), TokenType::Keyword(Keywords::True), TokenType::Identifier(String::from("a-z"))), Some(next_token.span))),
             };
     
             if expression_res.is_ok() {
@@ -528,12 +544,12 @@ impl Parser {
             return expression_res;
         } else {
             // There are no more tokens to parse
-            return Err(format!("Missing expression token at end of program; Valid expression beginning tokens are [Digit(0-9), {:?}, {:?}, {:?}, {:?}, {:?}]", TokenType::Symbol(Symbols::Quote), TokenType::Symbol(Symbols::LParen), TokenType::Keyword(Keywords::False), TokenType::Keyword(Keywords::True), TokenType::Identifier(String::from("a-z"))));
+            return Err(Diagnostic::new(DiagnosticCode::UnexpectedEndOfProgram, format!("Missing expression token at end of program; Valid expression beginning tokens are [Digit(0-9), {:?}, {:?}, {:?}, {:?}, {:?}]", TokenType::Symbol(Symbols::Quote), TokenType::Symbol(Symbols::LParen), TokenType::Keyword(Keywords::False), TokenType::Keyword(Keywords::True), TokenType::Identifier(String::from("a-z"))), None));
         }
     }
 
 
-    fn parse_int_expression(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_int_expression(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Log that we are parsing an integer expression
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -545,14 +561,14 @@ impl Parser {
         cst.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::IntExpr));
 
         // Parse the first digit and return error if needed
-        let first_digit_res: Result<(), String> = self.parse_digit(token_stream, cst);
+        let first_digit_res: Result<(), Diagnostic> = self.parse_digit(token_stream, cst);
         if first_digit_res.is_err() {
             return first_digit_res;
         }
 
         // Check the integer operator
         if self.peek_and_match_next_token(token_stream, TokenType::Symbol(Symbols::AdditionOp)) {     
-            let int_op_res: Result<(), String> = self.parse_int_op(token_stream, cst);
+            let int_op_res: Result<(), Diagnostic> = self.parse_int_op(token_stream, cst);
     
             if int_op_res.is_err() {
                 return int_op_res;
@@ -560,7 +576,7 @@ impl Parser {
 
             // Get the second half of the expression if there is an integer operator and return the error if needed
             // Type check does not matter, so can parse 3 + "hello" for now and semantic analysis will catch it
-            let second_half_res: Result<(), String> = self.parse_expression(token_stream, cst);
+            let second_half_res: Result<(), Diagnostic> = self.parse_expression(token_stream, cst);
             if second_half_res.is_err() {
                 return second_half_res;
             }
@@ -570,7 +586,7 @@ impl Parser {
         return Ok(());
     }
 
-    fn parse_string_expression(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_string_expression(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Log that we are parsing a string expression
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -582,19 +598,19 @@ impl Parser {
         cst.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::StringExpr));
 
         // Check for the open quote
-        let open_quote_res: Result<(), String> = self.match_token(token_stream, TokenType::Symbol(Symbols::Quote), cst);
+        let open_quote_res: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Symbol(Symbols::Quote), cst);
         if open_quote_res.is_err() {
             return open_quote_res;
         }
 
         // Parse the string contents
-        let char_list_res: Result<(), String> = self.parse_char_list(token_stream, cst);
+        let char_list_res: Result<(), Diagnostic> = self.parse_char_list(token_stream, cst);
         if char_list_res.is_err() {
             return char_list_res;
         }
 
         // Check for the close quote
-        let close_quote_res: Result<(), String> = self.match_token(token_stream, TokenType::Symbol(Symbols::Quote), cst);
+        let close_quote_res: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Symbol(Symbols::Quote), cst);
         if close_quote_res.is_err() {
             return close_quote_res;
         } else {
@@ -602,10 +618,13 @@ impl Parser {
             // No need to check for going out of bounds because both quotes will already have been consumed
             match &token_stream[self.cur_token_index - 2].token_type {
                 TokenType::Symbol(Symbols::Quote) => {
-                    nexus_log::log(
-                        nexus_log::LogTypes::Warning,
-                        nexus_log::LogSources::Parser,
-                        format!("Empty string found starting at {:?}", token_stream[self.cur_token_index - 2].position)
+                    diagnostic::report(
+                        &mut self.diagnostics,
+                        Diagnostic::new(
+                            DiagnosticCode::EmptyString,
+                            String::from("Empty string found"),
+                            Some(token_stream[self.cur_token_index - 2].span)
+                        )
                     );
                     self.num_warnings += 1;
                 },
@@ -617,7 +636,7 @@ impl Parser {
         return Ok(());
     }
 
-    fn parse_bool_expression(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_bool_expression(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Log that we are parsing a boolean expression
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -632,7 +651,7 @@ impl Parser {
         if next_token_peek.is_some() {
             let next_token: Token = next_token_peek.unwrap();
 
-            let bool_expr_res: Result<(), String> = match next_token.token_type {
+            let bool_expr_res: Result<(), Diagnostic> = match next_token.token_type {
                 // Long boolean expressions start with LParen
                 TokenType::Symbol(Symbols::LParen) => self.long_bool_expression_helper(token_stream, cst),
     
@@ -640,7 +659,7 @@ impl Parser {
                 TokenType::Keyword(Keywords::False) | TokenType::Keyword(Keywords::True) => self.parse_bool_val(token_stream, cst),
     
                 // Invalid boolean expression
-                _ => Err(format!("Invalid boolean expression token [ {:?} ] at {:?}; Valid boolean expression beginning tokens are {:?}", next_token.token_type, next_token.position, vec![TokenType::Symbol(Symbols::LParen), TokenType::Keyword(Keywords::False), TokenType::Keyword(Keywords::True)]))
+                _ => Err(Diagnostic::new(DiagnosticCode::UnexpectedToken, format!("Invalid boolean expression token [ {:?} ]; Valid boolean expression beginning tokens are {:?}", next_token.token_type, vec![TokenType::Symbol(Symbols::LParen), TokenType::Keyword(Keywords::False), TokenType::Keyword(Keywords::True)]), Some(next_token.span)))
             };
     
             if bool_expr_res.is_ok() {
@@ -649,41 +668,41 @@ impl Parser {
             return bool_expr_res;
         } else {
             // There are no more tokens to parse
-            return Err(format!("Missing boolean expression token at end of program; Valid boolean expression beginning tokens are {:?}", vec![TokenType::Symbol(Symbols::LParen), TokenType::Keyword(Keywords::False), TokenType::Keyword(Keywords::True)]));
+            return Err(Diagnostic::new(DiagnosticCode::UnexpectedEndOfProgram, format!("Missing boolean expression token at end of program; Valid boolean expression beginning tokens are {:?}", vec![TokenType::Symbol(Symbols::LParen), TokenType::Keyword(Keywords::False), TokenType::Keyword(Keywords::True)]), None));
         }
     }
 
-    fn long_bool_expression_helper(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
-        let lparen_res: Result<(), String> = self.match_token(token_stream, TokenType::Symbol(Symbols::LParen), cst);
+    fn long_bool_expression_helper(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
+        let lparen_res: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Symbol(Symbols::LParen), cst);
         if lparen_res.is_err() {
             return lparen_res;
         }
 
         // Then move on to the left side of the expression
-        let expr1_res: Result<(), String> = self.parse_expression(token_stream, cst);
+        let expr1_res: Result<(), Diagnostic> = self.parse_expression(token_stream, cst);
         if expr1_res.is_err() {
             return expr1_res;
         }
 
         // Next check for a boolean operator
-        let bool_op_res: Result<(), String> = self.parse_bool_op(token_stream, cst);
+        let bool_op_res: Result<(), Diagnostic> = self.parse_bool_op(token_stream, cst);
         if bool_op_res.is_err() {
             return bool_op_res;
         }
 
         // Next check for the other side of the expression
-        let expr2_res: Result<(), String> = self.parse_expression(token_stream, cst);
+        let expr2_res: Result<(), Diagnostic> = self.parse_expression(token_stream, cst);
         if expr2_res.is_err() {
             return expr2_res;
         }
 
         // Lastly close it with a paren
-        let rparen_res: Result<(), String> = self.match_token(token_stream, TokenType::Symbol(Symbols::RParen), cst);
+        let rparen_res: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Symbol(Symbols::RParen), cst);
         // Return the result regardless of error or ok
         return rparen_res;
     }
 
-    fn parse_identifier(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_identifier(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Log that we are parsing an identifier
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -695,7 +714,7 @@ impl Parser {
         cst.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::Id));
 
         // Match the id
-        let id_res: Result<(), String> = self.match_token(token_stream, TokenType::Identifier(String::from("a-z")), cst);
+        let id_res: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Identifier(String::from("a-z")), cst);
 
         if id_res.is_ok() {
             cst.move_up();
@@ -703,7 +722,7 @@ impl Parser {
         return id_res;
     }
 
-    fn parse_char_list(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_char_list(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Recursion base case
         // We have reached the end of the character list
         if self.peek_and_match_next_token(token_stream, TokenType::Symbol(Symbols::Quote)) {
@@ -725,13 +744,13 @@ impl Parser {
     
             // Add the CharList node
             cst.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::CharList));
-            let char_res: Result<(), String> = self.parse_char(token_stream, cst);
+            let char_res: Result<(), Diagnostic> = self.parse_char(token_stream, cst);
             if char_res.is_err() {
                 // Break from error
                 return char_res;
             } else {
                 // Otherwise continue for the rest of the string
-                let char_list_res: Result<(), String> = self.parse_char_list(token_stream, cst);
+                let char_list_res: Result<(), Diagnostic> = self.parse_char_list(token_stream, cst);
                 if char_list_res.is_ok() {
                     cst.move_up();
                 }
@@ -740,7 +759,7 @@ impl Parser {
         }
     }
 
-    fn parse_type(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_type(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Log that we are parsing a type
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -751,7 +770,7 @@ impl Parser {
         cst.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::Type));
 
         // Try to consume the int token
-        let type_res: Result<(), String> = self.match_token_collection(token_stream, vec![TokenType::Keyword(Keywords::Int), TokenType::Keyword(Keywords::String), TokenType::Keyword(Keywords::Boolean)], cst);
+        let type_res: Result<(), Diagnostic> = self.match_token_collection(token_stream, vec![TokenType::Keyword(Keywords::Int), TokenType::Keyword(Keywords::String), TokenType::Keyword(Keywords::Boolean)], cst);
         
         if type_res.is_ok() {
             cst.move_up();
@@ -760,7 +779,7 @@ impl Parser {
         return type_res;
     }
 
-    fn parse_digit(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_digit(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Log what we are doing
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -772,7 +791,7 @@ impl Parser {
         cst.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::Digit));
 
         // Match the token with a digit
-        let digit_res: Result<(), String> = self.match_token(token_stream, TokenType::Digit(0), cst);
+        let digit_res: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Digit(0), cst);
         if digit_res.is_err() {
             return digit_res;
         } else {
@@ -781,7 +800,7 @@ impl Parser {
         }
     }
 
-    fn parse_char(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_char(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Check for the next character's content to have the correct output (space vs char)
         let cur_token: Option<Token> = self.peek_next_token(token_stream);
         if cur_token.is_some() {
@@ -809,7 +828,7 @@ impl Parser {
         }
 
         // Make sure we have a character token here
-        let char_res: Result<(), String> = self.match_token(token_stream, TokenType::Char(String::from("a-z or space")), cst);
+        let char_res: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Char(String::from("a-z or space")), cst);
 
         if char_res.is_ok() {
             cst.move_up();
@@ -818,7 +837,7 @@ impl Parser {
         return char_res;
     }
 
-    fn parse_bool_op(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_bool_op(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Log that we are parsing a boolean operator
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -829,7 +848,7 @@ impl Parser {
         cst.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::BoolOp));
 
         // Try to consume the token
-        let bool_op_res: Result<(), String> = self.match_token_collection(token_stream, vec![TokenType::Symbol(Symbols::EqOp), TokenType::Symbol(Symbols::NeqOp)], cst);
+        let bool_op_res: Result<(), Diagnostic> = self.match_token_collection(token_stream, vec![TokenType::Symbol(Symbols::EqOp), TokenType::Symbol(Symbols::NeqOp)], cst);
 
         if bool_op_res.is_ok() {
             cst.move_up();
@@ -838,7 +857,7 @@ impl Parser {
         return bool_op_res;
     }
 
-    fn parse_bool_val(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_bool_val(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Log that we are parsing a boolean operator
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -850,7 +869,7 @@ impl Parser {
         cst.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::BoolVal));
 
         // Attempt to consume the token
-        let bool_val_res: Result<(), String> = self.match_token_collection(token_stream, vec![TokenType::Keyword(Keywords::False), TokenType::Keyword(Keywords::True)], cst);
+        let bool_val_res: Result<(), Diagnostic> = self.match_token_collection(token_stream, vec![TokenType::Keyword(Keywords::False), TokenType::Keyword(Keywords::True)], cst);
 
         if bool_val_res.is_ok() {
             // Move up if appropriate to do so
@@ -860,7 +879,7 @@ impl Parser {
         return bool_val_res;
     }
 
-    fn parse_int_op(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), String> {
+    fn parse_int_op(&mut self, token_stream: &Vec<Token>, cst: &mut SyntaxTree) -> Result<(), Diagnostic> {
         // Log that we are parsing an integer operator
         nexus_log::log(
             nexus_log::LogTypes::Debug,
@@ -871,7 +890,7 @@ impl Parser {
         cst.add_node(SyntaxTreeNodeTypes::Branch, SyntaxTreeNode::NonTerminalCst(NonTerminalsCst::IntOp));
 
         // Match the token or get the error
-        let res: Result<(), String> = self.match_token(token_stream, TokenType::Symbol(Symbols::AdditionOp), cst);
+        let res: Result<(), Diagnostic> = self.match_token(token_stream, TokenType::Symbol(Symbols::AdditionOp), cst);
 
         // Move up
         if res.is_ok() {
@@ -881,6 +900,56 @@ impl Parser {
         return res;
     }
 
+    // Reports a syntax error that the parser is recovering from
+    fn report_error(&mut self, error: Diagnostic) {
+        // Once the program runs out of tokens, every enclosing structure is also missing
+        // its closing token, so only the first one is worth reporting
+        if error.code == DiagnosticCode::UnexpectedEndOfProgram && self.diagnostics.iter().any(|diagnostic| diagnostic.code == DiagnosticCode::UnexpectedEndOfProgram) {
+            return;
+        }
+        diagnostic::report(&mut self.diagnostics, error);
+        self.num_errors += 1;
+    }
+
+    // Panic mode recovery that skips tokens until a right brace, the end of the program,
+    // or a keyword that starts a statement
+    fn synchronize(&mut self, token_stream: &Vec<Token>, statement_start: usize) {
+        // Always skip at least 1 token so the same statement cannot fail forever
+        if self.cur_token_index == statement_start {
+            self.cur_token_index += 1;
+        }
+
+        // Blocks that get skipped are skipped entirely so their right brace
+        // does not end the block we are recovering in
+        let mut depth: usize = 0;
+        while self.cur_token_index < token_stream.len() {
+            match &token_stream[self.cur_token_index].token_type {
+                TokenType::Symbol(Symbols::EOP) => break,
+                TokenType::Symbol(Symbols::LBrace) => depth += 1,
+                TokenType::Symbol(Symbols::RBrace) => {
+                    if depth == 0 {
+                        break;
+                    }
+                    depth -= 1;
+                },
+                TokenType::Keyword(Keywords::Print) | TokenType::Keyword(Keywords::While) | TokenType::Keyword(Keywords::If) |
+                TokenType::Keyword(Keywords::Int) | TokenType::Keyword(Keywords::String) | TokenType::Keyword(Keywords::Boolean) => {
+                    if depth == 0 {
+                        break;
+                    }
+                },
+                _ => {}
+            }
+            self.cur_token_index += 1;
+        }
+
+        nexus_log::log(
+            nexus_log::LogTypes::Debug,
+            nexus_log::LogSources::Parser,
+            format!("Recovered from parse error by skipping to token {}", self.cur_token_index)
+        );
+    }
+
     fn peek_next_token(&mut self, token_stream: &Vec<Token>) -> Option<Token> {
         // Make sure we are in-bounds
         if self.cur_token_index < token_stream.len() {
@@ -0,0 +1,303 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::nexus::code_generator_6502::CodeGenBytes;
+use crate::nexus::listing_6502::{self, Operand};
+use crate::util::nexus_log;
+
+// The registers whose values are tracked
+const ACCUMULATOR: usize = 0;
+const X_REGISTER: usize = 1;
+const Y_REGISTER: usize = 2;
+
+// An instruction in the code that is being optimized
+#[derive (Debug, Clone)]
+struct PeepholeInstruction {
+    // The address in the original code, which is None for instructions that were added
+    address: Option<usize>,
+    opcode: u8,
+    bytes: Vec<CodeGenBytes>,
+    // The original address that a BNE with a fixed offset goes to
+    // Branches to labels still have their jump placeholder, so the code generator resolves them
+    local_target: Option<usize>
+}
+
+impl PeepholeInstruction {
+    // Gets what the operand of a load or store refers to
+    fn get_known_value(&self) -> Option<KnownValue> {
+        match self.bytes[1..] {
+            [constant] => return Some(KnownValue::Constant(constant)),
+            [low, high] => return Some(KnownValue::Memory(low, high)),
+            _ => return None
+        }
+    }
+}
+
+// Something a register is known to be equal to
+#[derive (Debug, Clone, Copy, PartialEq)]
+enum KnownValue {
+    // An immediate constant
+    Constant(CodeGenBytes),
+    // The value at the address, which can still be a variable or temp placeholder
+    Memory(CodeGenBytes, CodeGenBytes)
+}
+
+// The code after the peephole optimizations
+#[derive (Debug)]
+pub(crate) struct OptimizedCode {
+    pub code: Vec<CodeGenBytes>,
+    // The new address of each address in the original code, including the address right after the code
+    pub new_addresses: Vec<usize>
+}
+
+// Rewrites the redundant instruction sequences in the 6502 code before the addresses are backpatched
+// The placeholders are kept as they are, so only the addresses in the code generator have to be moved
+#[derive (Debug)]
+pub(crate) struct PeepholeOptimizer {
+    num_bytes_saved: usize
+}
+
+impl PeepholeOptimizer {
+    pub(crate) fn new() -> Self {
+        return PeepholeOptimizer {
+            num_bytes_saved: 0
+        };
+    }
+
+    // Optimizes the code, where the label addresses are the places that branches to labels go to
+    pub(crate) fn optimize(&mut self, code: &[CodeGenBytes], label_addresses: &HashSet<usize>) -> OptimizedCode {
+        self.num_bytes_saved = 0;
+
+        let instructions: Vec<PeepholeInstruction> = match PeepholeOptimizer::decode(code) {
+            Some(instructions) => instructions,
+            None => {
+                nexus_log::log(
+                    nexus_log::LogTypes::Warning,
+                    nexus_log::LogSources::CodeGenerator,
+                    String::from("Skipping peephole optimization because the code could not be decoded")
+                );
+                return OptimizedCode {
+                    code: code.to_vec(),
+                    new_addresses: (0..=code.len()).collect()
+                };
+            }
+        };
+
+        let instructions: Vec<PeepholeInstruction> = self.shorten_bool_strings(instructions, label_addresses);
+        let instructions: Vec<PeepholeInstruction> = self.remove_redundant_loads(instructions, label_addresses);
+        let optimized: OptimizedCode = PeepholeOptimizer::assemble(&instructions, code.len());
+
+        nexus_log::log(
+            nexus_log::LogTypes::Info,
+            nexus_log::LogSources::CodeGenerator,
+            format!("Peephole optimization saved {} bytes", self.num_bytes_saved)
+        );
+
+        return optimized;
+    }
+
+    fn log_rewrite(&mut self, msg: String, address: Option<usize>, num_bytes_saved: usize) {
+        self.num_bytes_saved += num_bytes_saved;
+        let position: String = match address {
+            Some(address) => format!(" at 0x{:02X}", address),
+            None => String::new()
+        };
+        nexus_log::log(
+            nexus_log::LogTypes::Debug,
+            nexus_log::LogSources::CodeGenerator,
+            format!("{}{}", msg, position)
+        );
+    }
+
+    // Splits the code into its instructions, which fails if there is anything that is not an instruction
+    fn decode(code: &[CodeGenBytes]) -> Option<Vec<PeepholeInstruction>> {
+        let mut instructions: Vec<PeepholeInstruction> = Vec::new();
+        let mut address: usize = 0;
+        while address < code.len() {
+            let opcode: u8 = match code[address] {
+                CodeGenBytes::Code(opcode) => opcode,
+                _ => return None
+            };
+            let (_, operand): (&str, Operand) = listing_6502::get_instruction(opcode)?;
+            let size: usize = 1 + listing_6502::get_operand_size(operand);
+            if address + size > code.len() {
+                return None;
+            }
+
+            let bytes: Vec<CodeGenBytes> = code[address..address + size].to_vec();
+            let local_target: Option<usize> = match (operand, bytes.get(1)) {
+                (Operand::Relative, Some(CodeGenBytes::Code(offset))) => Some((address + size).wrapping_add_signed(*offset as i8 as isize)),
+                _ => None
+            };
+            instructions.push(PeepholeInstruction {
+                address: Some(address),
+                opcode,
+                bytes,
+                local_target
+            });
+            address += size;
+        }
+        return Some(instructions);
+    }
+
+    // Gets the original addresses that can be reached from somewhere other than the instruction before it
+    fn get_branch_targets(instructions: &[PeepholeInstruction], label_addresses: &HashSet<usize>) -> HashSet<usize> {
+        let mut targets: HashSet<usize> = label_addresses.to_owned();
+        targets.extend(instructions.iter().filter_map(|instruction| instruction.local_target));
+        return targets;
+    }
+
+    // Replaces the 2 branches that load the true or false string into Y with 1 branch
+    //      BNE +7; LDY true; CPX zero; BNE +2; LDY false
+    // becomes
+    //      LDY false; BNE +2; LDY true
+    // LDY does not change the Z flag, so the branch still uses the value from before
+    fn shorten_bool_strings(&mut self, instructions: Vec<PeepholeInstruction>, label_addresses: &HashSet<usize>) -> Vec<PeepholeInstruction> {
+        let mut optimized: Vec<PeepholeInstruction> = Vec::new();
+        let mut i: usize = 0;
+        while i < instructions.len() {
+            let sequence: &[PeepholeInstruction] = &instructions[i..(i + 5).min(instructions.len())];
+            let is_bool_string: bool = match sequence {
+                [first_branch, load_true, compare, second_branch, load_false] => {
+                    first_branch.bytes == [CodeGenBytes::Code(0xD0), CodeGenBytes::Code(0x07)]
+                        && load_true.opcode == 0xA0
+                        // The only CPX of an address that is already known is the one with the 0x00 byte
+                        && matches!(compare.bytes[..], [CodeGenBytes::Code(0xEC), CodeGenBytes::Code(_), CodeGenBytes::Code(_)])
+                        && second_branch.bytes == [CodeGenBytes::Code(0xD0), CodeGenBytes::Code(0x02)]
+                        && load_false.opcode == 0xA0
+                        // Nothing else can go into the middle of the sequence
+                        && sequence[1..].iter().all(|instruction| !label_addresses.contains(&instruction.address.unwrap()))
+                },
+                _ => false
+            };
+
+            if !is_bool_string {
+                optimized.push(instructions[i].to_owned());
+                i += 1;
+                continue;
+            }
+
+            let load_true: &PeepholeInstruction = &sequence[1];
+            let load_false: &PeepholeInstruction = &sequence[4];
+            let end_address: usize = load_false.address.unwrap() + load_false.bytes.len();
+            optimized.push(PeepholeInstruction {
+                address: sequence[0].address,
+                ..load_false.to_owned()
+            });
+            optimized.push(PeepholeInstruction {
+                address: None,
+                opcode: 0xD0,
+                bytes: vec![CodeGenBytes::Code(0xD0), CodeGenBytes::Code(0x02)],
+                local_target: Some(end_address)
+            });
+            optimized.push(PeepholeInstruction {
+                address: None,
+                ..load_true.to_owned()
+            });
+            self.log_rewrite(String::from("Replaced the branches for the true and false strings with 1 branch"), sequence[0].address, 5);
+            i += 5;
+        }
+        return optimized;
+    }
+
+    // Removes the loads of values that are already in the register, like LDA right after STA to the same address
+    // or LDX #$01 for a second print
+    fn remove_redundant_loads(&mut self, instructions: Vec<PeepholeInstruction>, label_addresses: &HashSet<usize>) -> Vec<PeepholeInstruction> {
+        let targets: HashSet<usize> = PeepholeOptimizer::get_branch_targets(&instructions, label_addresses);
+
+        // Everything that each register is known to be equal to
+        let mut register_values: [Vec<KnownValue>; 3] = [Vec::new(), Vec::new(), Vec::new()];
+        let mut optimized: Vec<PeepholeInstruction> = Vec::new();
+        for instruction in instructions.into_iter() {
+            // Nothing is known when the code can be reached from more than 1 place
+            if instruction.address.is_some_and(|address| targets.contains(&address)) {
+                for values in register_values.iter_mut() {
+                    values.clear();
+                }
+            }
+
+            let known_value: Option<KnownValue> = instruction.get_known_value();
+            match instruction.opcode {
+                // LDA, LDX, and LDY
+                0xA9 | 0xAD | 0xA2 | 0xAE | 0xA0 | 0xAC => {
+                    let register: usize = match instruction.opcode {
+                        0xA9 | 0xAD => ACCUMULATOR,
+                        0xA2 | 0xAE => X_REGISTER,
+                        _ => Y_REGISTER
+                    };
+                    let value: KnownValue = known_value.unwrap();
+                    if register_values[register].contains(&value) {
+                        let msg: String = format!("Removed the load of {:?} because it is already in the register", instruction.bytes);
+                        self.log_rewrite(msg, instruction.address, instruction.bytes.len());
+                        continue;
+                    }
+                    register_values[register] = vec![value];
+                },
+                // STA
+                0x8D => {
+                    let value: KnownValue = known_value.unwrap();
+                    for values in register_values.iter_mut() {
+                        values.retain(|known| *known != value);
+                    }
+                    register_values[ACCUMULATOR].push(value);
+                },
+                // INC
+                0xEE => {
+                    let value: KnownValue = known_value.unwrap();
+                    for values in register_values.iter_mut() {
+                        values.retain(|known| *known != value);
+                    }
+                },
+                // ADC
+                0x6D => register_values[ACCUMULATOR].clear(),
+                // TXA, TYA, TAX, and TAY
+                0x8A => register_values[ACCUMULATOR] = register_values[X_REGISTER].to_owned(),
+                0x98 => register_values[ACCUMULATOR] = register_values[Y_REGISTER].to_owned(),
+                0xAA => register_values[X_REGISTER] = register_values[ACCUMULATOR].to_owned(),
+                0xA8 => register_values[Y_REGISTER] = register_values[ACCUMULATOR].to_owned(),
+                // Branches, comparisons, and sys calls only read the registers
+                _ => {}
+            }
+            optimized.push(instruction);
+        }
+        return optimized;
+    }
+
+    // Puts the instructions back together and moves the fixed branch offsets to their new targets
+    fn assemble(instructions: &[PeepholeInstruction], code_len: usize) -> OptimizedCode {
+        let mut starts: Vec<usize> = Vec::new();
+        let mut code_end: usize = 0;
+        for instruction in instructions.iter() {
+            starts.push(code_end);
+            code_end += instruction.bytes.len();
+        }
+
+        // Removed instructions move to whatever came after them
+        let original_indices: HashMap<usize, usize> = instructions.iter().enumerate()
+            .filter_map(|(i, instruction)| instruction.address.map(|address| (address, i)))
+            .collect();
+        let mut new_addresses: Vec<usize> = vec![code_end; code_len + 1];
+        let mut next_address: usize = code_end;
+        for address in (0..code_len).rev() {
+            if let Some(i) = original_indices.get(&address) {
+                next_address = starts[*i];
+            }
+            new_addresses[address] = next_address;
+        }
+
+        let mut code: Vec<CodeGenBytes> = Vec::new();
+        for (instruction, start) in instructions.iter().zip(starts.iter()) {
+            let mut bytes: Vec<CodeGenBytes> = instruction.bytes.to_owned();
+            if let Some(target) = instruction.local_target {
+                // The offset is from the address after the branch
+                let offset: u8 = (new_addresses[target] as u8).wrapping_sub((*start + bytes.len()) as u8);
+                bytes[1] = CodeGenBytes::Code(offset);
+            }
+            code.append(&mut bytes);
+        }
+
+        return OptimizedCode {
+            code,
+            new_addresses
+        };
+    }
+}
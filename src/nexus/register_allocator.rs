@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::nexus::ir::{IrProgram, Instruction, Variable, Temp, Value};
+use crate::util::nexus_log;
+
+// Anything in the IR that can be given a register
+#[derive (Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Name {
+    // A variable by its name and scope
+    Var(String, usize),
+    Temp(Temp)
+}
+
+impl Name {
+    pub fn from_var(var: &Variable) -> Self {
+        return Name::Var(var.name.to_owned(), var.scope);
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Name::Var(name, scope) => write!(f, "{}_{}", name, scope),
+            Name::Temp(temp) => write!(f, "{}", temp)
+        }
+    }
+}
+
+// The first and last instruction where a name has to keep its value
+#[derive (Debug, Clone, PartialEq)]
+struct LiveInterval {
+    name: Name,
+    start: usize,
+    end: usize
+}
+
+// Where every name ended up, where anything without a register is spilled to memory
+#[derive (Debug, Clone, PartialEq)]
+pub struct Allocation {
+    registers: HashMap<Name, &'static str>,
+    // The names that did not get a register
+    pub spilled: Vec<Name>
+}
+
+impl Allocation {
+    pub fn get_register(&self, name: &Name) -> Option<&'static str> {
+        return self.registers.get(name).copied();
+    }
+}
+
+// Linear scan register allocator over the IR
+// Variables and temps get their own set of registers so the backend can pick registers that survive its calls
+#[derive (Debug)]
+pub struct RegisterAllocator {
+    var_registers: &'static [&'static str],
+    temp_registers: &'static [&'static str]
+}
+
+impl RegisterAllocator {
+    pub fn new(var_registers: &'static [&'static str], temp_registers: &'static [&'static str]) -> Self {
+        return RegisterAllocator {
+            var_registers,
+            temp_registers
+        };
+    }
+
+    // Gives every variable and temp in the program a register or spills it
+    pub fn allocate(&self, ir: &IrProgram) -> Allocation {
+        let intervals: Vec<LiveInterval> = RegisterAllocator::get_live_intervals(ir);
+        let mut allocation: Allocation = Allocation {
+            registers: HashMap::new(),
+            spilled: Vec::new()
+        };
+
+        let (var_intervals, temp_intervals): (Vec<LiveInterval>, Vec<LiveInterval>) = intervals.into_iter()
+            .partition(|interval| matches!(interval.name, Name::Var(..)));
+        RegisterAllocator::linear_scan(var_intervals, self.var_registers, &mut allocation);
+        RegisterAllocator::linear_scan(temp_intervals, self.temp_registers, &mut allocation);
+
+        nexus_log::log(
+            nexus_log::LogTypes::Info,
+            nexus_log::LogSources::CodeGenerator,
+            format!("Register allocation completed with {} in registers and {} spilled", allocation.registers.len(), allocation.spilled.len())
+        );
+
+        return allocation;
+    }
+
+    // Finds the live interval of every name, which are in the order they start
+    fn get_live_intervals(ir: &IrProgram) -> Vec<LiveInterval> {
+        let mut intervals: Vec<LiveInterval> = Vec::new();
+        let mut interval_indices: HashMap<Name, usize> = HashMap::new();
+
+        for (i, instruction) in ir.instructions.iter().enumerate() {
+            let mut names: Vec<Name> = instruction.get_values().iter().filter_map(|value| match value {
+                Value::Var(var) => Some(Name::from_var(var)),
+                Value::Temp(temp) => Some(Name::Temp(*temp)),
+                _ => None
+            }).collect();
+            match instruction {
+                Instruction::Declare(var) | Instruction::Assign { dest: var, .. } => names.push(Name::from_var(var)),
+                Instruction::Add { dest, .. } | Instruction::Compare { dest, .. } => names.push(Name::Temp(*dest)),
+                _ => {}
+            }
+
+            for name in names {
+                match interval_indices.get(&name) {
+                    Some(index) => intervals[*index].end = i,
+                    None => {
+                        interval_indices.insert(name.to_owned(), intervals.len());
+                        intervals.push(LiveInterval { name, start: i, end: i });
+                    }
+                }
+            }
+        }
+
+        // A variable that is live at the start of a loop has to keep its value until the jump back
+        let label_indices: HashMap<usize, usize> = ir.instructions.iter().enumerate()
+            .filter_map(|(i, instruction)| match instruction {
+                Instruction::Label(label) => Some((label.0, i)),
+                _ => None
+            })
+            .collect();
+        let back_edges: Vec<(usize, usize)> = ir.instructions.iter().enumerate()
+            .filter_map(|(i, instruction)| match instruction {
+                Instruction::Jump(label) if label_indices[&label.0] < i => Some((label_indices[&label.0], i)),
+                _ => None
+            })
+            .collect();
+
+        // Nested loops can extend an interval into an outer loop, so keep going until nothing changes
+        let mut changed: bool = true;
+        while changed {
+            changed = false;
+            for interval in intervals.iter_mut() {
+                for (loop_start, loop_end) in back_edges.iter() {
+                    if interval.start < *loop_start && interval.end >= *loop_start && interval.end < *loop_end {
+                        interval.end = *loop_end;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        return intervals;
+    }
+
+    // Gives out the registers in the order the intervals start
+    // When there are not enough, the interval that ends last is spilled because it would block a register the longest
+    fn linear_scan(intervals: Vec<LiveInterval>, registers: &'static [&'static str], allocation: &mut Allocation) {
+        let mut free_registers: Vec<&'static str> = registers.iter().rev().copied().collect();
+        let mut active: Vec<LiveInterval> = Vec::new();
+
+        for interval in intervals.into_iter() {
+            // Free the registers of the intervals that are over, which can be used by
+            // an instruction that reads the old value and writes the new one
+            active.retain(|active_interval| {
+                if active_interval.end <= interval.start {
+                    free_registers.push(allocation.registers[&active_interval.name]);
+                    return false;
+                }
+                return true;
+            });
+
+            if let Some(register) = free_registers.pop() {
+                nexus_log::log(
+                    nexus_log::LogTypes::Debug,
+                    nexus_log::LogSources::CodeGenerator,
+                    format!("Allocated {} to {}", interval.name, register)
+                );
+                allocation.registers.insert(interval.name.to_owned(), register);
+                active.push(interval);
+                continue;
+            }
+
+            // Either the current interval or the active one that ends last is spilled
+            let last_index: Option<usize> = (0..active.len()).max_by_key(|index| active[*index].end);
+            let spilled: Name = match last_index {
+                Some(last_index) if active[last_index].end > interval.end => {
+                    let last: LiveInterval = active.remove(last_index);
+                    let register: &'static str = allocation.registers.remove(&last.name).unwrap();
+                    nexus_log::log(
+                        nexus_log::LogTypes::Debug,
+                        nexus_log::LogSources::CodeGenerator,
+                        format!("Allocated {} to {} from {}", interval.name, register, last.name)
+                    );
+                    allocation.registers.insert(interval.name.to_owned(), register);
+                    active.push(interval);
+                    last.name
+                },
+                _ => interval.name
+            };
+            nexus_log::log(
+                nexus_log::LogTypes::Debug,
+                nexus_log::LogSources::CodeGenerator,
+                format!("Spilled {} to memory", spilled)
+            );
+            allocation.spilled.push(spilled);
+        }
+    }
+}
@@ -0,0 +1,590 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::nexus::elf_writer;
+
+// An error in the assembly, where the line number starts at 1
+#[derive (Debug, Clone, PartialEq)]
+pub struct AssembleError {
+    pub line_number: usize,
+    pub message: String
+}
+
+impl fmt::Display for AssembleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Error on line {}: {}", self.line_number, self.message)
+    }
+}
+
+// The part of a line after its labels
+#[derive (Debug, Clone, PartialEq)]
+pub(crate) enum Statement {
+    // Such as .half 5, where the arguments are kept as a string so .ascii can have commas
+    Directive { name: String, args: String },
+    Instruction { mnemonic: String, operands: Vec<String> }
+}
+
+// A line of assembly split into its labels and statement
+#[derive (Debug, Clone, PartialEq)]
+pub(crate) struct SourceLine {
+    pub line_number: usize,
+    pub labels: Vec<String>,
+    pub statement: Option<Statement>
+}
+
+// Splits the assembly into its labels and statements
+pub(crate) fn parse_lines(assembly: &str) -> Result<Vec<SourceLine>, AssembleError> {
+    let mut lines: Vec<SourceLine> = Vec::new();
+
+    for (i, raw_line) in assembly.lines().enumerate() {
+        let line_number: usize = i + 1;
+        let mut line: &str = strip_comment(raw_line).trim();
+        let mut labels: Vec<String> = Vec::new();
+
+        // Labels can be on the same line as an instruction or directive
+        while let Some(colon) = find_label_end(line) {
+            let label: &str = line[..colon].trim();
+            if label.is_empty() || label.contains(char::is_whitespace) {
+                return Err(AssembleError { line_number, message: format!("Invalid label '{}'", label) });
+            }
+            labels.push(String::from(label));
+            line = line[colon + 1..].trim();
+        }
+
+        let statement: Option<Statement> = if line.is_empty() {
+            None
+        } else {
+            let (name, rest): (&str, &str) = match line.find(char::is_whitespace) {
+                Some(index) => (&line[..index], line[index..].trim()),
+                None => (line, "")
+            };
+            if name.starts_with('.') {
+                Some(Statement::Directive { name: String::from(name), args: String::from(rest) })
+            } else {
+                Some(Statement::Instruction {
+                    mnemonic: name.to_lowercase(),
+                    operands: if rest.is_empty() { Vec::new() } else { rest.split(',').map(|operand| String::from(operand.trim())).collect() }
+                })
+            }
+        };
+
+        lines.push(SourceLine { line_number, labels, statement });
+    }
+
+    return Ok(lines);
+}
+
+// Gets the bytes for a data directive, or None if the directive does not add any data
+pub(crate) fn parse_data(name: &str, args: &str, line_number: usize) -> Result<Option<Vec<u8>>, AssembleError> {
+    match name {
+        ".byte" | ".half" | ".word" | ".dword" => {
+            let num_bytes: usize = match name {
+                ".byte" => 1,
+                ".half" => 2,
+                ".word" => 4,
+                _ => 8
+            };
+            let mut bytes: Vec<u8> = Vec::new();
+            for value_str in args.split(',') {
+                let value: i64 = parse_immediate(value_str.trim())
+                    .ok_or_else(|| AssembleError { line_number, message: format!("Invalid value '{}'", value_str.trim()) })?;
+                bytes.extend_from_slice(&value.to_le_bytes()[..num_bytes]);
+            }
+            return Ok(Some(bytes));
+        },
+        ".ascii" | ".asciz" | ".string" => {
+            let mut bytes: Vec<u8> = parse_string_literal(args)
+                .ok_or_else(|| AssembleError { line_number, message: format!("Invalid string {}", args) })?;
+            if name != ".ascii" {
+                bytes.push(0x00);
+            }
+            return Ok(Some(bytes));
+        },
+        ".section" | ".text" | ".data" | ".global" | ".globl" => return Ok(None),
+        _ => return Err(AssembleError { line_number, message: format!("Unsupported directive {}", name) })
+    }
+}
+
+// Gets the number of bytes for an .align or .p2align directive, which is given as a power of 2
+pub(crate) fn parse_alignment(args: &str, line_number: usize) -> Result<usize, AssembleError> {
+    return parse_immediate(args.trim())
+        .filter(|power| (0..=12).contains(power))
+        .map(|power| 1 << power)
+        .ok_or_else(|| AssembleError { line_number, message: format!("Invalid alignment '{}'", args.trim()) });
+}
+
+// Removes a # comment that is not inside of a string
+fn strip_comment(line: &str) -> &str {
+    let mut in_string: bool = false;
+    let mut prev: char = ' ';
+    for (i, c) in line.char_indices() {
+        if c == '"' && prev != '\\' {
+            in_string = !in_string;
+        } else if c == '#' && !in_string {
+            return &line[..i];
+        }
+        prev = c;
+    }
+    return line;
+}
+
+// Finds the colon at the end of a label at the start of the line, if there is one
+fn find_label_end(line: &str) -> Option<usize> {
+    let colon: usize = line.find(':')?;
+    // A colon in a string is not a label
+    if line[..colon].contains('"') {
+        return None;
+    }
+    return Some(colon);
+}
+
+// Parses decimal, negative, and 0x hex numbers
+// Unsigned 64 bit numbers are allowed too, which are the same bits as a negative number
+pub(crate) fn parse_immediate(value: &str) -> Option<i64> {
+    let (is_negative, digits): (bool, &str) = match value.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, value)
+    };
+    let magnitude: u64 = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+        None => digits.parse::<u64>().ok()?
+    };
+    return Some(if is_negative { (magnitude as i64).wrapping_neg() } else { magnitude as i64 });
+}
+
+// Gets the bytes of a quoted string with the usual escapes
+fn parse_string_literal(literal: &str) -> Option<Vec<u8>> {
+    let inner: &str = literal.strip_prefix('"')?.strip_suffix('"')?;
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next()? {
+                'n' => bytes.push(b'\n'),
+                't' => bytes.push(b'\t'),
+                '0' => bytes.push(0x00),
+                '\\' => bytes.push(b'\\'),
+                '"' => bytes.push(b'"'),
+                _ => return None
+            }
+        } else {
+            let mut buf: [u8; 4] = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+        }
+    }
+    return Some(bytes);
+}
+
+// Gets the number of a register from its x name or ABI name
+pub(crate) fn parse_register(name: &str) -> Result<usize, String> {
+    let abi_names: [&str; 32] = [
+        "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2",
+        "s0", "s1", "a0", "a1", "a2", "a3", "a4", "a5",
+        "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7",
+        "s8", "s9", "s10", "s11", "t3", "t4", "t5", "t6"
+    ];
+
+    if name == "fp" {
+        return Ok(8);
+    }
+    if let Some(index) = abi_names.iter().position(|abi_name| *abi_name == name) {
+        return Ok(index);
+    }
+    if let Some(number) = name.strip_prefix('x').and_then(|number| number.parse::<usize>().ok()) {
+        if number < 32 {
+            return Ok(number);
+        }
+    }
+    return Err(format!("Invalid register '{}'", name));
+}
+
+pub(crate) fn parse_label(name: &str, labels: &HashMap<String, u64>) -> Result<u64, String> {
+    return labels.get(name).copied().ok_or_else(|| format!("Undefined label '{}'", name));
+}
+
+// Splits offset(register) into its parts, or returns None if the operand is a label
+pub(crate) fn parse_offset_operand(operand: &str) -> Result<Option<(i64, usize)>, String> {
+    let open: usize = match operand.find('(') {
+        Some(open) => open,
+        None => return Ok(None)
+    };
+    let register_str: &str = operand[open + 1..].strip_suffix(')').ok_or_else(|| format!("Invalid memory operand '{}'", operand))?;
+    let offset_str: &str = operand[..open].trim();
+    let offset: i64 = if offset_str.is_empty() {
+        0
+    } else {
+        parse_immediate(offset_str).ok_or_else(|| format!("Invalid offset '{}'", offset_str))?
+    };
+    return Ok(Some((offset, parse_register(register_str.trim())?)));
+}
+
+// The number of operands for every supported instruction, which is also the list of what is supported
+pub(crate) fn get_num_operands(mnemonic: &str) -> Result<usize, String> {
+    match mnemonic {
+        "nop" | "ret" | "ecall" => return Ok(0),
+        "j" | "call" => return Ok(1),
+        "li" | "la" | "mv" | "seqz" | "snez" | "beqz" | "bnez" => return Ok(2),
+        "lb" | "lbu" | "lh" | "lhu" | "lw" | "lwu" | "ld" | "sb" | "sh" | "sw" | "sd" => return Ok(2),
+        "add" | "sub" | "xor" | "sltu" | "mul" | "divu" | "remu" | "addi" | "andi" | "sltiu" => return Ok(3),
+        "beq" | "bne" | "blt" | "bge" | "bltu" | "bgeu" => return Ok(3),
+        _ => return Err(format!("Unsupported instruction {}", mnemonic))
+    }
+}
+
+// The machine code for a program that is ready to be written to an executable
+#[derive (Debug, Clone, PartialEq)]
+pub struct AssembledProgram {
+    pub text: Vec<u8>,
+    pub data: Vec<u8>,
+    pub text_address: u64,
+    pub data_address: u64,
+    pub entry: u64
+}
+
+#[derive (Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Text,
+    Data
+}
+
+// An instruction that is encoded once every label has an address
+struct PendingInstruction {
+    line_number: usize,
+    offset: usize,
+    mnemonic: String,
+    operands: Vec<String>
+}
+
+// Assembles the program into machine code for the RV64IM base
+// Everything after .section .data goes in the data section and everything else is in the text section
+pub fn assemble(assembly: &str) -> Result<AssembledProgram, AssembleError> {
+    let mut text: Vec<u8> = Vec::new();
+    let mut data: Vec<u8> = Vec::new();
+    let mut section: Section = Section::Text;
+    let mut section_labels: Vec<(String, Section, usize, usize)> = Vec::new();
+    let mut pending: Vec<PendingInstruction> = Vec::new();
+    let mut entry_label: String = String::from("_start");
+
+    // First pass gets the size of everything so the labels have offsets in their section
+    for line in parse_lines(assembly)?.into_iter() {
+        let cur_offset: usize = if section == Section::Text { text.len() } else { data.len() };
+        for label in line.labels.iter() {
+            if section_labels.iter().any(|(name, ..)| name == label) {
+                return Err(AssembleError { line_number: line.line_number, message: format!("Label '{}' is defined more than once", label) });
+            }
+            section_labels.push((label.to_owned(), section, cur_offset, line.line_number));
+        }
+
+        match line.statement {
+            None => {},
+            Some(Statement::Directive { name, args }) => {
+                match name.as_str() {
+                    ".text" => section = Section::Text,
+                    ".data" => section = Section::Data,
+                    ".section" => {
+                        section = match args.split(',').next().unwrap_or("").trim() {
+                            ".text" => Section::Text,
+                            ".data" | ".bss" | ".rodata" => Section::Data,
+                            other => return Err(AssembleError { line_number: line.line_number, message: format!("Unsupported section {}", other) })
+                        };
+                    },
+                    ".global" | ".globl" => entry_label = args,
+                    ".align" | ".p2align" => {
+                        // The data starts on an 8 byte boundary, so padding the offset aligns the address too
+                        if section == Section::Text {
+                            return Err(AssembleError { line_number: line.line_number, message: format!("{} is only supported in the data section", name) });
+                        }
+                        let alignment: usize = parse_alignment(&args, line.line_number)?;
+                        if alignment > 8 {
+                            return Err(AssembleError { line_number: line.line_number, message: format!("The data can only be aligned to 8 bytes, but {} was given", alignment) });
+                        }
+                        data.resize(data.len().next_multiple_of(alignment), 0x00);
+                    },
+                    _ => {
+                        let mut bytes: Vec<u8> = parse_data(&name, &args, line.line_number)?.unwrap_or_default();
+                        if section == Section::Text { text.append(&mut bytes) } else { data.append(&mut bytes) }
+                    }
+                }
+            },
+            Some(Statement::Instruction { mnemonic, operands }) => {
+                if section != Section::Text {
+                    return Err(AssembleError { line_number: line.line_number, message: format!("Instruction {} is outside of the text section", mnemonic) });
+                }
+                let num_operands: usize = get_num_operands(&mnemonic)
+                    .map_err(|message| AssembleError { line_number: line.line_number, message })?;
+                if operands.len() != num_operands {
+                    return Err(AssembleError {
+                        line_number: line.line_number,
+                        message: format!("Expected {} operands for {}, but received {}", num_operands, mnemonic, operands.len())
+                    });
+                }
+                let size: usize = get_instruction_size(&mnemonic, &operands)
+                    .map_err(|message| AssembleError { line_number: line.line_number, message })?;
+
+                pending.push(PendingInstruction { line_number: line.line_number, offset: text.len(), mnemonic, operands });
+                // Filled in by the second pass
+                text.resize(text.len() + size, 0x00);
+            }
+        }
+    }
+
+    // Now that the size of the text is known, everything can be given its address
+    let (text_address, data_address): (u64, u64) = elf_writer::get_layout(text.len());
+    let labels: HashMap<String, u64> = section_labels.into_iter()
+        .map(|(name, label_section, offset, _)| {
+            let base: u64 = if label_section == Section::Text { text_address } else { data_address };
+            return (name, base + offset as u64);
+        })
+        .collect();
+
+    // Second pass encodes the instructions
+    for instruction in pending.iter() {
+        let address: u64 = text_address + instruction.offset as u64;
+        let words: Vec<u32> = encode_instruction(&instruction.mnemonic, &instruction.operands, address, &labels)
+            .map_err(|message| AssembleError { line_number: instruction.line_number, message })?;
+        for (i, word) in words.iter().enumerate() {
+            let start: usize = instruction.offset + i * 4;
+            text[start..start + 4].copy_from_slice(&word.to_le_bytes());
+        }
+    }
+
+    let entry: u64 = *labels.get(&entry_label)
+        .ok_or_else(|| AssembleError { line_number: 0, message: format!("Entry point {} is never defined", entry_label) })?;
+
+    return Ok(AssembledProgram { text, data, text_address, data_address, entry });
+}
+
+// Assembles the program and writes it to a statically linked ELF executable
+pub fn create_executable(assembly: &str) -> Result<Vec<u8>, AssembleError> {
+    let program: AssembledProgram = assemble(assembly)?;
+    return Ok(elf_writer::create_riscv_executable(&program));
+}
+
+fn fits_signed(value: i64, bits: u32) -> bool {
+    let limit: i64 = 1 << (bits - 1);
+    return value >= -limit && value < limit;
+}
+
+// Pseudo instructions that need an address or large constant take more than 1 instruction
+fn get_instruction_size(mnemonic: &str, operands: &[String]) -> Result<usize, String> {
+    match mnemonic {
+        "la" | "call" => return Ok(8),
+        "li" => {
+            let value: i64 = parse_immediate(&operands[1]).ok_or_else(|| format!("Invalid immediate '{}'", operands[1]))?;
+            return Ok(encode_load_immediate(0, value)?.len() * 4);
+        },
+        "lb" | "lbu" | "lh" | "lhu" | "lw" | "lwu" | "ld" => {
+            // Loading from a label needs the address first
+            if parse_offset_operand(&operands[1])?.is_none() {
+                return Ok(8);
+            }
+            return Ok(4);
+        },
+        "sb" | "sh" | "sw" | "sd" => {
+            if parse_offset_operand(&operands[1])?.is_none() {
+                return Err(format!("Storing to a label needs offset(register) for {}", mnemonic));
+            }
+            return Ok(4);
+        },
+        _ => return Ok(4)
+    }
+}
+
+fn encode_r_type(opcode: u32, rd: usize, funct3: u32, rs1: usize, rs2: usize, funct7: u32) -> u32 {
+    return (funct7 << 25) | ((rs2 as u32) << 20) | ((rs1 as u32) << 15) | (funct3 << 12) | ((rd as u32) << 7) | opcode;
+}
+
+fn encode_i_type(opcode: u32, rd: usize, funct3: u32, rs1: usize, imm: i64) -> Result<u32, String> {
+    if !fits_signed(imm, 12) {
+        return Err(format!("Immediate {} does not fit in 12 bits", imm));
+    }
+    return Ok((((imm as u32) & 0xFFF) << 20) | ((rs1 as u32) << 15) | (funct3 << 12) | ((rd as u32) << 7) | opcode);
+}
+
+fn encode_s_type(opcode: u32, funct3: u32, rs1: usize, rs2: usize, imm: i64) -> Result<u32, String> {
+    if !fits_signed(imm, 12) {
+        return Err(format!("Offset {} does not fit in 12 bits", imm));
+    }
+    let imm: u32 = (imm as u32) & 0xFFF;
+    return Ok(((imm >> 5) << 25) | ((rs2 as u32) << 20) | ((rs1 as u32) << 15) | (funct3 << 12) | ((imm & 0x1F) << 7) | opcode);
+}
+
+fn encode_b_type(funct3: u32, rs1: usize, rs2: usize, offset: i64) -> Result<u32, String> {
+    if !fits_signed(offset, 13) {
+        return Err(format!("Branch target is {} bytes away, which is too far for a branch", offset));
+    }
+    let imm: u32 = (offset as u32) & 0x1FFF;
+    return Ok((((imm >> 12) & 0x1) << 31) | (((imm >> 5) & 0x3F) << 25) | ((rs2 as u32) << 20) | ((rs1 as u32) << 15)
+        | (funct3 << 12) | (((imm >> 1) & 0xF) << 8) | (((imm >> 11) & 0x1) << 7) | 0x63);
+}
+
+fn encode_u_type(opcode: u32, rd: usize, upper: i64) -> u32 {
+    return (((upper as u32) & 0xFFFFF) << 12) | ((rd as u32) << 7) | opcode;
+}
+
+fn encode_j_type(rd: usize, offset: i64) -> Result<u32, String> {
+    if !fits_signed(offset, 21) {
+        return Err(format!("Jump target is {} bytes away, which is too far for a jump", offset));
+    }
+    let imm: u32 = (offset as u32) & 0x1FFFFF;
+    return Ok((((imm >> 20) & 0x1) << 31) | (((imm >> 1) & 0x3FF) << 21) | (((imm >> 11) & 0x1) << 20)
+        | (((imm >> 12) & 0xFF) << 12) | ((rd as u32) << 7) | 0x6F);
+}
+
+// Splits a value into the upper 20 bits and lower 12 bits for auipc/lui and the instruction after it
+// The lower part is sign extended, so the upper part rounds up to make up for it
+fn split_upper_lower(value: i64) -> (i64, i64) {
+    let upper: i64 = (value + 0x800) >> 12;
+    let lower: i64 = value - (upper << 12);
+    return (upper, lower);
+}
+
+// Encodes li, which can take up to 8 instructions for a full 64 bit value
+fn encode_load_immediate(rd: usize, value: i64) -> Result<Vec<u32>, String> {
+    if fits_signed(value, 12) {
+        return Ok(vec![encode_i_type(0x13, rd, 0, 0, value)?]);
+    }
+    if fits_signed(value, 32) {
+        // lui and addiw work on 32 bits, so this is right for anything that fits in 32 bits
+        let (upper, lower): (i64, i64) = split_upper_lower(value);
+        if lower == 0 {
+            return Ok(vec![encode_u_type(0x37, rd, upper)]);
+        }
+        return Ok(vec![encode_u_type(0x37, rd, upper), encode_i_type(0x1B, rd, 0, rd, lower)?]);
+    }
+
+    // Bigger values load the upper bits without their trailing zeros, shift them into place with slli,
+    // and then add the sign extended lower 12 bits
+    let lower: i64 = (value << 52) >> 52;
+    let upper: u64 = (value as u64).wrapping_add(0x800) >> 12;
+    let shift: u32 = 12 + upper.trailing_zeros();
+    let upper: i64 = (((upper >> (shift - 12)) << shift) as i64) >> shift;
+
+    // Shifting 12 bits less lets the upper bits be loaded with only lui
+    let (upper, shift): (i64, u32) = if shift > 12 && !fits_signed(upper, 12) && fits_signed(upper, 20) {
+        (upper << 12, shift - 12)
+    } else {
+        (upper, shift)
+    };
+
+    let mut instructions: Vec<u32> = encode_load_immediate(rd, upper)?;
+    instructions.push(encode_i_type(0x13, rd, 1, rd, shift as i64)?);
+    if lower != 0 {
+        instructions.push(encode_i_type(0x13, rd, 0, rd, lower)?);
+    }
+    return Ok(instructions);
+}
+
+// Gets auipc and the instruction after it for a pc-relative address
+fn encode_pc_relative(rd: usize, target: u64, address: u64, second: impl Fn(i64) -> Result<u32, String>) -> Result<Vec<u32>, String> {
+    let offset: i64 = target as i64 - address as i64;
+    if !fits_signed(offset, 32) {
+        return Err(format!("Target is {} bytes away, which is too far for auipc", offset));
+    }
+    let (upper, lower): (i64, i64) = split_upper_lower(offset);
+    return Ok(vec![encode_u_type(0x17, rd, upper), second(lower)?]);
+}
+
+fn get_load_funct3(mnemonic: &str) -> u32 {
+    match mnemonic {
+        "lb" => return 0,
+        "lh" => return 1,
+        "lw" => return 2,
+        "ld" => return 3,
+        "lbu" => return 4,
+        "lhu" => return 5,
+        _ => return 6
+    }
+}
+
+// Encodes the instruction at the address into 1 or 2 words
+fn encode_instruction(mnemonic: &str, operands: &[String], address: u64, labels: &HashMap<String, u64>) -> Result<Vec<u32>, String> {
+    let branch_offset = |label: &str| -> Result<i64, String> {
+        return Ok(parse_label(label, labels)? as i64 - address as i64);
+    };
+
+    match mnemonic {
+        "nop" => return Ok(vec![encode_i_type(0x13, 0, 0, 0, 0)?]),
+        "ecall" => return Ok(vec![0x00000073]),
+        "ret" => return Ok(vec![encode_i_type(0x67, 0, 0, 1, 0)?]),
+        "j" => return Ok(vec![encode_j_type(0, branch_offset(&operands[0])?)?]),
+        "call" => {
+            let target: u64 = parse_label(&operands[0], labels)?;
+            return encode_pc_relative(1, target, address, |lower| encode_i_type(0x67, 1, 0, 1, lower));
+        },
+        "li" => {
+            let rd: usize = parse_register(&operands[0])?;
+            let value: i64 = parse_immediate(&operands[1]).ok_or_else(|| format!("Invalid immediate '{}'", operands[1]))?;
+            return encode_load_immediate(rd, value);
+        },
+        "la" => {
+            let rd: usize = parse_register(&operands[0])?;
+            let target: u64 = parse_label(&operands[1], labels)?;
+            return encode_pc_relative(rd, target, address, |lower| encode_i_type(0x13, rd, 0, rd, lower));
+        },
+        "mv" => return Ok(vec![encode_i_type(0x13, parse_register(&operands[0])?, 0, parse_register(&operands[1])?, 0)?]),
+        "addi" | "andi" | "sltiu" => {
+            let funct3: u32 = match mnemonic {
+                "addi" => 0,
+                "sltiu" => 3,
+                _ => 7
+            };
+            let imm: i64 = parse_immediate(&operands[2]).ok_or_else(|| format!("Invalid immediate '{}'", operands[2]))?;
+            return Ok(vec![encode_i_type(0x13, parse_register(&operands[0])?, funct3, parse_register(&operands[1])?, imm)?]);
+        },
+        // seqz is sltiu rd, rs, 1
+        "seqz" => return Ok(vec![encode_i_type(0x13, parse_register(&operands[0])?, 3, parse_register(&operands[1])?, 1)?]),
+        // snez is sltu rd, zero, rs
+        "snez" => return Ok(vec![encode_r_type(0x33, parse_register(&operands[0])?, 3, 0, parse_register(&operands[1])?, 0x00)]),
+        "add" | "sub" | "xor" | "sltu" | "mul" | "divu" | "remu" => {
+            let (funct3, funct7): (u32, u32) = match mnemonic {
+                "add" => (0, 0x00),
+                "sub" => (0, 0x20),
+                "xor" => (4, 0x00),
+                "sltu" => (3, 0x00),
+                "mul" => (0, 0x01),
+                "divu" => (5, 0x01),
+                _ => (7, 0x01)
+            };
+            return Ok(vec![encode_r_type(0x33, parse_register(&operands[0])?, funct3, parse_register(&operands[1])?, parse_register(&operands[2])?, funct7)]);
+        },
+        "lb" | "lbu" | "lh" | "lhu" | "lw" | "lwu" | "ld" => {
+            let rd: usize = parse_register(&operands[0])?;
+            let funct3: u32 = get_load_funct3(mnemonic);
+            match parse_offset_operand(&operands[1])? {
+                Some((offset, rs1)) => return Ok(vec![encode_i_type(0x03, rd, funct3, rs1, offset)?]),
+                // The destination holds the address until the load replaces it
+                None => {
+                    let target: u64 = parse_label(&operands[1], labels)?;
+                    return encode_pc_relative(rd, target, address, |lower| encode_i_type(0x03, rd, funct3, rd, lower));
+                }
+            }
+        },
+        "sb" | "sh" | "sw" | "sd" => {
+            let funct3: u32 = match mnemonic {
+                "sb" => 0,
+                "sh" => 1,
+                "sw" => 2,
+                _ => 3
+            };
+            let (offset, rs1): (i64, usize) = parse_offset_operand(&operands[1])?
+                .ok_or_else(|| format!("Storing to a label needs offset(register) for {}", mnemonic))?;
+            return Ok(vec![encode_s_type(0x23, funct3, rs1, parse_register(&operands[0])?, offset)?]);
+        },
+        "beqz" | "bnez" => {
+            let funct3: u32 = if mnemonic == "beqz" { 0 } else { 1 };
+            return Ok(vec![encode_b_type(funct3, parse_register(&operands[0])?, 0, branch_offset(&operands[1])?)?]);
+        },
+        _ => {
+            let funct3: u32 = match mnemonic {
+                "beq" => 0,
+                "bne" => 1,
+                "blt" => 4,
+                "bge" => 5,
+                "bltu" => 6,
+                _ => 7
+            };
+            return Ok(vec![encode_b_type(funct3, parse_register(&operands[0])?, parse_register(&operands[1])?, branch_offset(&operands[2])?)?]);
+        }
+    }
+}
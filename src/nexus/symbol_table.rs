@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use petgraph::graph::{NodeIndex, Graph};
 
-use crate::util::nexus_log;
+use crate::nexus::diagnostic::{Diagnostic, DiagnosticCode, Span};
 
 use web_sys::{Window, Document, Element, DomTokenList};
 
@@ -26,7 +26,7 @@ pub enum SymbolTableEntryField {
 #[derive (Debug)]
 pub struct SymbolTableEntry {
     pub symbol_type: Type,
-    pub position: (usize, usize),
+    pub span: Span,
     pub scope: usize,
     pub is_initialized: bool,
     pub is_used: bool
@@ -88,7 +88,7 @@ impl SymbolTable {
     }
 
     // Adds an identifier to the current scope and returns if it was successful
-    pub fn new_identifier(&mut self, id: String, id_type: Type, id_position: (usize, usize)) -> bool {
+    pub fn new_identifier(&mut self, id: String, id_type: Type, id_span: Span) -> bool {
         // Get the current scope's hash table
         let scope_table: &mut HashMap<String, SymbolTableEntry> = self.graph.node_weight_mut(NodeIndex::new(self.cur_scope.unwrap())).unwrap();
         if (*scope_table).contains_key(&id) {
@@ -98,7 +98,7 @@ impl SymbolTable {
             // Add the id and its respective information to the hash table
             let new_entry = SymbolTableEntry {
                 symbol_type: id_type,
-                position: id_position,
+                span: id_span,
                 scope: self.cur_scope.unwrap(),
                 is_initialized: false,
                 is_used: false
@@ -140,7 +140,7 @@ impl SymbolTable {
     // Returns a reference to the appropriate symbol table entry
     // based on the current scope and position in the code
     // for code generation after the symbol table is already fully populated
-    pub fn get_symbol_with_context(&mut self, id: &str, cur_position: (usize, usize)) -> Option<&SymbolTableEntry> {
+    pub fn get_symbol_with_context(&mut self, id: &str, cur_span: Span) -> Option<&SymbolTableEntry> {
         // Start with the current scope
         let mut cur_scope_check: usize = self.cur_scope.unwrap();
       
@@ -151,7 +151,7 @@ impl SymbolTable {
 
             // We have to make sure that the entry being received was declared before the current position
             let entry: Option<&SymbolTableEntry> = (*scope_table).get(id);
-            if entry.is_some() && self.is_in_context(entry.unwrap().position, cur_position) {
+            if entry.is_some() && self.is_in_context(entry.unwrap().span, cur_span) {
                 return entry;
             } else {
                 if cur_scope_check == 0 {
@@ -169,17 +169,9 @@ impl SymbolTable {
         }
     }
 
-    fn is_in_context(&self, symbol_position: (usize, usize), cur_position: (usize, usize), ) -> bool {
-        if symbol_position.0 < cur_position.0 {
-            // Symbol declared first, so it is valid
-            return true;
-        } else if symbol_position.0 > cur_position.0 {
-            // Symbol declared second, so is not valid
-            return false;
-        } else {
-            // Position in the row makes the difference if in the same row
-            return symbol_position.1 <= cur_position.1;
-        }
+    fn is_in_context(&self, symbol_span: Span, cur_span: Span) -> bool {
+        // The symbol is only valid if it was declared first
+        return symbol_span.start <= cur_span.start;
     }
 
     // Function to set a variable to be initialized
@@ -214,45 +206,42 @@ impl SymbolTable {
     }
 
     // Function to find all of the warnings after scope and type checks are completed
-    pub fn mass_warnings(&mut self) -> i32 {
-        let mut warning_count: i32 = 0;
+    pub fn mass_warnings(&mut self) -> Vec<Diagnostic> {
+        let mut warnings: Vec<Diagnostic> = Vec::new();
         
         // Iterate through each scope
-        for scope_table in self.graph.node_weights() {
-            // Iterate through each entry in the scope's symbol table
-            for (id_name, entry) in scope_table.iter() {
+        for scope in 0..self.num_scopes() {
+            // Iterate through each entry in the scope's symbol table in a consistent order
+            for (id_name, entry) in self.get_scope_entries(scope) {
                 if !entry.is_initialized {
                     if entry.is_used {
                         // Throw warning for declared and used but not initialized
-                        nexus_log::log(
-                            nexus_log::LogTypes::Warning,
-                            nexus_log::LogSources::SemanticAnalyzer,
-                            format!("Warning at {:?}; Id [ {} ] is declared and used, but never initialized", entry.position, id_name)
-                        );
-                        warning_count += 1;
+                        warnings.push(Diagnostic::new(
+                            DiagnosticCode::UsedButNeverInitialized,
+                            format!("Id [ {} ] is declared and used, but never initialized", id_name),
+                            Some(entry.span)
+                        ));
                     } else {
                         // Throw warning for declared but never initialized or used
-                        nexus_log::log(
-                            nexus_log::LogTypes::Warning,
-                            nexus_log::LogSources::SemanticAnalyzer,
-                            format!("Warning at {:?}; Id [ {} ] is declared, but never initialized or used", entry.position, id_name)
-                        );
-                        warning_count += 1;
+                        warnings.push(Diagnostic::new(
+                            DiagnosticCode::NeverInitializedOrUsed,
+                            format!("Id [ {} ] is declared, but never initialized or used", id_name),
+                            Some(entry.span)
+                        ));
                     }
                 } else {
                     if !entry.is_used {
                         // Throw warning for declared and initialized but never used
-                        nexus_log::log(
-                            nexus_log::LogTypes::Warning,
-                            nexus_log::LogSources::SemanticAnalyzer,
-                            format!("Warning at {:?}; Id [ {} ] is declared and initialized, but never used", entry.position, id_name)
-                        );
-                        warning_count += 1;
+                        warnings.push(Diagnostic::new(
+                            DiagnosticCode::InitializedButNeverUsed,
+                            format!("Id [ {} ] is declared and initialized, but never used", id_name),
+                            Some(entry.span)
+                        ));
                     }
                 }
             }
         }
-        return warning_count;
+        return warnings;
     }
 
     pub fn display_symbol_table(&mut self, program_number: &u32) {
@@ -350,7 +339,7 @@ impl SymbolTable {
                 row_elem.append_child(&scope_elem).expect("Should be able to append child node");
 
                 let position_elem: Element = document.create_element("td").expect("Should be able to create position element");
-                position_elem.set_inner_html(format!("{:?}", entry.position).as_str());
+                position_elem.set_inner_html(format!("{:?}", entry.span.position()).as_str());
                 row_elem.append_child(&position_elem).expect("Should be able to append child node");
 
                 let init_elem: Element = document.create_element("td").expect("Should be able to create init element");
@@ -366,6 +355,23 @@ impl SymbolTable {
         }
     }
 
+    // Gets the number of scopes in the program
+    pub fn num_scopes(&self) -> usize {
+        return self.graph.node_count();
+    }
+
+    // Gets the scope that the given scope is nested in, or None for the outermost scope
+    pub fn get_parent_scope(&self, scope: usize) -> Option<usize> {
+        return self.graph.neighbors(NodeIndex::new(scope)).next().map(|parent| parent.index());
+    }
+
+    // Gets the ids in a scope sorted by name along with their entries
+    pub fn get_scope_entries(&self, scope: usize) -> Vec<(&String, &SymbolTableEntry)> {
+        let mut entries: Vec<(&String, &SymbolTableEntry)> = self.graph.node_weight(NodeIndex::new(scope)).unwrap().iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        return entries;
+    }
+
     // Function to reset the symbol table for the new analysis
     pub fn reset(&mut self) {
         self.graph.clear();
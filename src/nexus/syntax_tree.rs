@@ -96,6 +96,17 @@ impl SyntaxTree {
         }
     }
 
+    // Gets the node that new nodes are currently being added under
+    pub fn current_node(&self) -> Option<usize> {
+        return self.current;
+    }
+
+    // Moves back to a node that was previously the current node, such as
+    // when the parser recovers from an error partway through a subtree
+    pub fn move_to(&mut self, node: usize) {
+        self.current = Some(node);
+    }
+
     // Function to move back up
     pub fn move_up(&mut self) {
         // Get the current parent
@@ -130,7 +141,8 @@ impl SyntaxTree {
         self.create_image(svg_id);
     }
 
-    fn create_text(&self) -> String {
+    // Creates the indented text form of the tree, such as -<Block>
+    pub fn create_text(&self) -> String {
         let mut tree_builder: Builder = Builder::default();
 
         self.create_text_dfs(&mut tree_builder, self.root.unwrap(), 0);
@@ -160,13 +172,16 @@ impl SyntaxTree {
         }
     }
 
+    // Converts the graph into the dot format
+    pub fn create_dot(&self) -> String {
+        let graph_dot: Dot<&Graph<SyntaxTreeNode, ()>> = Dot::with_config(&self.graph, &[Config::EdgeNoLabel]);
+        return format!("{:?}", graph_dot);
+    }
+
     // Function that creates 
     fn create_image(&self, svg_id: String) {
-        // Convert the graph into a dot format
-        let graph_dot: Dot<&Graph<SyntaxTreeNode, ()>> = Dot::with_config(&self.graph, &[Config::EdgeNoLabel]);
-        
         // Call the JS to create the graph on the webpage using d3.js
-        create_rendering(format!("{:?}", graph_dot).as_str(), &svg_id);
+        create_rendering(self.create_dot().as_str(), &svg_id);
     }
 
     fn create_display_area(&self, program_number: &u32) -> String {
@@ -1,3 +1,5 @@
+use crate::nexus::diagnostic::Span;
+
 // Defines a token
 #[derive (Debug, Clone)]
 pub struct Token {
@@ -5,19 +7,24 @@ pub struct Token {
     pub token_type: TokenType,
     // The content of the token
     pub text: String,
-    // The position in the source code the token is located
-    pub position: (usize, usize)
+    // Where the token is located in the source code
+    pub span: Span
 }
 
 impl Token {
     // Create a new token with the given information
-    pub fn new(token_type_in: TokenType, token_text: String, line_number: usize, col_number: usize) -> Self {
+    pub fn new(token_type_in: TokenType, token_text: String, span: Span) -> Self {
         return Token {
             token_type: token_type_in,
             text: token_text,
-            position: (line_number, col_number)
+            span
         }
     }
+
+    // Gets the start of the token as a (line, col) tuple
+    pub fn position(&self) -> (usize, usize) {
+        return self.span.position();
+    }
 }
 
 // Defines the token types and what they hold
@@ -26,7 +33,8 @@ pub enum TokenType {
     Keyword(Keywords),
     Identifier(String),
     Symbol(Symbols),
-    Digit(u8),
+    // A single digit, or a whole integer literal with the multi-digit integer extension
+    Digit(u64),
     Char(String),
     Unrecognized(String)
 }
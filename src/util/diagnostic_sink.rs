@@ -0,0 +1,272 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use web_sys::{Document, Window, Element, DomTokenList};
+
+use crate::nexus::diagnostic::{Diagnostic, Severity};
+use crate::util::nexus_log::{LogTypes, LogSources};
+
+// Defines somewhere that the logs from the compiler can be sent to
+pub trait DiagnosticSink {
+    // Reports a message with the given type and source
+    fn log(&mut self, log_type: &LogTypes, src: &LogSources, msg: &str);
+
+    // Adds a separator between groups of logs
+    fn insert_empty_line(&mut self);
+
+    // Removes all of the existing logs
+    fn clear(&mut self);
+
+    // Reports an error or warning from one of the phases
+    // By default it is logged like any other message
+    fn report(&mut self, diagnostic: &Diagnostic) {
+        self.log(&get_log_type(diagnostic), &diagnostic.phase, &diagnostic.get_log_message());
+    }
+}
+
+// Errors and warnings are logged with the matching log type
+pub fn get_log_type(diagnostic: &Diagnostic) -> LogTypes {
+    match diagnostic.severity {
+        Severity::Error => return LogTypes::Error,
+        Severity::Warning => return LogTypes::Warning
+    }
+}
+
+// Sink that writes the logs to the log area on the webpage
+pub struct DomSink;
+
+impl DiagnosticSink for DomSink {
+    fn log(&mut self, log_type: &LogTypes, src: &LogSources, msg: &str) {
+        // Get the log area
+        let log_area: Element = get_log_area();
+
+        // Create the new element to place in the logs
+        let new_log: Element = get_document().create_element("p").expect("Should be able to create the element");
+        new_log.set_inner_html(format!("[{} - {}]: {}", log_type, src, msg).as_str());
+
+        // Set the new value
+        log_area.append_child(&new_log).expect("Should be able to add the child");
+
+        // Special cases and such
+        match log_type {
+            LogTypes::Debug => {
+                // Only log if in verbose mode
+                if !is_verbose_mode(src) {
+                    log_area.remove_child(&new_log).expect("Should be able to remove the child");
+                }
+            },
+            LogTypes::Error => {
+                // Errors have special classes
+                new_log.set_class_name("error");
+            },
+            LogTypes::Warning => {
+                // Set the warning class
+                new_log.set_class_name("warning");
+            },
+            _ => {
+                // Nothing else to do here
+            }
+        }
+    }
+
+    fn insert_empty_line(&mut self) {
+        // Get the log area
+        let log_area: Element = get_log_area();
+
+        // The new line is just a br tag
+        let new_line: Element = get_document().create_element("br").expect("Should be able to create the br element");
+        log_area.append_child(&new_line).expect("Should be able to add the child");
+    }
+
+    fn clear(&mut self) {
+        // Get the log area
+        let log_area: Element = get_log_area();
+
+        // Remove all children by wiping the inner html
+        log_area.set_inner_html("");
+    }
+}
+
+// Sink that prints the logs to stderr
+pub struct TerminalSink {
+    // Whether or not info and debug logs are printed
+    verbose: bool,
+    // The source code that errors and warnings point into, if it is known
+    source_code: Option<String>
+}
+
+impl TerminalSink {
+    pub fn new(verbose: bool) -> Self {
+        return TerminalSink {
+            verbose,
+            source_code: None
+        };
+    }
+
+    // Errors and warnings show where they are in the source code instead of only their message
+    pub fn with_source_code(mut self, source_code: &str) -> Self {
+        self.source_code = Some(String::from(source_code));
+        return self;
+    }
+}
+
+impl DiagnosticSink for TerminalSink {
+    fn log(&mut self, log_type: &LogTypes, src: &LogSources, msg: &str) {
+        // Only warnings and errors are printed unless in verbose mode
+        if (*log_type == LogTypes::Info || *log_type == LogTypes::Debug) && !self.verbose {
+            return;
+        }
+        eprintln!("[{} - {}]: {}", log_type, src, msg);
+    }
+
+    fn insert_empty_line(&mut self) {
+        // The empty lines only separate the phases in the full logs
+        if self.verbose {
+            eprintln!();
+        }
+    }
+
+    fn clear(&mut self) {
+        // Nothing can be removed from the terminal
+    }
+
+    fn report(&mut self, diagnostic: &Diagnostic) {
+        match &self.source_code {
+            Some(source_code) => eprintln!("{}\n", diagnostic.render(source_code).trim_end()),
+            None => self.log(&get_log_type(diagnostic), &diagnostic.phase, &diagnostic.get_log_message())
+        }
+    }
+}
+
+// A single log that was reported to a collector
+#[derive (Debug, Clone, PartialEq)]
+pub enum LogEntry {
+    Message {
+        log_type: LogTypes,
+        src: LogSources,
+        msg: String
+    },
+    // An error or warning, which is kept whole so the sink it is replayed to can show its location
+    Diagnostic(Diagnostic),
+    EmptyLine
+}
+
+impl LogEntry {
+    // Sends the log to another sink, such as when showing collected logs on the webpage
+    pub fn replay(&self, sink: &mut dyn DiagnosticSink) {
+        match self {
+            LogEntry::Message { log_type, src, msg } => sink.log(log_type, src, msg),
+            LogEntry::Diagnostic(diagnostic) => sink.report(diagnostic),
+            LogEntry::EmptyLine => sink.insert_empty_line()
+        }
+    }
+}
+
+// Sink that keeps all of the logs in memory so they can be inspected later
+// Clones share the same buffer, so one copy can be given to the compiler while the other is read
+#[derive (Clone, Default)]
+pub struct CollectorSink {
+    entries: Rc<RefCell<Vec<LogEntry>>>,
+    diagnostics: Rc<RefCell<Vec<Diagnostic>>>
+}
+
+impl CollectorSink {
+    pub fn new() -> Self {
+        return CollectorSink {
+            entries: Rc::new(RefCell::new(Vec::new())),
+            diagnostics: Rc::new(RefCell::new(Vec::new()))
+        };
+    }
+
+    // Gets a copy of all of the logs collected so far
+    pub fn entries(&self) -> Vec<LogEntry> {
+        return self.entries.borrow().clone();
+    }
+
+    // Gets the logs of the given type, where errors and warnings count as their severity
+    pub fn entries_of_type(&self, log_type: LogTypes) -> Vec<LogEntry> {
+        return self.entries.borrow().iter()
+            .filter(|entry| match entry {
+                LogEntry::Message { log_type: entry_type, .. } => *entry_type == log_type,
+                LogEntry::Diagnostic(diagnostic) => get_log_type(diagnostic) == log_type,
+                LogEntry::EmptyLine => false
+            })
+            .cloned()
+            .collect();
+    }
+
+    // Gets a copy of all of the errors and warnings reported so far
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        return self.diagnostics.borrow().clone();
+    }
+}
+
+impl DiagnosticSink for CollectorSink {
+    fn log(&mut self, log_type: &LogTypes, src: &LogSources, msg: &str) {
+        self.entries.borrow_mut().push(LogEntry::Message {
+            log_type: log_type.to_owned(),
+            src: src.to_owned(),
+            msg: String::from(msg)
+        });
+    }
+
+    fn insert_empty_line(&mut self) {
+        // Keep the empty lines so the logs can be replayed with the same formatting
+        self.entries.borrow_mut().push(LogEntry::EmptyLine);
+    }
+
+    fn clear(&mut self) {
+        self.entries.borrow_mut().clear();
+        self.diagnostics.borrow_mut().clear();
+    }
+
+    fn report(&mut self, diagnostic: &Diagnostic) {
+        // Keep the structured diagnostic in the logs too, so it is only shown once when they are replayed
+        self.entries.borrow_mut().push(LogEntry::Diagnostic(diagnostic.to_owned()));
+        self.diagnostics.borrow_mut().push(diagnostic.to_owned());
+    }
+}
+
+fn get_log_area() -> Element {
+    let document: Document = get_document();
+
+    // Get the area where the logs are printed
+    let log_area: Element = document
+        .get_element_by_id("nexus-log-area")
+        .expect("There should be a nexus-log-area element");
+
+    return log_area;
+}
+
+fn get_document() -> Document {
+    // Grab the window and document elements for DOM manipulation
+    let window: Window = web_sys::window().expect("The window object should exist.");
+    let document: Document = window.document().expect("The document object should exist");
+
+    return document;
+}
+
+fn is_verbose_mode(src: &LogSources) -> bool {
+    // Grab the window and document elements for DOM manipulation
+    let window: Window = web_sys::window().expect("The window object should exist.");
+    let document: Document = window.document().expect("The document object should exist");
+
+    // Assume we are in verbose mode
+    let mut out: bool = true;
+
+    // Get the target button element
+    let target: Element = match src {
+        LogSources::Nexus => document.get_element_by_id("nexus-log-mode").expect("Should be able to find the nexus-log-mode element"),
+        LogSources::Lexer => document.get_element_by_id("lexer-log-mode").expect("Should be able to find the lexer-log-mode element"),
+        LogSources::Parser => document.get_element_by_id("parser-log-mode").expect("Should be able to find the parser-log-mode element"),
+        LogSources::SemanticAnalyzer => document.get_element_by_id("semantic-log-mode").expect("Should be able to find the semantic-log-mode element"),
+        LogSources::CodeGenerator => document.get_element_by_id("codegen-log-mode").expect("Should be able to find the codegen-log-mode element"),
+    };
+
+    // Check to see if it is in simple mode
+    let class_list: DomTokenList = target.class_list();
+    if class_list.contains("simple") {
+        out = false;
+    }
+    return out;
+}
@@ -0,0 +1,126 @@
+use crate::nexus::compiler::{self, CompilationResult, CompileOptions};
+use crate::nexus::backend::Artifact;
+use crate::nexus::emulator_6502::{self, Emulator6502};
+use crate::nexus::emulator_riscv::{self, EmulatorRiscV};
+use crate::nexus::riscv_assembler::AssembleError;
+
+// The printed output of a program when it is run for both targets
+#[derive (Debug, Clone)]
+pub struct DifferentialResult {
+    pub program_number: u32,
+    pub result_6502: emulator_6502::EmulatorResult,
+    // The generated assembly should always assemble, so an error is a bug in the code generator
+    pub result_riscv: Result<emulator_riscv::EmulatorResult, AssembleError>,
+    // The RISC-V output in the same format as the 6502 output
+    pub normalized_riscv_output: String
+}
+
+impl DifferentialResult {
+    // Whether either program was stopped at the instruction limit, such as a while true loop
+    pub fn is_truncated(&self) -> bool {
+        return self.is_6502_truncated() || self.is_riscv_truncated();
+    }
+
+    fn is_6502_truncated(&self) -> bool {
+        return self.result_6502.stop_reason == emulator_6502::StopReason::InstructionLimit;
+    }
+
+    fn is_riscv_truncated(&self) -> bool {
+        return matches!(&self.result_riscv, Ok(result) if result.stop_reason == emulator_riscv::StopReason::InstructionLimit);
+    }
+
+    // Both programs have to finish with the same output
+    // When one was stopped early, the other has to either be stopped early too or finish normally
+    // with everything the stopped one printed, so only the output both of them got to is compared
+    pub fn is_match(&self) -> bool {
+        let result_riscv: &emulator_riscv::EmulatorResult = match &self.result_riscv {
+            Ok(result) => result,
+            Err(_) => return false
+        };
+        let is_6502_done: bool = self.is_6502_truncated() || self.result_6502.is_ok();
+        let is_riscv_done: bool = self.is_riscv_truncated() || result_riscv.is_ok();
+        if !is_6502_done || !is_riscv_done {
+            return false;
+        }
+
+        let output_6502: &str = &self.result_6502.output;
+        let output_riscv: &str = &self.normalized_riscv_output;
+        if self.is_truncated() {
+            let len: usize = output_6502.len().min(output_riscv.len());
+            return output_6502.as_bytes()[..len] == output_riscv.as_bytes()[..len];
+        }
+        return output_6502 == output_riscv;
+    }
+
+    // Describes the difference for a test failure
+    pub fn describe(&self) -> String {
+        let riscv_stop_reason: String = match &self.result_riscv {
+            Ok(result) => result.stop_reason.to_string(),
+            Err(error) => format!("The generated assembly does not assemble: {}", error)
+        };
+        return format!("Program {}: 6502 printed {:?} ({}), but RISC-V printed {:?} ({})",
+                       self.program_number,
+                       self.result_6502.output, self.result_6502.stop_reason,
+                       self.normalized_riscv_output, riscv_stop_reason);
+    }
+}
+
+// Converts the RISC-V output to the 6502 format
+// Each print is on its own line and integers are printed with 3 digits, but the 6502 does neither
+// Strings cannot have digits in them, so a line of exactly 3 digits can only come from print_int
+fn normalize_riscv_output(output: &str, is_truncated: bool) -> String {
+    let mut lines: Vec<&str> = output.split('\n').collect();
+    // The last piece is empty when the output ends with a new line, or a partial print if it was cut off
+    let last: &str = lines.pop().unwrap_or("");
+
+    let mut normalized: String = String::new();
+    for line in lines {
+        if line.len() == 3 && line.chars().all(|c| c.is_ascii_digit()) {
+            let trimmed: &str = line.trim_start_matches('0');
+            normalized.push_str(if trimmed.is_empty() { "0" } else { trimmed });
+        } else {
+            normalized.push_str(line);
+        }
+    }
+    if !is_truncated {
+        normalized.push_str(last);
+    }
+    return normalized;
+}
+
+// Compiles the source code for both targets and runs every program that compiled for both in the emulators
+// Programs that do not compile for one of the targets, such as from running out of memory on the 6502, are skipped
+pub fn run_source(source_code: &str) -> Vec<DifferentialResult> {
+    let compilation_6502: CompilationResult = compiler::compile(source_code, &CompileOptions::new("6502")).expect("The 6502 backend is always registered");
+    let compilation_riscv: CompilationResult = compiler::compile(source_code, &CompileOptions::new("riscv")).expect("The RISC-V backend is always registered");
+
+    let mut results: Vec<DifferentialResult> = Vec::new();
+    for (program_6502, program_riscv) in compilation_6502.programs.iter().zip(compilation_riscv.programs.iter()) {
+        let image: &Vec<u8> = match program_6502.code.as_ref() {
+            Some(Artifact::Image(image)) => image,
+            _ => continue
+        };
+        let assembly: &String = match program_riscv.code.as_ref() {
+            Some(Artifact::Assembly(assembly)) => assembly,
+            _ => continue
+        };
+
+        let result_6502: emulator_6502::EmulatorResult = Emulator6502::new(image).run(emulator_6502::DEFAULT_INSTRUCTION_LIMIT);
+        let result_riscv: Result<emulator_riscv::EmulatorResult, AssembleError> = EmulatorRiscV::new(assembly)
+            .map(|mut emulator_riscv| emulator_riscv.run(emulator_riscv::DEFAULT_INSTRUCTION_LIMIT));
+
+        // Nothing was printed if it did not assemble
+        let normalized_riscv_output: String = match &result_riscv {
+            Ok(result) => normalize_riscv_output(&result.output, result.stop_reason == emulator_riscv::StopReason::InstructionLimit),
+            Err(_) => String::new()
+        };
+        results.push(DifferentialResult {
+            program_number: program_6502.program_number,
+            normalized_riscv_output,
+            result_6502,
+            result_riscv
+        });
+    }
+
+    return results;
+}
@@ -1,3 +1,4 @@
 pub mod nexus_log;
+pub mod diagnostic_sink;
 pub mod test;
-pub mod target;
+pub mod differential;
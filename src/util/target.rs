@@ -1,6 +0,0 @@
-// Enum for determining the target for the compiler
-#[derive (Debug)]
-pub enum Target {
-    Target6502,
-    TargetRiscV
-}
@@ -0,0 +1,968 @@
+use std::path::PathBuf;
+
+use nexus_compiler::nexus::ast::{Block, Stmt};
+use nexus_compiler::nexus::ast_folder::Folder;
+use nexus_compiler::nexus::ast_visitor::Visitor;
+use nexus_compiler::nexus::backend;
+use nexus_compiler::nexus::compiler::{self, CompilationResult, CompileOptions, ProgramResult};
+use nexus_compiler::nexus::dead_block_pruner::DeadBlockPruner;
+use nexus_compiler::nexus::diagnostic::{Diagnostic, DiagnosticCode};
+use nexus_compiler::nexus::emulator_6502::{self, Emulator6502, EmulatorResult, StopReason};
+use nexus_compiler::nexus::emulator_riscv::{self, EmulatorRiscV};
+use nexus_compiler::nexus::ir::IrProgram;
+use nexus_compiler::nexus::memory_layout::MemoryLayout;
+use nexus_compiler::nexus::register_allocator::{RegisterAllocator, Name};
+use nexus_compiler::nexus::riscv_assembler::{self, AssembleError, AssembledProgram};
+use nexus_compiler::nexus::token::{Token, TokenType};
+use nexus_compiler::util::test::{get_tests, Test};
+use nexus_compiler::util::differential::{self, DifferentialResult};
+use nexus_compiler::util::diagnostic_sink::{CollectorSink, DiagnosticSink, LogEntry};
+use nexus_compiler::util::nexus_log::{self, LogSources, LogTypes};
+
+// Golden tests for the sample programs in the test dropdown
+// Run with UPDATE_SNAPSHOTS=1 to regenerate the snapshots after an intended change
+
+fn snapshot_dir() -> PathBuf {
+    return PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("snapshots");
+}
+
+// Creates a file name such as codegen_alans_tests.snap
+fn snapshot_name(test: &Test) -> String {
+    let mut name: String = format!("{}_", test.test_type).to_lowercase();
+    for c in test.test_name.to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            name.push(c);
+        } else if c == ' ' && !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    return format!("{}.snap", name.trim_end_matches('_'));
+}
+
+fn compile(source_code: &str, target: &str) -> CompilationResult {
+    return compiler::compile(source_code, &CompileOptions::new(target)).unwrap();
+}
+
+// Gets the status of each phase for the program
+fn phase_status(program: &ProgramResult) -> [&'static str; 3] {
+    let lex: &str = if program.tokens.is_some() { "ok" } else { "failed" };
+    let parse: &str = if program.tokens.is_none() {
+        "skipped"
+    } else if program.cst.is_some() {
+        "ok"
+    } else {
+        "failed"
+    };
+    let semantic: &str = if program.ast.is_none() {
+        "skipped"
+    } else if program.symbol_table.is_some() {
+        "ok"
+    } else {
+        "failed"
+    };
+    return [lex, parse, semantic];
+}
+
+fn code_gen_status(program: &ProgramResult) -> &'static str {
+    if program.symbol_table.is_none() {
+        return "skipped";
+    } else if program.code.is_some() {
+        return "ok";
+    } else {
+        return "failed";
+    }
+}
+
+fn format_diagnostic(diagnostic: &Diagnostic) -> String {
+    // The position is kept so the snapshots show where each diagnostic is
+    return format!("{} {}: {}", diagnostic.code, diagnostic.severity, diagnostic.get_log_message());
+}
+
+// Splits the 256 byte image into rows of 16 bytes
+fn format_image(code: &str) -> String {
+    let bytes: Vec<&str> = code.split_whitespace().collect();
+    return bytes.chunks(16).map(|row| row.join(" ")).collect::<Vec<String>>().join("\n");
+}
+
+// Creates the snapshot text for a test by compiling it for both targets
+fn create_snapshot(test: &Test) -> String {
+    let result_6502: CompilationResult = compile(&test.test_code, "6502");
+    let result_riscv: CompilationResult = compile(&test.test_code, "riscv");
+
+    let mut out: String = String::new();
+    for (program, program_riscv) in result_6502.programs.iter().zip(result_riscv.programs.iter()) {
+        let [lex, parse, semantic]: [&str; 3] = phase_status(program);
+        let num_errors: usize = program.diagnostics.iter().filter(|diagnostic| diagnostic.is_error()).count();
+        let num_warnings: usize = program.diagnostics.iter().filter(|diagnostic| diagnostic.is_warning()).count();
+
+        out.push_str(&format!("== Program {} ==\n", program.program_number));
+        out.push_str(&format!("lex: {}\n", lex));
+        out.push_str(&format!("parse: {}\n", parse));
+        out.push_str(&format!("semantic analysis: {}\n", semantic));
+        out.push_str(&format!("code gen 6502: {}\n", code_gen_status(program)));
+        out.push_str(&format!("code gen riscv: {}\n", code_gen_status(program_riscv)));
+        out.push_str(&format!("errors: {}\n", num_errors));
+        out.push_str(&format!("warnings: {}\n", num_warnings));
+
+        if !program.diagnostics.is_empty() {
+            out.push_str("\n-- diagnostics --\n");
+            for diagnostic in program.diagnostics.iter() {
+                out.push_str(&format!("{}\n", format_diagnostic(diagnostic)));
+            }
+        }
+        if program.cst.is_some() {
+            out.push_str(&format!("\n-- cst --\n{}", program.cst.as_ref().unwrap().create_text()));
+        }
+        if program.ast.is_some() {
+            out.push_str(&format!("\n-- ast --\n{}", program.ast.as_ref().unwrap().create_syntax_tree().create_text()));
+        }
+        if program.ir.is_some() {
+            out.push_str(&format!("\n-- ir --\n{}", program.ir.as_ref().unwrap().create_text()));
+        }
+        if program.code.is_some() {
+            out.push_str(&format!("\n-- 6502 --\n{}\n", format_image(&program.code.as_ref().unwrap().to_string())));
+        }
+        if program.listing.is_some() {
+            out.push_str(&format!("\n-- 6502 listing --\n{}\n", program.listing.as_ref().unwrap().trim_end()));
+        }
+        if program_riscv.code.is_some() {
+            out.push_str(&format!("\n-- riscv --\n{}\n", program_riscv.code.as_ref().unwrap().to_string().trim_end()));
+        }
+        out.push('\n');
+    }
+    return out;
+}
+
+#[test]
+fn sample_programs_match_snapshots() {
+    let update: bool = std::env::var("UPDATE_SNAPSHOTS").is_ok();
+    let mut failures: Vec<String> = Vec::new();
+
+    for test in get_tests().iter() {
+        let path: PathBuf = snapshot_dir().join(snapshot_name(test));
+        let actual: String = create_snapshot(test);
+
+        if update {
+            std::fs::create_dir_all(snapshot_dir()).unwrap();
+            std::fs::write(&path, &actual).unwrap();
+            continue;
+        }
+
+        match std::fs::read_to_string(&path) {
+            Ok(expected) => {
+                if expected != actual {
+                    // Point to the first line that is different
+                    let line: usize = expected.lines().zip(actual.lines()).take_while(|(a, b)| a == b).count() + 1;
+                    failures.push(format!("[{}] - {} does not match {} (first difference on line {})",
+                                          test.test_type, test.test_name, path.display(), line));
+                }
+            },
+            Err(_) => failures.push(format!("[{}] - {} is missing {}", test.test_type, test.test_name, path.display()))
+        }
+    }
+
+    assert!(failures.is_empty(), "{}\nRun with UPDATE_SNAPSHOTS=1 to accept the changes", failures.join("\n"));
+}
+
+#[test]
+fn snapshot_names_are_unique() {
+    let mut names: Vec<String> = get_tests().iter().map(snapshot_name).collect();
+    let num_tests: usize = names.len();
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), num_tests);
+}
+
+fn get_test(name: &str, test_type: &str) -> Test {
+    return get_tests().into_iter()
+        .find(|test| test.test_name == name && test.test_type.to_string() == test_type)
+        .unwrap_or_else(|| panic!("No test named [{}] - {}", test_type, name));
+}
+
+fn codes(program: &ProgramResult) -> Vec<DiagnosticCode> {
+    return program.diagnostics.iter().map(|diagnostic| diagnostic.code).collect();
+}
+
+#[test]
+fn alans_lex_tests() {
+    let result: CompilationResult = compile(&get_test("Alan's tests", "LEX").test_code, "6502");
+    assert_eq!(result.programs.len(), 5);
+
+    // The 4th program has an @, which is not in the grammar
+    assert!(result.programs[3].tokens.is_none());
+    assert_eq!(codes(&result.programs[3]), vec![DiagnosticCode::UnrecognizedToken]);
+
+    // Everything else makes it through lex
+    for i in [0, 1, 2, 4] {
+        assert!(result.programs[i].tokens.is_some(), "Program {} should lex", i + 1);
+    }
+}
+
+#[test]
+fn multiple_syntax_errors() {
+    let result: CompilationResult = compile(&get_test("Multiple syntax errors", "PARSE").test_code, "6502");
+    let program: &ProgramResult = &result.programs[0];
+
+    // The parser recovers after each error, so all 4 are reported
+    assert!(program.cst.is_none());
+    assert!(program.ast.is_none());
+    assert_eq!(codes(program), vec![DiagnosticCode::UnexpectedToken; 4]);
+}
+
+#[test]
+fn token_spans_match_the_source() {
+    for test in get_tests().iter() {
+        let result: CompilationResult = compile(&test.test_code, "6502");
+        for token in result.programs.iter().filter_map(|program| program.tokens.as_ref()).flatten() {
+            assert_eq!(&test.test_code[token.span.start..token.span.end], token.text,
+                       "[{}] - {} has a bad span for the token at {:?}", test.test_type, test.test_name, token.position());
+        }
+    }
+}
+
+#[test]
+fn type_mismatch_points_at_the_declaration() {
+    let source: String = get_test("Mismatched types", "SEMANTICANALYSIS").test_code;
+    let result: CompilationResult = compile(&source, "6502");
+    let mismatch: &Diagnostic = result.programs[0].diagnostics.iter()
+        .find(|diagnostic| diagnostic.code == DiagnosticCode::AssignmentTypeMismatch)
+        .unwrap();
+
+    // The declaration is shown first because it comes first in the source code
+    let expected: &str = "error[S003]: Mismatched types; Expected Int for the assignment type, but received String
+ --> 4:6
+  |
+3 | \tint a
+  | \t    - Declared as Int here
+4 | \ta = \"hello\"
+  | \t    ^^^^^^^
+";
+    assert_eq!(mismatch.render(&source), expected);
+
+    // The logs do not show the source code, so they still have the position
+    assert_eq!(mismatch.get_log_message(), "Error at (4, 6); Mismatched types; Expected Int for the assignment type, but received String");
+}
+
+// Records the scopes in the order they are entered
+struct ScopeRecorder {
+    scopes: Vec<usize>
+}
+
+impl Visitor for ScopeRecorder {
+    fn enter_scope(&mut self, scope: usize, _context: &mut ()) {
+        self.scopes.push(scope);
+    }
+}
+
+// Uses all of the default methods, so it should rebuild the same AST
+struct IdentityFolder;
+
+impl Folder for IdentityFolder {}
+
+#[test]
+fn ast_traversals_match_the_symbol_table() {
+    for test in get_tests().iter() {
+        let result: CompilationResult = compile(&test.test_code, "6502");
+        for program in result.programs.iter().filter(|program| program.symbol_table.is_some()) {
+            let ast: &Block = program.ast.as_ref().unwrap();
+
+            // Scopes are entered in the same order the analyzer created them
+            let mut recorder: ScopeRecorder = ScopeRecorder { scopes: Vec::new() };
+            recorder.visit_block(ast, &mut ());
+            let num_scopes: usize = program.symbol_table.as_ref().unwrap().num_scopes();
+            assert_eq!(recorder.scopes, (0..num_scopes).collect::<Vec<usize>>(), "[{}] - {}", test.test_type, test.test_name);
+
+            assert_eq!(&IdentityFolder.fold_block(ast.to_owned(), &mut ()), ast, "[{}] - {}", test.test_type, test.test_name);
+        }
+    }
+}
+
+#[test]
+fn dead_blocks_are_pruned_from_the_ast() {
+    let source: &str = "{\n  if false {\n    print(1)\n  }\n  while false {\n    print(2)\n  }\n  {\n    print(3)\n  }\n}$";
+    let result: CompilationResult = compile(source, "6502");
+    let ast: &Block = result.programs[0].ast.as_ref().unwrap();
+
+    // The AST that is shown still has every statement
+    assert_eq!(ast.statements.len(), 3);
+
+    // Only the plain block is left, and it keeps the scope it had before the dead blocks were removed
+    let pruned: Block = DeadBlockPruner::new().prune_dead_blocks(ast.to_owned());
+    assert_eq!(pruned.statements.len(), 1);
+    match &pruned.statements[0] {
+        Stmt::Block(block) => assert_eq!(block.scope, 3),
+        other => panic!("Expected the block to be kept, found {:?}", other)
+    }
+}
+
+#[test]
+fn blocks_after_skipped_blocks_keep_their_scopes() {
+    // The dead blocks are skipped, but the later blocks keep their own scopes
+    let source: &str = "{\n  if false {\n    int a\n    a = 5\n  }\n  while false {\n    int a\n  }\n  {\n    int b\n    b = 1\n    print(b)\n  }\n  {\n    string b\n    b = \"two\"\n    if (b == \"two\") {\n      print(2)\n    }\n  }\n}$";
+
+    for target in backend::get_backend_names() {
+        let result: CompilationResult = compile(source, target);
+        assert!(result.is_ok(), "{} should compile", target);
+
+        let program: &ProgramResult = &result.programs[0];
+        let mut recorder: ScopeRecorder = ScopeRecorder { scopes: Vec::new() };
+        recorder.visit_block(program.ast.as_ref().unwrap(), &mut ());
+        assert_eq!(recorder.scopes, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    let results: Vec<EmulatorResult> = run_6502(source, emulator_6502::DEFAULT_INSTRUCTION_LIMIT);
+    assert!(results[0].is_ok());
+    assert_eq!(results[0].output, "12");
+    let differential_results: Vec<DifferentialResult> = differential::run_source(source);
+    assert!(differential_results[0].is_match(), "{}", differential_results[0].describe());
+}
+
+#[test]
+fn lots_of_warnings() {
+    let result: CompilationResult = compile(&get_test("Lots of warnings", "SEMANTICANALYSIS").test_code, "6502");
+    let program: &ProgramResult = &result.programs[0];
+
+    assert!(program.is_ok());
+    assert!(program.diagnostics.iter().all(|diagnostic| diagnostic.is_warning()));
+    assert!(codes(program).contains(&DiagnosticCode::NeverInitializedOrUsed));
+    assert!(codes(program).contains(&DiagnosticCode::UsedButNeverInitialized));
+    assert!(codes(program).contains(&DiagnosticCode::InitializedAfterUse));
+}
+
+#[test]
+fn memory_overflows_fail_6502_only() {
+    // The original stack overflow sample fits now that 1 + 2 + 3 is folded, so the one with a variable is used
+    for (name, code) in [("Stack overflow error with a variable", DiagnosticCode::StackOverflow), ("Heap overflow error", DiagnosticCode::HeapOverflow)] {
+        let source: String = get_test(name, "CODEGEN").test_code;
+
+        let result_6502: CompilationResult = compile(&source, "6502");
+        assert!(!result_6502.programs[0].is_ok(), "{} should fail for the 6502", name);
+        let errors: Vec<DiagnosticCode> = result_6502.programs[0].diagnostics.iter()
+            .filter(|diagnostic| diagnostic.is_error())
+            .map(|diagnostic| diagnostic.code)
+            .collect();
+        assert_eq!(errors, vec![code]);
+
+        // RISC-V does not have the 256 byte limit
+        let result_riscv: CompilationResult = compile(&source, "riscv");
+        assert!(result_riscv.programs[0].is_ok(), "{} should compile for RISC-V", name);
+    }
+}
+
+#[test]
+fn code_gen_tests_compile() {
+    for target in backend::get_backend_names() {
+        for test in get_tests().iter().filter(|test| test.test_type.to_string() == "CODEGEN" && !test.test_name.contains("overflow")) {
+            let result: CompilationResult = compile(&test.test_code, target);
+            assert!(result.is_ok(), "{} should compile for {}", test.test_name, target);
+            assert!(result.programs.iter().all(|program| program.diagnostics.iter().all(|diagnostic| !diagnostic.is_error())));
+        }
+    }
+}
+
+#[test]
+fn backends_are_found_by_name() {
+    let names: Vec<&str> = backend::get_backend_names();
+    assert_eq!(names, vec!["6502", "riscv"]);
+
+    for backend in backend::get_backends() {
+        // Every name and alias finds the same backend, ignoring case
+        for name in std::iter::once(backend.name()).chain(backend.aliases().iter().copied()) {
+            assert_eq!(backend::get_backend(&name.to_uppercase()).map(|found| found.name()), Some(backend.name()));
+        }
+    }
+    assert!(backend::get_backend("x86").is_none());
+
+    // Compiling for a target that does not exist lists the ones that do
+    let err: String = compiler::compile("{}$", &CompileOptions::new("x86")).err().unwrap();
+    assert_eq!(err, "No backend named x86; Expected one of 6502, riscv");
+}
+
+// Runs the 6502 image for each program in the source code
+fn run_6502(source_code: &str, max_instructions: usize) -> Vec<EmulatorResult> {
+    let result: CompilationResult = compile(source_code, "6502");
+    return result.programs.iter().map(|program| {
+        let image: Vec<u8> = match program.code.as_ref() {
+            Some(backend::Artifact::Image(image)) => image.to_owned(),
+            _ => panic!("Program {} did not generate an image", program.program_number)
+        };
+        return Emulator6502::new(&image).run(max_instructions);
+    }).collect();
+}
+
+#[test]
+fn code_gen_tests_print_the_expected_output() {
+    let expected: [(&str, Vec<&str>); 5] = [
+        ("Alan's tests", vec!["44", "2alan", "2alanblackstone", "2345"]),
+        ("Boolean hell", vec!["success"]),
+        ("Addition is fun", vec!["3545"]),
+        ("Lots of loops", vec![" inner1 inner2 outer1 inner1 inner2 outer2 inner1 inner2 outer3"]),
+        ("Nested if", vec!["success"])
+    ];
+
+    for (name, outputs) in expected {
+        let results: Vec<EmulatorResult> = run_6502(&get_test(name, "CODEGEN").test_code, emulator_6502::DEFAULT_INSTRUCTION_LIMIT);
+        assert!(results.iter().all(|result| result.is_ok()), "{} should reach BRK", name);
+        assert_eq!(results.iter().map(|result| result.output.as_str()).collect::<Vec<&str>>(), outputs, "{}", name);
+    }
+}
+
+#[test]
+fn emulator_stops_at_the_instruction_limit() {
+    // The while true loop never ends
+    let results: Vec<EmulatorResult> = run_6502(&get_test("If and while optimizations", "CODEGEN").test_code, 1000);
+    assert_eq!(results[0].stop_reason, StopReason::InstructionLimit);
+    assert_eq!(results[0].num_instructions, 1000);
+    assert!(results[0].output.starts_with("hitruetrue"));
+
+    // Memory holds the stored variables when the program ends, where 7 is only there after the loop and addition
+    let results: Vec<EmulatorResult> = run_6502("{ int a while (a != 3) { a = 1 + a } a = 4 + a print(a) }$", emulator_6502::DEFAULT_INSTRUCTION_LIMIT);
+    assert!(results[0].is_ok());
+    assert_eq!(results[0].memory.len(), 0x100);
+    assert!(results[0].memory.contains(&7));
+
+    // Anything outside of the subset stops the program
+    let result: EmulatorResult = Emulator6502::new(&[0xA9, 0x01, 0x02]).run(10);
+    assert_eq!(result.stop_reason, StopReason::InvalidOpcode { opcode: 0x02, address: 0x02 });
+}
+
+#[test]
+fn listing_annotates_the_image() {
+    let result: CompilationResult = compile("{\n  int a\n  while (a != 2) {\n    a = 1 + a\n  }\n  print(\"hi\")\n}$", "6502");
+    let listing: &str = result.programs[0].listing.as_ref().unwrap();
+
+    // Variables are named after their scope, the loop goes back to a label, and strings are shown with their text
+    assert!(listing.contains("0002  8D 2A 00  STA $002A   ; a_0"));
+    assert!(listing.contains("002A  00        a_0"));
+    assert!(listing.contains("; 3:3 while (a != 2)"));
+    assert!(listing.contains("; 4:5 a = 1 + a"));
+    assert!(listing.contains("BNE L0      ; $05"));
+    assert!(listing.contains("LDY #$F1    ; \"hi\""));
+    assert!(listing.contains("00F1  \"hi\""));
+
+    // Only the 6502 has a listing because the RISC-V code is already assembly
+    let result: CompilationResult = compile("{ print(1) }$", "riscv");
+    assert!(result.programs[0].is_ok());
+    assert!(result.programs[0].listing.is_none());
+}
+
+#[test]
+fn constants_are_folded_before_code_gen() {
+    // 29 nines is 261, which wraps around to 5 in 8 bits
+    let source: &str = "{\n  int a\n  a = 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9 + 9\n  print((a == 5))\n  while (a != 7) {\n    a = 1 + 1 + a\n  }\n  print(a)\n}$";
+    let result: CompilationResult = compile(source, "6502");
+    let ir: String = result.programs[0].ir.as_ref().unwrap().create_text();
+    assert!(ir.contains("    a_0 = 5\n"));
+    assert!(ir.contains("    print boolean true\n"));
+
+    // Nothing is known about a inside the loop, but the constants can still be combined
+    assert!(ir.contains("    t0 = a_0 != 7\n"));
+    assert!(ir.contains("    t1 = 2 + a_0\n"));
+    assert_eq!(result.programs[0].ir.as_ref().unwrap().num_temps, 2);
+
+    let results: Vec<EmulatorResult> = run_6502(source, emulator_6502::DEFAULT_INSTRUCTION_LIMIT);
+    assert_eq!(results[0].output, "true7");
+    let results: Vec<emulator_riscv::EmulatorResult> = run_riscv(source, emulator_riscv::DEFAULT_INSTRUCTION_LIMIT);
+    assert_eq!(results[0].output, "true\n007\n");
+}
+
+#[test]
+fn dead_code_is_removed_before_code_gen() {
+    let source: &str = "{\n  int u\n  int a\n  while (a != 3) {\n    a = 1 + a\n  }\n  int b\n  b = a\n  b = 2 + a\n  print(b)\n  while (1 == 2) {\n    print(\"never\")\n  }\n}$";
+    let result: CompilationResult = compile(source, "6502");
+    let ir: String = result.programs[0].ir.as_ref().unwrap().create_text();
+
+    // u is never used, the first store to b is overwritten, and the last loop never runs
+    assert!(!ir.contains("u_0"));
+    assert_eq!(ir.matches("b_0 = ").count(), 1);
+    assert!(!ir.contains("never"));
+
+    // Only the variables that are left get a static slot
+    let listing: &str = result.programs[0].listing.as_ref().unwrap();
+    let statics: &str = &listing[listing.find("; Static variables").unwrap()..listing.find("; Heap").unwrap()];
+    assert_eq!(statics.lines().filter(|line| !line.trim().is_empty()).count(), 3);
+    assert!(!listing.contains("never"));
+
+    let results: Vec<EmulatorResult> = run_6502(source, emulator_6502::DEFAULT_INSTRUCTION_LIMIT);
+    assert_eq!(results[0].output, "5");
+}
+
+#[test]
+fn peephole_removes_redundant_loads() {
+    let source: &str = "{\n  int a\n  while (a != 2) {\n    a = 1 + a\n  }\n  print(a)\n  print(2)\n  boolean b\n  b = (a == 1)\n  print(b)\n  print((a != 3))\n}$";
+    let result: CompilationResult = compile(source, "6502");
+
+    // The optimizer logs how much smaller it made the code
+    let num_bytes_saved: usize = result.logs.iter().find_map(|entry| match entry {
+        LogEntry::Message { msg, .. } => msg.strip_prefix("Peephole optimization saved ")?.strip_suffix(" bytes")?.parse().ok(),
+        _ => None
+    }).expect("The peephole optimization should be logged");
+    assert!(num_bytes_saved > 0);
+
+    // X cannot be loaded with 1 again while it is still 1, which is only unknown after a label that can be branched to
+    let listing: &str = result.programs[0].listing.as_ref().unwrap();
+    let mut x_is_1: bool = false;
+    for line in listing.lines() {
+        if line.ends_with(':') {
+            x_is_1 = false;
+        } else if line.contains("LDX #$01") {
+            assert!(!x_is_1, "X is already 1 at\n{}", line);
+            x_is_1 = true;
+        } else if ["LDX", "INX", "DEX", "TAX", "TSX"].iter().any(|mnemonic| line.contains(&format!(" {} ", mnemonic)) || line.ends_with(mnemonic)) {
+            x_is_1 = false;
+        }
+    }
+
+    // The program still prints the same thing, which also matches the RISC-V code that is not optimized
+    let results: Vec<EmulatorResult> = run_6502(source, emulator_6502::DEFAULT_INSTRUCTION_LIMIT);
+    assert_eq!(results[0].output, "22falsetrue");
+    let differential_result: DifferentialResult = differential::run_source(source).remove(0);
+    assert!(differential_result.is_match(), "{}", differential_result.describe());
+}
+
+// Gets the 6502 image of the first program
+fn get_image(result: &CompilationResult) -> Vec<u8> {
+    match result.programs[0].code.as_ref() {
+        Some(backend::Artifact::Image(image)) => return image.to_owned(),
+        _ => panic!("The program did not generate an image")
+    }
+}
+
+#[test]
+fn memory_layout_moves_the_code_and_variables() {
+    let source: String = get_test("Stack overflow error with a variable", "CODEGEN").test_code;
+
+    // Everything has to fit in 256 bytes by default
+    let result: CompilationResult = compile(&source, "6502");
+    let error: &Diagnostic = result.programs[0].diagnostics.iter().find(|diagnostic| diagnostic.is_error()).unwrap();
+    let notes: &Vec<String> = &error.notes;
+    assert!(notes[0].starts_with("When memory ran out, the code had used "));
+    assert!(notes[1].contains("256 bytes"));
+
+    // Loading the code at 0x100 leaves all of zero page for the heap
+    let layout: MemoryLayout = MemoryLayout {
+        image_size: 0x200,
+        load_address: 0x100,
+        ..Default::default()
+    };
+    let options: CompileOptions = CompileOptions {
+        memory_layout: layout,
+        ..CompileOptions::new("6502")
+    };
+    let result: CompilationResult = compiler::compile(&source, &options).unwrap();
+    assert!(result.is_ok());
+    let image: Vec<u8> = get_image(&result);
+    assert_eq!(image.len(), 0x200);
+    let listing: &str = result.programs[0].listing.as_ref().unwrap();
+    assert!(listing.contains("; Code 0x100-"));
+
+    let emulator_result: EmulatorResult = Emulator6502::new(&image).with_start_address(0x100).run(emulator_6502::DEFAULT_INSTRUCTION_LIMIT);
+    assert!(emulator_result.is_ok(), "{}", emulator_result.stop_reason);
+    assert!(emulator_result.output.starts_with("abcdefghijklmnopqrstuvwxyz"));
+    assert!(emulator_result.output.ends_with("z3"));
+
+    // The static variables can go somewhere else
+    let options: CompileOptions = CompileOptions {
+        memory_layout: MemoryLayout {
+            static_address: Some(0x1F0),
+            ..layout
+        },
+        ..CompileOptions::new("6502")
+    };
+    let moved_result: CompilationResult = compiler::compile(&source, &options).unwrap();
+    let listing: &str = moved_result.programs[0].listing.as_ref().unwrap();
+    assert!(listing.contains("$01F0"));
+    let moved_emulator_result: EmulatorResult = Emulator6502::new(&get_image(&moved_result)).with_start_address(0x100).run(emulator_6502::DEFAULT_INSTRUCTION_LIMIT);
+    assert_eq!(moved_emulator_result.output, emulator_result.output);
+
+    // The static variables cannot be on top of the code
+    let options: CompileOptions = CompileOptions {
+        memory_layout: MemoryLayout {
+            static_address: Some(0x101),
+            ..layout
+        },
+        ..CompileOptions::new("6502")
+    };
+    let overlap_result: CompilationResult = compiler::compile(&source, &options).unwrap();
+    let overlap_error: &Diagnostic = overlap_result.programs[0].diagnostics.iter().find(|diagnostic| diagnostic.is_error()).unwrap();
+    assert_eq!(overlap_error.code, DiagnosticCode::StackOverflow);
+    // The error names what actually overlaps and where the static variables were put
+    assert_eq!(overlap_error.message, "The code ran into the static variables causing a stack overflow error");
+    assert!(overlap_error.notes.iter().any(|note| note.contains("the static variables start at 0x0101,")));
+}
+
+#[test]
+fn static_variables_on_the_0x00_are_not_a_heap_overflow() {
+    let options: CompileOptions = CompileOptions {
+        memory_layout: MemoryLayout::parse("static=0xFF").unwrap(),
+        ..CompileOptions::new("6502")
+    };
+    let result: CompilationResult = compiler::compile("{\n  int a\n  while (a != 3) {\n    a = 1 + a\n    print(a)\n  }\n}$", &options).unwrap();
+    let errors: Vec<&Diagnostic> = result.programs[0].diagnostics.iter().filter(|diagnostic| diagnostic.is_error()).collect();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code, DiagnosticCode::StackOverflow);
+    assert_eq!(errors[0].message, "The static variables ran into the 0x00 at 0x00FF causing a stack overflow error");
+}
+
+#[test]
+fn far_branches_use_jmp() {
+    let prints: String = "    print(a)\n    print(\" \")\n".repeat(12);
+    let source: String = format!("{{\n  int a\n  while (a != 3) {{\n    a = 1 + a\n{}  }}\n  print(\"done\")\n}}$", prints);
+    let expected_output: String = format!("{}done", (1..=3).map(|i| format!("{} ", i).repeat(12)).collect::<String>());
+
+    let options: CompileOptions = CompileOptions {
+        memory_layout: MemoryLayout::parse("size=0x400,load=0x100").unwrap(),
+        ..CompileOptions::new("6502")
+    };
+    let result: CompilationResult = compiler::compile(&source, &options).unwrap();
+    assert!(result.is_ok());
+    let listing: &str = result.programs[0].listing.as_ref().unwrap();
+    assert!(listing.contains("JMP"));
+
+    let emulator_result: EmulatorResult = Emulator6502::new(&get_image(&result)).with_start_address(0x100).run(emulator_6502::DEFAULT_INSTRUCTION_LIMIT);
+    assert!(emulator_result.is_ok(), "{}", emulator_result.stop_reason);
+    assert_eq!(emulator_result.output, expected_output);
+}
+
+#[test]
+fn memory_layouts_are_parsed() {
+    assert_eq!(MemoryLayout::parse("").unwrap(), MemoryLayout::default());
+    assert_eq!(MemoryLayout::parse("size=4096, load=0x200, heap=0xEF, static=0x0F00").unwrap(), MemoryLayout {
+        image_size: 0x1000,
+        load_address: 0x200,
+        heap_start: 0xEF,
+        static_address: Some(0xF00)
+    });
+
+    for layout in ["size=0x10001", "heap=0x100", "load=0x100", "static=0x200,size=0x200", "stack=0x10", "size"] {
+        assert!(MemoryLayout::parse(layout).is_err(), "{} should not be a valid layout", layout);
+    }
+
+    // A layout built by hand is checked before anything is compiled
+    let mut options: CompileOptions = CompileOptions::new("6502");
+    options.memory_layout.load_address = 0x100;
+    let err: String = compiler::compile("{}$", &options).err().unwrap();
+    assert_eq!(err, "Invalid memory layout: The load address 0x0100 is past the end of the 256 byte image");
+}
+
+// Assembles and runs the RISC-V code for each program in the source code
+fn run_riscv(source_code: &str, max_instructions: usize) -> Vec<emulator_riscv::EmulatorResult> {
+    let result: CompilationResult = compile(source_code, "riscv");
+    return result.programs.iter().map(|program| {
+        let assembly: String = program.code.as_ref()
+            .unwrap_or_else(|| panic!("Program {} did not generate code", program.program_number))
+            .to_string();
+        let mut emulator: EmulatorRiscV = EmulatorRiscV::new(&assembly).unwrap_or_else(|error| panic!("{}", error));
+        return emulator.run(max_instructions);
+    }).collect();
+}
+
+#[test]
+fn riscv_programs_run_in_the_emulator() {
+    // Integers are printed with 3 digits and every print ends with a new line
+    let results: Vec<emulator_riscv::EmulatorResult> = run_riscv(&get_test("Alan's tests", "CODEGEN").test_code, emulator_riscv::DEFAULT_INSTRUCTION_LIMIT);
+    let outputs: Vec<&str> = results.iter().map(|result| result.output.as_str()).collect();
+    assert_eq!(outputs, vec!["004\n004\n", "002\nalan\n", "002\nalan\nblackstone\n", "002\n003\n004\n005\n"]);
+    assert!(results.iter().all(|result| result.is_ok()));
+
+    let results: Vec<emulator_riscv::EmulatorResult> = run_riscv(&get_test("Boolean hell", "CODEGEN").test_code, emulator_riscv::DEFAULT_INSTRUCTION_LIMIT);
+    assert_eq!(results[0].output, "success\n");
+
+    // The while true loop never ends
+    let results: Vec<emulator_riscv::EmulatorResult> = run_riscv(&get_test("If and while optimizations", "CODEGEN").test_code, 1000);
+    assert_eq!(results[0].stop_reason, emulator_riscv::StopReason::InstructionLimit);
+    assert!(results[0].output.starts_with("hi\ntrue\ntrue\n"));
+}
+
+#[test]
+fn riscv_assemble_errors_have_line_numbers() {
+    let error: AssembleError = EmulatorRiscV::new(".global _start\n_start:\nli  a0, 1\nj  missing").unwrap_err();
+    assert_eq!(error.line_number, 4);
+    assert_eq!(error.message, "Undefined label 'missing'");
+
+    let error: AssembleError = EmulatorRiscV::new("_start:\nfoo  a0").unwrap_err();
+    assert_eq!(error.message, "Unsupported instruction foo");
+}
+
+#[test]
+fn backends_print_the_same_output() {
+    // Every sample plus programs for the parts of the language that differ the most between the backends
+    let mut sources: Vec<String> = get_tests().into_iter().map(|test| test.test_code).collect();
+    sources.push(String::from("{ boolean b b = (1 == 2) print(b) b = (\"a\" != \"b\") print(b) print((b == true)) print(false) }$"));
+    sources.push(String::from("{ int a a = 5 int b b = 2 + a print((a != b)) print((3 + 4 == b)) print(4 + 5 + a + b) }$"));
+    sources.push(String::from("{ string s s = \"same\" string t t = s if (s == t) { print(\"equal\") } if (s != \"same\") { print(\"not\") } }$"));
+    sources.push(String::from("{ int i i = 0 while (i != 4) { i = 1 + i if ((i == 2) != false) { print(i) } } print(\"done\") }$"));
+
+    let mut mismatches: Vec<String> = Vec::new();
+    let mut num_compared: usize = 0;
+    for source in sources.iter() {
+        let results: Vec<DifferentialResult> = differential::run_source(source);
+        num_compared += results.len();
+        for result in results.iter().filter(|result| !result.is_match()) {
+            mismatches.push(format!("{}\n{}", source, result.describe()));
+        }
+    }
+
+    assert!(num_compared > 20, "Only {} programs compiled for both targets", num_compared);
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n\n"));
+}
+
+#[test]
+fn truncated_differential_results_need_the_other_backend_to_finish() {
+    let result: DifferentialResult = DifferentialResult {
+        program_number: 1,
+        result_6502: EmulatorResult {
+            output: String::from("1 2 3"),
+            memory: Vec::new(),
+            stop_reason: StopReason::InstructionLimit,
+            num_instructions: emulator_6502::DEFAULT_INSTRUCTION_LIMIT
+        },
+        result_riscv: Ok(emulator_riscv::EmulatorResult {
+            output: String::from("1\n \n2\n"),
+            stop_reason: emulator_riscv::StopReason::InvalidAddress { target: 0, address: 0x10000 },
+            num_instructions: 100
+        }),
+        normalized_riscv_output: String::from("1 2")
+    };
+    // RISC-V crashed, so the output being a prefix is not enough
+    assert!(!result.is_match());
+
+    let mut finished_result: DifferentialResult = result.clone();
+    if let Ok(result_riscv) = finished_result.result_riscv.as_mut() {
+        result_riscv.stop_reason = emulator_riscv::StopReason::Exit(0);
+    }
+    assert!(finished_result.is_match());
+
+    let mut failed_result: DifferentialResult = result.clone();
+    failed_result.result_riscv = Err(AssembleError { line_number: 1, message: String::from("Unsupported instruction foo") });
+    assert!(!failed_result.is_match());
+    assert!(failed_result.describe().contains("does not assemble"));
+}
+
+// Compiles the source code with the multi-digit integer extension
+fn compile_multi_digit(source_code: &str, target: &str) -> CompilationResult {
+    let options: CompileOptions = CompileOptions {
+        multi_digit_integers: true,
+        ..CompileOptions::new(target)
+    };
+    return compiler::compile(source_code, &options).unwrap();
+}
+
+#[test]
+fn multi_digit_integers_are_opt_in() {
+    let source: String = get_test("Multi-digit numbers", "PARSE").test_code;
+    assert!(!compile(&source, "6502").is_ok());
+
+    for target in backend::get_backend_names() {
+        let result: CompilationResult = compile_multi_digit(&source, target);
+        assert!(result.is_ok(), "{} should compile for {}", source, target);
+        let tokens: &Vec<Token> = result.programs[0].tokens.as_ref().unwrap();
+        assert!(tokens.iter().any(|token| token.token_type == TokenType::Digit(42) && token.text == "42"));
+    }
+
+    // Nothing can hold a literal that does not fit in 64 bits
+    let result: CompilationResult = compile_multi_digit("{ print(18446744073709551616) }$", "riscv");
+    assert_eq!(codes(&result.programs[0]), vec![DiagnosticCode::IntegerTooLarge]);
+}
+
+#[test]
+fn multi_digit_integers_use_the_width_of_the_target() {
+    let source: &str = "{\n  int a\n  a = 300\n  print(a)\n  int b\n  b = 0\n  while (b != 1000) {\n    b = 250 + b\n  }\n  print(b)\n  print(18446744073709551615 + 2)\n  print((4000 == 4000))\n  print(0)\n}$";
+
+    // Bytes wrap around on the 6502, so 1000 is 232 and the loop stops early
+    let result: CompilationResult = compile_multi_digit(source, "6502");
+    let warnings: Vec<&Diagnostic> = result.programs[0].diagnostics.iter().filter(|diagnostic| diagnostic.code == DiagnosticCode::IntegerOutOfRange).collect();
+    assert_eq!(warnings.len(), 5);
+    assert!(warnings[0].message.contains("[ 300 ] does not fit in 8 bits and becomes 44"));
+    let emulator_result: EmulatorResult = Emulator6502::new(&get_image(&result)).run(emulator_6502::DEFAULT_INSTRUCTION_LIMIT);
+    assert_eq!(emulator_result.output, "442321true0");
+
+    // RISC-V integers are the full 64 bits, so only the largest one wraps around
+    let result: CompilationResult = compile_multi_digit(source, "riscv");
+    assert!(!result.programs[0].diagnostics.iter().any(|diagnostic| diagnostic.code == DiagnosticCode::IntegerOutOfRange));
+    let assembly: String = result.programs[0].code.as_ref().unwrap().to_string();
+    let emulator_result: emulator_riscv::EmulatorResult = EmulatorRiscV::new(&assembly).unwrap().run(emulator_riscv::DEFAULT_INSTRUCTION_LIMIT);
+    assert_eq!(emulator_result.output, "300\n1000\n1\ntrue\n0\n");
+    assert!(riscv_assembler::assemble(&assembly).is_ok());
+
+    // Variables that do not fit in the registers are double words in memory
+    // The loop makes the values unknown, so they are not propagated into the prints
+    let names: Vec<char> = ('a'..='n').collect();
+    let declarations: String = names.iter().enumerate().map(|(i, name)| format!("int {} {} = {}000000000 ", name, name, i + 1)).collect();
+    let prints: String = names.iter().map(|name| format!("print({}) ", name)).collect();
+    // The booleans are still bytes, so they are declared first to be mixed in with the double words
+    let declarations: String = format!("boolean y y = true boolean z z = false {}", declarations);
+    let prints: String = format!("{}print(y) print(z) ", prints);
+    let source: String = format!("{{ {} while (a != 1000000002) {{ a = 1 + a }} {}}}$", declarations, prints);
+    let result: CompilationResult = compile_multi_digit(&source, "riscv");
+    let assembly: String = result.programs[0].code.as_ref().unwrap().to_string();
+    assert!(assembly.contains(".section .data\n.align 3\n"));
+    // The double words are aligned, so they come before anything smaller
+    let statics: Vec<&str> = assembly.lines().skip_while(|line| *line != ".align 3").skip(1).take_while(|line| line.ends_with(" 0")).collect();
+    assert!(statics.first().unwrap().ends_with(": .dword 0"));
+    assert!(statics.windows(2).all(|pair| pair[0].ends_with(".dword 0") || !pair[1].ends_with(".dword 0")));
+    let program: AssembledProgram = riscv_assembler::assemble(&assembly).unwrap();
+    assert_eq!(program.data_address % 8, 0);
+    let emulator_result: emulator_riscv::EmulatorResult = EmulatorRiscV::new(&assembly).unwrap().run(emulator_riscv::DEFAULT_INSTRUCTION_LIMIT);
+    let expected_output: String = (2..=14).fold(String::from("1000000002\n"), |output, i| format!("{}{}000000000\n", output, i)) + "true\nfalse\n";
+    assert_eq!(emulator_result.output, expected_output);
+}
+
+// Checks for the stack frame of the spilled temps, which is the only one kept 16 byte aligned
+fn has_spill_frame(assembly: &str) -> bool {
+    return assembly.lines()
+        .filter_map(|line| line.strip_prefix("addi  sp, sp, -")?.parse::<usize>().ok())
+        .any(|frame_size| frame_size % 16 == 0);
+}
+
+#[test]
+fn riscv_variables_are_kept_in_registers() {
+    // Nothing is spilled when there are no more live values than registers, even if the temps have to share one
+    let source: &str = "{\n  int a\n  while (a != 3) {\n    a = 1 + a\n  }\n  print(a)\n}$";
+    let result: CompilationResult = compile(source, "riscv");
+    let ir: &IrProgram = result.programs[0].ir.as_ref().unwrap();
+    assert!(RegisterAllocator::new(&["s0"], &["t2"]).allocate(ir).spilled.is_empty());
+    assert_eq!(RegisterAllocator::new(&[], &["t2"]).allocate(ir).spilled, vec![Name::Var(String::from("a"), 0)]);
+
+    // The generated code does not need any memory for the variable or the temps, and comparisons are done inline
+    let assembly: String = result.programs[0].code.as_ref().unwrap().to_string();
+    assert!(!assembly.contains("a_0:"));
+    assert!(!has_spill_frame(&assembly));
+    assert!(!assembly.contains("compare_eq"));
+    assert_eq!(run_riscv(source, emulator_riscv::DEFAULT_INSTRUCTION_LIMIT)[0].output, "003\n");
+
+    // There are only 12 registers for the variables and 5 for the temps, so the rest are spilled to memory
+    let source: &str = "{\n  int a\n  int b\n  int c\n  int d\n  int e\n  int f\n  int g\n  int h\n  int i\n  int j\n  int k\n  int l\n  int m\n  int n\n  a = 1\n  b = 2\n  c = 3\n  d = 4\n  e = 5\n  f = 6\n  g = 7\n  h = 8\n  i = 9\n  j = 1\n  k = 2\n  l = 3\n  m = 4\n  n = 5\n  while (a != 2) {\n    a = 1 + a\n  }\n  print(b)\n  print(c)\n  print(d)\n  print(e)\n  print(f)\n  print(g)\n  print(h)\n  print(i)\n  print(j)\n  print(k)\n  print(l)\n  print(m)\n  print(n)\n  print(((a == b) == ((c == d) == ((e == f) == ((g == h) == ((i == j) == (k == l)))))))\n}$";
+    let result: CompilationResult = compile(source, "riscv");
+    let assembly: String = result.programs[0].code.as_ref().unwrap().to_string();
+    let num_spilled_vars: usize = assembly.lines().filter(|line| line.ends_with("_0: .byte 0") && !line.starts_with("print_int_char")).count();
+    assert!(num_spilled_vars >= 2, "Expected the variables that did not fit to be in memory\n{}", assembly);
+    assert!(has_spill_frame(&assembly), "Expected the temps that did not fit to be on the stack\n{}", assembly);
+
+    // Spilling does not change what the program prints
+    let results: Vec<emulator_riscv::EmulatorResult> = run_riscv(source, emulator_riscv::DEFAULT_INSTRUCTION_LIMIT);
+    assert!(results[0].is_ok());
+    assert_eq!(results[0].output, "002\n003\n004\n005\n006\n007\n008\n009\n001\n002\n003\n004\n005\nfalse\n");
+}
+
+#[test]
+fn riscv_instructions_are_encoded() {
+    // The expected words are from llvm-mc
+    let program: AssembledProgram = riscv_assembler::assemble("_start:\nli  a0, 5000\nlwu  a0, 8(t0)\nsd  s1, 24(sp)\nsub  a0, a1, a2\nremu  a3, a4, a5\naddi  sp, sp, -4\nandi  t0, t0, 0xFF\nxor  a0, a1, a2\nseqz  a0, a0\nsnez  a0, a0\nloop:\nblt  t1, t3, loop\necall\nli  a0, 10000000000000000000\nli  a1, 0xFFFFFFFFFFFFFFFF\nli  t2, 4096").unwrap();
+    let words: Vec<u32> = program.text.chunks(4).map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]])).collect();
+    assert_eq!(words, vec![0x00001537, 0x3885051B, 0x0082E503, 0x00913C23, 0x40C58533, 0x02F776B3, 0xFFC10113, 0x0FF2F293, 0x00C5C533, 0x00153513, 0x00A03533, 0x01C34063, 0x00000073, 0xC5639537, 0x00251513, 0x60950513, 0x00C51513, 0x13D50513, 0x01351513, 0xFFF00593, 0x000013B7]);
+    assert_eq!(program.entry, program.text_address);
+}
+
+#[test]
+fn riscv_executables_are_elf_files() {
+    let riscv: Box<dyn backend::Backend> = backend::get_backend("riscv").unwrap();
+    for test in get_tests().iter().filter(|test| test.test_type.to_string() == "CODEGEN") {
+        let result: CompilationResult = compile(&test.test_code, "riscv");
+        for program in result.programs.iter() {
+            let elf: Vec<u8> = riscv.create_executable(program.code.as_ref().unwrap()).unwrap();
+
+            // 64-bit little endian RISC-V executable
+            assert_eq!(&elf[0..6], &[0x7F, b'E', b'L', b'F', 2, 1]);
+            assert_eq!(u16::from_le_bytes([elf[16], elf[17]]), 2);
+            assert_eq!(u16::from_le_bytes([elf[18], elf[19]]), 243);
+
+            // The entry point is the nop at the start of the text, which is right after the headers
+            let entry: u64 = u64::from_le_bytes(elf[24..32].try_into().unwrap());
+            let entry_offset: usize = (entry - 0x10000) as usize;
+            assert_eq!(&elf[entry_offset..entry_offset + 4], &[0x13, 0x00, 0x00, 0x00], "{}", test.test_name);
+        }
+    }
+
+    // The 6502 image is already an executable
+    let image: backend::Artifact = backend::Artifact::Image(vec![0xA9, 0x01, 0x00]);
+    assert_eq!(backend::get_backend("6502").unwrap().create_executable(&image), Ok(vec![0xA9, 0x01, 0x00]));
+}
+
+#[test]
+fn json_output_has_every_phase() {
+    let doc: serde_json::Value = serde_json::from_str(&nexus_compiler::nexus_compile_json("{ int a a = 1 print(a) }$", "6502", false)).unwrap();
+    assert_eq!(doc["target"], "6502");
+    assert_eq!(doc["success"], true);
+    assert!(doc["logs"].as_array().unwrap().iter().all(|log| log["type"].is_string() && log["source"].is_string() && log["message"].is_string()));
+
+    let programs: &Vec<serde_json::Value> = doc["programs"].as_array().unwrap();
+    assert_eq!(programs.len(), 1);
+    let program: &serde_json::Value = &programs[0];
+    let mut keys: Vec<&str> = program.as_object().unwrap().keys().map(|key| key.as_str()).collect();
+    keys.sort();
+    assert_eq!(keys, vec!["ast", "code", "cst", "diagnostics", "ir", "listing", "program_number", "success", "symbol_table", "tokens"]);
+    assert_eq!(program["program_number"], 1);
+    assert_eq!(program["success"], true);
+
+    // Every phase has its output
+    assert_eq!(program["tokens"][0]["kind"], "Symbol");
+    assert_eq!(program["tokens"][0]["text"], "{");
+    assert_eq!(program["tokens"][0]["span"], serde_json::json!({ "start": 0, "end": 1, "line": 1, "col": 1 }));
+    for (tree, root) in [("cst", "Program"), ("ast", "Block")] {
+        assert!(program[tree]["dot"].is_string() && program[tree]["text"].is_string(), "{} is missing its text", tree);
+        assert_eq!(program[tree]["root"]["label"], root);
+    }
+    assert_eq!(program["symbol_table"]["scopes"][0]["symbols"][0]["id"], "a");
+    assert!(program["ir"].is_string());
+    assert_eq!(program["diagnostics"], serde_json::json!([]));
+    assert_eq!(program["code"]["image"].as_array().unwrap().len(), 256);
+    assert!(program["code"]["text"].is_string());
+    assert!(program["listing"].is_string());
+
+    // A semantic error stops before the IR and code generation
+    let doc: serde_json::Value = serde_json::from_str(&nexus_compiler::nexus_compile_json("{ print(b) }$", "riscv", false)).unwrap();
+    assert_eq!(doc["success"], false);
+    let program: &serde_json::Value = &doc["programs"][0];
+    assert_eq!(program["success"], false);
+    assert!(program["tokens"].is_array() && program["cst"].is_object() && program["ast"].is_object());
+    assert!(program["ir"].is_null() && program["code"].is_null() && program["listing"].is_null());
+    assert_eq!(program["diagnostics"], serde_json::json!([{
+        "severity": "ERROR",
+        "phase": "SEMANTICANALYZER",
+        "code": "S001",
+        "message": "Id [ b ] has not been declared",
+        "span": { "start": 8, "end": 9, "line": 1, "col": 9 },
+        "labels": [],
+        "notes": []
+    }]));
+
+    // The compiler is not run at all for an unknown target
+    let doc: serde_json::Value = serde_json::from_str(&nexus_compiler::nexus_compile_json("{}$", "x86", false)).unwrap();
+    assert_eq!(doc, serde_json::json!({ "success": false, "error": "No backend named x86; Expected one of 6502, riscv", "programs": [] }));
+}
+
+#[test]
+fn collector_sink_is_replaced_and_restored() {
+    // The outer collector stands in for whatever sink was there before
+    let outer: CollectorSink = CollectorSink::new();
+    let default_sink: Box<dyn DiagnosticSink> = nexus_log::set_sink(Box::new(outer.clone()));
+
+    let inner: CollectorSink = CollectorSink::new();
+    let prev_sink: Box<dyn DiagnosticSink> = nexus_log::set_sink(Box::new(inner.clone()));
+    nexus_log::log(LogTypes::Info, LogSources::Lexer, String::from("Lexing program 1"));
+    nexus_log::insert_empty_line();
+    let diagnostic: Diagnostic = Diagnostic::new(DiagnosticCode::UndeclaredIdentifier, String::from("Id [ b ] has not been declared"), None);
+    nexus_log::report(&diagnostic);
+    nexus_log::set_sink(prev_sink);
+    nexus_log::log(LogTypes::Info, LogSources::Nexus, String::from("Back to the outer sink"));
+
+    assert_eq!(inner.entries(), vec![
+        LogEntry::Message { log_type: LogTypes::Info, src: LogSources::Lexer, msg: String::from("Lexing program 1") },
+        LogEntry::EmptyLine,
+        LogEntry::Diagnostic(diagnostic.clone())
+    ]);
+    assert_eq!(inner.entries_of_type(LogTypes::Error).len(), 1);
+    assert_eq!(inner.diagnostics(), vec![diagnostic]);
+    assert_eq!(outer.entries(), vec![LogEntry::Message { log_type: LogTypes::Info, src: LogSources::Nexus, msg: String::from("Back to the outer sink") }]);
+
+    // Compiling collects its own logs and puts the outer sink back, even when the options are invalid
+    let result: CompilationResult = compile("{ print(b) }$", "6502");
+    assert!(!result.logs.is_empty());
+    assert!(compiler::compile("{}$", &CompileOptions::new("x86")).is_err());
+    nexus_log::log(LogTypes::Info, LogSources::Nexus, String::from("Still the outer sink"));
+    assert_eq!(outer.entries().len(), 2);
+    assert!(outer.diagnostics().is_empty());
+
+    nexus_log::set_sink(default_sink);
+}
@@ -0,0 +1,73 @@
+use std::io::Write;
+use std::process::{Child, Command, Output, Stdio};
+
+// Runs nexusc with the source code on stdin
+fn run_nexusc(source_code: &str, args: &[&str]) -> Output {
+    let mut child: Child = Command::new(env!("CARGO_BIN_EXE_nexusc"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Should be able to run nexusc");
+    child.stdin.take().unwrap().write_all(source_code.as_bytes()).unwrap();
+    return child.wait_with_output().unwrap();
+}
+
+#[test]
+fn successful_compiles_exit_with_0() {
+    let output: Output = run_nexusc("{ print(\"hi\") }$", &[]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(!output.stdout.is_empty());
+
+    // Only the warnings and errors are logged without --verbose
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+
+    let output: Output = run_nexusc("{ print(\"hi\") }$", &["--target", "riscv", "--verbose"]);
+    assert_eq!(output.status.code(), Some(0));
+    let stderr: String = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("[INFO - NEXUS]: Compiling program 1"));
+    assert!(stderr.contains("[DEBUG - LEXER]"));
+}
+
+#[test]
+fn compile_errors_exit_with_1() {
+    let output: Output = run_nexusc("{ print(b) }$", &[]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr: String = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Id [ b ] has not been declared"));
+    assert!(!stderr.contains("[INFO"));
+
+    // Every program has to compile
+    let output: Output = run_nexusc("{ print(1) }$\n{ print(b) }$", &["--target", "riscv"]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn invalid_arguments_exit_with_2() {
+    let output: Output = run_nexusc("{}$", &["--target", "x86"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn diagnostics_are_printed_once_with_their_program() {
+    let output: Output = run_nexusc("{ int a a = \"x\" }$\n{ print(b) }$", &["--verbose"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr: String = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.matches("Expected Int for the assignment type, but received String").count(), 1);
+    assert_eq!(stderr.matches("Id [ b ] has not been declared").count(), 1);
+
+    // Each error is shown before the next program starts compiling
+    let mismatch_index: usize = stderr.find("error[S003]").unwrap();
+    let program_2_index: usize = stderr.find("Compiling program 2").unwrap();
+    let undeclared_index: usize = stderr.find("error[S001]").unwrap();
+    assert!(mismatch_index < program_2_index && program_2_index < undeclared_index);
+}
+
+#[test]
+fn memory_overflows_are_reported_once() {
+    let output: Output = run_nexusc("{ string s s = \"hello\" print(s) }$", &["--memory", "heap=0"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr: String = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.matches("error[C002]").count(), 1);
+}